@@ -0,0 +1,63 @@
+//! ASCII armor for sending documents by email or chat.
+//!
+//! An armored document is the encrypted file, Base64 encoded between BEGIN
+//! and END lines. Mail clients and chat apps rewrap long lines and add
+//! quoting, so everything outside the markers, and any whitespace or `>`
+//! quoting inside them, is ignored when reading it back.
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+
+use crate::crypto::CryptoError;
+
+/// The extension of an exported armored document.
+pub const EXTENSION: &str = "asc";
+
+const BEGIN: &str = "-----BEGIN CRYPTODOC DOCUMENT-----";
+const END: &str = "-----END CRYPTODOC DOCUMENT-----";
+
+// The same width as OpenPGP armor, which no mail client wraps.
+const LINE_WIDTH: usize = 64;
+
+/// Whether `contents` contains an armored document.
+pub fn is_armored(contents: &str) -> bool {
+    contents.contains(BEGIN)
+}
+
+/// Armors the contents of an encrypted document.
+pub fn armor(encrypted: &str) -> String {
+    let encoded = STANDARD.encode(encrypted.trim_end());
+
+    let mut armored = format!("{}\n", BEGIN);
+
+    // Base64 is ASCII, so splitting on bytes can't split a character.
+    for line in encoded.as_bytes().chunks(LINE_WIDTH) {
+        armored.push_str(std::str::from_utf8(line).expect("Base64 is ASCII"));
+        armored.push('\n');
+    }
+
+    armored.push_str(END);
+    armored.push('\n');
+
+    armored
+}
+
+/// Recovers the encrypted document from armored text, such as a whole
+/// email it was pasted into.
+pub fn dearmor(contents: &str) -> Result<String, CryptoError> {
+    let (_, rest) = contents.split_once(BEGIN).ok_or(CryptoError::BadFormat)?;
+    let (body, _) = rest.split_once(END).ok_or(CryptoError::CorruptData)?;
+
+    let encoded: String = body
+        .lines()
+        .map(|line| line.trim_start_matches(|c: char| c == '>' || c.is_whitespace()))
+        .flat_map(str::chars)
+        .filter(|c| !c.is_whitespace())
+        .collect();
+
+    let decoded = STANDARD
+        .decode(encoded)
+        .map_err(|_| CryptoError::CorruptData)?;
+
+    String::from_utf8(decoded).map_err(|_| CryptoError::CorruptData)
+}
@@ -0,0 +1,214 @@
+//! An encrypted, append-only log of security-relevant events, like documents
+//! being opened or unlocking failing, to notice if someone has been trying
+//! to get in.
+//!
+//! Events have to be written without a password, since a failed unlock is
+//! exactly when there isn't one. So the log has an X25519 key pair: each
+//! entry is encrypted to the public key with a key pair of its own, and only
+//! the private key, kept encrypted with the log's password in the first
+//! lines of the file, can read them back.
+//!
+//! ```text
+//! cryptodoc-audit v1
+//! <public key>
+//! <private key, encrypted with the log's password>
+//! <ephemeral public key>/<iv>/<entry>/<mac>
+//! ...
+//! ```
+
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use crypto::curve25519::{curve25519, curve25519_base};
+use crypto::digest::Digest;
+use crypto::sha2::Sha256;
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroize;
+
+use crate::crypto::{decrypt, encrypt, open, seal, CryptoError};
+
+const HEADER: &str = "cryptodoc-audit v1";
+
+const KEY_SIZE: usize = 32;
+const IV_SIZE: usize = 12;
+const MAC_SIZE: usize = 16;
+
+/// Where the app keeps the log, next to its config.
+pub const DEFAULT_PATH: &str = "./audit.log";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Event {
+    Opened { document: String },
+    FailedUnlock { document: String },
+    PasswordChanged { document: String },
+    Exported { document: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Entry {
+    pub time: DateTime<Utc>,
+    #[serde(flatten)]
+    pub event: Event,
+}
+
+#[derive(Debug, Clone)]
+pub struct AuditLog {
+    path: PathBuf,
+}
+
+impl AuditLog {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Whether the log has been turned on.
+    pub fn exists(&self) -> bool {
+        self.path.exists()
+    }
+
+    /// Starts a new log readable with `password`, replacing any old one.
+    pub fn create(&self, password: &str) -> io::Result<()> {
+        let mut private_key: [u8; 32] = rand::random();
+        let public_key = curve25519_base(&private_key);
+        let encrypted_key = encrypt(&private_key, password);
+        private_key.zeroize();
+
+        fs::write(
+            &self.path,
+            format!(
+                "{}\n{}\n{}\n",
+                HEADER,
+                hex::encode(public_key),
+                encrypted_key
+            ),
+        )
+    }
+
+    /// Adds `event` to the end of the log. Does nothing if the log isn't
+    /// turned on.
+    pub fn append(&self, event: Event) -> io::Result<()> {
+        let contents = match fs::read_to_string(&self.path) {
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(()),
+            contents => contents?,
+        };
+
+        let public_key = contents
+            .lines()
+            .nth(1)
+            .and_then(|key| hex::decode(key).ok())
+            .filter(|key| key.len() == KEY_SIZE)
+            .ok_or(io::ErrorKind::InvalidData)?;
+
+        let entry = Entry {
+            time: Utc::now(),
+            event,
+        };
+        let plaintext = serde_json::to_vec(&entry).expect("Failed to serialize audit entry");
+
+        let mut ephemeral_key: [u8; 32] = rand::random();
+        let ephemeral_public_key = curve25519_base(&ephemeral_key);
+        let key = shared_key(
+            &ephemeral_key,
+            &public_key,
+            &ephemeral_public_key,
+            &public_key,
+        );
+        ephemeral_key.zeroize();
+
+        let iv: [u8; IV_SIZE] = rand::random();
+        let (data, mac) = seal(&key, &iv, &plaintext);
+
+        let mut file = OpenOptions::new().append(true).open(&self.path)?;
+        writeln!(
+            file,
+            "{}/{}/{}/{}",
+            hex::encode(ephemeral_public_key),
+            hex::encode(iv),
+            hex::encode(data),
+            hex::encode(mac)
+        )
+    }
+
+    /// Every entry in the log, oldest first. Entries that have been damaged
+    /// are left out.
+    pub fn read(&self, password: &str) -> Result<Vec<Entry>, CryptoError> {
+        let contents = fs::read_to_string(&self.path)?;
+        let mut lines = contents.lines();
+
+        let (Some(HEADER), Some(public_key), Some(encrypted_key)) =
+            (lines.next(), lines.next(), lines.next())
+        else {
+            return Err(CryptoError::BadFormat);
+        };
+
+        let public_key = hex::decode(public_key).map_err(|_| CryptoError::CorruptData)?;
+        let mut private_key = decrypt(encrypted_key, password)?;
+
+        if private_key.len() != KEY_SIZE {
+            private_key.zeroize();
+            return Err(CryptoError::CorruptData);
+        }
+
+        let entries = lines
+            .filter_map(|line| read_entry(line, &private_key, &public_key))
+            .collect();
+
+        private_key.zeroize();
+
+        Ok(entries)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+fn read_entry(line: &str, private_key: &[u8], public_key: &[u8]) -> Option<Entry> {
+    let parts = line
+        .split('/')
+        .map(hex::decode)
+        .collect::<Result<Vec<_>, _>>()
+        .ok()?;
+    let [ephemeral_public_key, iv, data, mac]: [Vec<u8>; 4] = parts.try_into().ok()?;
+
+    if ephemeral_public_key.len() != KEY_SIZE || iv.len() != IV_SIZE || mac.len() != MAC_SIZE {
+        return None;
+    }
+
+    let key = shared_key(
+        private_key,
+        &ephemeral_public_key,
+        &ephemeral_public_key,
+        public_key,
+    );
+
+    match open(&key, &iv, &data, &mac) {
+        (true, plaintext) => serde_json::from_slice(&plaintext).ok(),
+        (false, _) => None,
+    }
+}
+
+/// The AES key for one entry: the X25519 secret hashed together with both
+/// public keys, as they're what the secret came from.
+fn shared_key(
+    private_key: &[u8],
+    their_public_key: &[u8],
+    ephemeral_public_key: &[u8],
+    public_key: &[u8],
+) -> [u8; 32] {
+    let mut secret = curve25519(private_key, their_public_key);
+
+    let mut hasher = Sha256::new();
+    hasher.input(&secret);
+    hasher.input(ephemeral_public_key);
+    hasher.input(public_key);
+    secret.zeroize();
+
+    let mut key = [0; 32];
+    hasher.result(&mut key);
+
+    key
+}
@@ -0,0 +1,106 @@
+//! Measures how fast this machine derives keys and encrypts documents.
+//!
+//! The numbers include everything a real save or open does, hex encoding
+//! included, so they show what a user would actually wait for.
+
+use std::time::{Duration, Instant};
+
+use rand::RngCore;
+use serde::Serialize;
+
+use crate::crypto::{decrypt, derive_key, encrypt, KDF_ITERATIONS};
+
+/// The plaintext sizes measured, from a short note to a large document.
+pub const SIZES: [usize; 4] = [4 << 10, 64 << 10, 1 << 20, 16 << 20];
+
+const PASSWORD: &str = "correct horse battery staple";
+
+// Each measurement repeats until it has taken at least this long, so small
+// sizes aren't lost in timer noise.
+const MIN_DURATION: Duration = Duration::from_millis(200);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Throughput {
+    /// The plaintext size in bytes.
+    pub size: usize,
+    /// Encryption speed in megabytes of plaintext per second.
+    pub encrypt: f64,
+    /// Decryption speed in megabytes of plaintext per second.
+    pub decrypt: f64,
+    /// Whether decrypting gave back the original plaintext.
+    pub verified: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Results {
+    /// How long deriving a key from a password takes, in milliseconds.
+    pub key_derivation: f64,
+    pub throughput: Vec<Throughput>,
+}
+
+impl Results {
+    pub fn all_verified(&self) -> bool {
+        self.throughput.iter().all(|throughput| throughput.verified)
+    }
+}
+
+/// Runs every measurement. This takes a few seconds, so call it from a
+/// blocking thread in async code.
+pub fn run() -> Results {
+    Results {
+        key_derivation: key_derivation(),
+        throughput: SIZES.into_iter().map(throughput).collect(),
+    }
+}
+
+fn key_derivation() -> f64 {
+    let (rounds, elapsed) = repeat(|| {
+        std::hint::black_box(derive_key(
+            std::hint::black_box(PASSWORD),
+            &[0; 16],
+            KDF_ITERATIONS,
+        ));
+    });
+
+    elapsed.as_secs_f64() * 1000.0 / rounds as f64
+}
+
+fn throughput(size: usize) -> Throughput {
+    let mut plaintext = vec![0; size];
+    rand::thread_rng().fill_bytes(&mut plaintext);
+
+    let mut encrypted = String::new();
+    let (encrypt_rounds, encrypt_elapsed) = repeat(|| {
+        encrypted = encrypt(&plaintext, PASSWORD);
+    });
+
+    let mut decrypted = Ok(vec![]);
+    let (decrypt_rounds, decrypt_elapsed) = repeat(|| {
+        decrypted = decrypt(&encrypted, PASSWORD);
+    });
+
+    Throughput {
+        size,
+        encrypt: megabytes_per_second(size, encrypt_rounds, encrypt_elapsed),
+        decrypt: megabytes_per_second(size, decrypt_rounds, decrypt_elapsed),
+        verified: decrypted.is_ok_and(|decrypted| decrypted == plaintext),
+    }
+}
+
+/// Calls `f` until [`MIN_DURATION`] has passed, and returns how many times
+/// it ran and how long that took.
+fn repeat(mut f: impl FnMut()) -> (u32, Duration) {
+    let start = Instant::now();
+    let mut rounds = 0;
+
+    while rounds == 0 || start.elapsed() < MIN_DURATION {
+        f();
+        rounds += 1;
+    }
+
+    (rounds, start.elapsed())
+}
+
+fn megabytes_per_second(size: usize, rounds: u32, elapsed: Duration) -> f64 {
+    (size as f64 * rounds as f64) / 1_000_000.0 / elapsed.as_secs_f64()
+}
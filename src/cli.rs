@@ -0,0 +1,1477 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::io::{self, IsTerminal, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use clap::builder::PossibleValuesParser;
+use clap::{Args, Parser, Subcommand};
+use cryptodoc::armor;
+use cryptodoc::audit::{AuditLog, Event, DEFAULT_PATH};
+use cryptodoc::bench;
+use cryptodoc::crypto::{decrypt, encrypt, inspect, CryptoError, FormatInfo};
+use cryptodoc::file::{self, format_size, DOCUMENT_EXTENSION};
+use cryptodoc::formats::{
+    self, archive_exporter, archive_importer, exporter, importer, FormatError, Importer,
+    NamedDocument, PlainText, ARCHIVE_EXPORTERS, ARCHIVE_IMPORTERS, EXPORTERS, IMPORTERS,
+};
+use cryptodoc::history;
+use cryptodoc::index::{index_path, VaultIndex};
+use cryptodoc::migrate::{backup_if_outdated, migrate_document};
+use cryptodoc::mirror::{self, Direction};
+#[cfg(feature = "openpgp")]
+use cryptodoc::openpgp;
+use cryptodoc::selftest::{self, Check};
+use cryptodoc::{Container, Vault};
+use rayon::prelude::*;
+use serde::Serialize;
+
+const PASSWORD_VARIABLE: &str = "CRYPTODOC_PASSWORD";
+const NEW_PASSWORD_VARIABLE: &str = "CRYPTODOC_NEW_PASSWORD";
+const ARCHIVE_PASSWORD_VARIABLE: &str = "CRYPTODOC_ARCHIVE_PASSWORD";
+/// Stands for standard input or output in place of a path.
+const STDIO: &str = "-";
+
+#[derive(Debug, Parser)]
+#[command(version, about, args_conflicts_with_subcommands = true)]
+#[cfg_attr(not(feature = "gui"), command(subcommand_required = true))]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Print the result of a command as JSON instead of plain messages.
+    #[arg(long, global = true)]
+    pub json: bool,
+
+    /// A document to open in the editor.
+    #[cfg(feature = "gui")]
+    pub path: Option<PathBuf>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Encrypt a plaintext file, or any other data, into a document.
+    Encrypt {
+        #[command(flatten)]
+        files: Files,
+
+        /// Convert the input from this format to text first, rather than
+        /// encrypting it as it is.
+        #[arg(long, value_name = "FORMAT", value_parser = importer_ids())]
+        from: Option<String>,
+
+        /// Write a passphrase-encrypted OpenPGP message that `gpg --decrypt`
+        /// can read, instead of a CryptoDoc document.
+        #[cfg(feature = "openpgp")]
+        #[arg(long)]
+        openpgp: bool,
+
+        /// Write the document as Base64 text between BEGIN and END lines, to
+        /// paste into an email or chat. OpenPGP output is always armored.
+        #[arg(long)]
+        armor: bool,
+
+        #[command(flatten)]
+        options: Options,
+    },
+    /// Decrypt a document into a plaintext file.
+    Decrypt {
+        #[command(flatten)]
+        files: Files,
+
+        /// Convert the plaintext to this format.
+        #[arg(long, value_name = "FORMAT", value_parser = exporter_ids())]
+        to: Option<String>,
+
+        #[command(flatten)]
+        options: Options,
+    },
+    /// Decrypt a document and print the plaintext, without writing it to
+    /// disk.
+    Cat {
+        /// The document to print, or - for standard input.
+        input: PathBuf,
+
+        /// Convert the plaintext to this format.
+        #[arg(long, value_name = "FORMAT", value_parser = exporter_ids())]
+        to: Option<String>,
+
+        #[command(flatten)]
+        options: Options,
+    },
+    /// Change the password of a document, or of every document in a folder.
+    /// Warns about documents that had the new password before.
+    Rekey {
+        path: PathBuf,
+
+        /// Read the current password from the first line of this file instead
+        /// of the CRYPTODOC_PASSWORD variable or the terminal.
+        #[arg(long, value_name = "FILE")]
+        old_password_file: Option<PathBuf>,
+
+        /// Read the new password from the first line of this file instead of
+        /// the CRYPTODOC_NEW_PASSWORD variable or the terminal.
+        #[arg(long, value_name = "FILE")]
+        new_password_file: Option<PathBuf>,
+    },
+    /// Rewrite a document, or every document in a folder, in the current
+    /// format. The old files are kept next to them as backups.
+    Migrate {
+        path: PathBuf,
+
+        #[command(flatten)]
+        options: Options,
+    },
+    /// Turn every entry of another app's archive, like a KeePass database,
+    /// into a document.
+    Import {
+        archive: PathBuf,
+
+        #[arg(long, value_name = "FORMAT", value_parser = archive_importer_ids())]
+        from: String,
+
+        /// The folder to put the documents in. Existing documents are never
+        /// replaced.
+        #[arg(long, value_name = "FOLDER")]
+        into: PathBuf,
+
+        /// Read the archive's password from the first line of this file
+        /// instead of the CRYPTODOC_ARCHIVE_PASSWORD variable or the terminal.
+        #[arg(long, value_name = "FILE")]
+        archive_password_file: Option<PathBuf>,
+
+        #[command(flatten)]
+        options: Options,
+    },
+    /// Decrypt every document in a folder into one archive another app can
+    /// import, like a Standard Notes backup.
+    Export {
+        folder: PathBuf,
+
+        #[arg(long, value_name = "FORMAT", value_parser = archive_exporter_ids())]
+        to: String,
+
+        /// Where to write the archive.
+        #[arg(short, long)]
+        output: PathBuf,
+
+        /// Overwrite the archive if it already exists.
+        #[arg(short, long)]
+        force: bool,
+
+        #[command(flatten)]
+        options: Options,
+    },
+    /// Encrypt every file in a folder of notes, such as an Obsidian vault,
+    /// into a mirror of it that's safe to sync. Only files that changed since
+    /// the last run are encrypted again.
+    Mirror {
+        notes: PathBuf,
+
+        mirror: PathBuf,
+
+        /// Decrypt the mirror back into the notes folder instead.
+        #[arg(long)]
+        decrypt: bool,
+
+        /// Overwrite files that were changed since they were last mirrored.
+        #[arg(short, long)]
+        force: bool,
+
+        #[command(flatten)]
+        options: Options,
+    },
+    /// Watch a folder and encrypt every file placed in it, then shred the
+    /// original. Runs until interrupted.
+    Watch {
+        folder: PathBuf,
+
+        /// Put the encrypted documents here instead of in the watched folder.
+        #[arg(long, value_name = "FOLDER")]
+        output: Option<PathBuf>,
+
+        /// How often to look for new files, in seconds.
+        #[arg(long, value_name = "SECONDS", default_value_t = 2)]
+        interval: u64,
+
+        #[command(flatten)]
+        options: Options,
+    },
+    /// Check that encryption works correctly on this machine.
+    Selftest,
+    /// Measure how fast this machine derives keys, encrypts and decrypts.
+    Bench,
+}
+
+fn exporter_ids() -> PossibleValuesParser {
+    PossibleValuesParser::new(EXPORTERS.iter().map(|exporter| exporter.id()))
+}
+
+fn importer_ids() -> PossibleValuesParser {
+    PossibleValuesParser::new(IMPORTERS.iter().map(|importer| importer.id()))
+}
+
+fn archive_importer_ids() -> PossibleValuesParser {
+    PossibleValuesParser::new(ARCHIVE_IMPORTERS.iter().map(|importer| importer.id()))
+}
+
+fn archive_exporter_ids() -> PossibleValuesParser {
+    PossibleValuesParser::new(ARCHIVE_EXPORTERS.iter().map(|exporter| exporter.id()))
+}
+
+#[derive(Debug, Args)]
+pub struct Files {
+    /// The file to read, or - for standard input.
+    #[arg(required_unless_present = "stdin")]
+    input: Option<PathBuf>,
+
+    /// Read from standard input.
+    #[arg(long, conflicts_with = "input")]
+    stdin: bool,
+
+    /// Where to write the result, or - for standard output. Defaults to the
+    /// input with its extension changed, or standard output when reading
+    /// from standard input.
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+
+    /// Write to standard output.
+    #[arg(long, conflicts_with = "output")]
+    stdout: bool,
+
+    /// Overwrite the output file if it already exists.
+    #[arg(short, long)]
+    force: bool,
+}
+
+impl Files {
+    /// The file to read, or `None` for standard input.
+    fn source(&self) -> Option<&Path> {
+        if self.stdin {
+            return None;
+        }
+
+        self.input
+            .as_deref()
+            .filter(|path| *path != Path::new(STDIO))
+    }
+
+    /// The file to write, or `None` for standard output.
+    fn sink(&self, extension: &str) -> Option<PathBuf> {
+        if self.stdout {
+            return None;
+        }
+
+        match &self.output {
+            Some(path) if path == Path::new(STDIO) => None,
+            Some(path) => Some(path.clone()),
+            None => self.source().map(|path| path.with_extension(extension)),
+        }
+    }
+
+    fn writes_stdout(&self) -> bool {
+        self.sink(DOCUMENT_EXTENSION).is_none()
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct Options {
+    /// Read the password from the first line of this file instead of the
+    /// CRYPTODOC_PASSWORD variable or the terminal.
+    #[arg(long, value_name = "FILE")]
+    password_file: Option<PathBuf>,
+}
+
+#[derive(Debug)]
+enum CliError {
+    Io(PathBuf, io::Error),
+    Exists(PathBuf),
+    Prompt(io::Error),
+    Stdin(io::Error),
+    Stdout(io::Error),
+    PasswordMismatch,
+    EmptyPassword,
+    Decrypt(PathBuf, CryptoError),
+    Unverified(PathBuf),
+    Conflict(PathBuf),
+    Format(FormatError),
+    Rekey { failed: usize, total: usize },
+    Migrate { failed: usize, total: usize },
+    Import { failed: usize, total: usize },
+    Export { failed: usize, total: usize },
+    Mirror { failed: usize, total: usize },
+    SelfTest { failed: usize },
+    Bench { failed: usize },
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CliError::Io(path, error) => write!(f, "{}: {}", path.display(), error),
+            CliError::Exists(path) => write!(
+                f,
+                "{} already exists (use --force to overwrite it)",
+                path.display()
+            ),
+            CliError::Prompt(error) => write!(f, "couldn't read the password: {}", error),
+            CliError::Stdin(error) => write!(f, "couldn't read standard input: {}", error),
+            CliError::Stdout(error) => write!(f, "couldn't write standard output: {}", error),
+            CliError::PasswordMismatch => write!(f, "the passwords don't match"),
+            CliError::EmptyPassword => write!(f, "the password is empty"),
+            CliError::Decrypt(path, error) => write!(f, "{}: {}", path.display(), error),
+            CliError::Unverified(path) => write!(
+                f,
+                "{}: the encrypted copy didn't decrypt back to it, so it was left as it is",
+                path.display()
+            ),
+            CliError::Conflict(path) => write!(
+                f,
+                "{} was changed since it was last mirrored (use --force to overwrite it)",
+                path.display()
+            ),
+            CliError::Format(error) => write!(f, "couldn't convert the document: {}", error),
+            CliError::Rekey { failed, total } => {
+                write!(f, "{} of {} documents couldn't be rekeyed", failed, total)
+            }
+            CliError::Migrate { failed, total } => {
+                write!(f, "{} of {} documents couldn't be migrated", failed, total)
+            }
+            CliError::Import { failed, total } => {
+                write!(f, "{} of {} documents couldn't be imported", failed, total)
+            }
+            CliError::Export { failed, total } => write!(
+                f,
+                "{} of {} documents couldn't be decrypted and were left out of the archive",
+                failed, total
+            ),
+            CliError::Mirror { failed, total } => {
+                write!(f, "{} of {} files couldn't be mirrored", failed, total)
+            }
+            CliError::SelfTest { failed } => write!(
+                f,
+                "{} self-test checks failed; don't trust this build with documents",
+                failed
+            ),
+            CliError::Bench { failed } => write!(
+                f,
+                "decrypting gave back the wrong data at {} sizes; don't trust this build with documents",
+                failed
+            ),
+        }
+    }
+}
+
+impl CliError {
+    /// A stable name for the error, for scripts reading `--json` output.
+    fn kind(&self) -> &'static str {
+        match self {
+            CliError::Io(..) => "io",
+            CliError::Exists(_) => "exists",
+            CliError::Prompt(_) => "prompt",
+            CliError::Stdin(_) => "stdin",
+            CliError::Stdout(_) => "stdout",
+            CliError::PasswordMismatch => "password_mismatch",
+            CliError::EmptyPassword => "empty_password",
+            CliError::Decrypt(_, error) => match error {
+                CryptoError::BadFormat => "bad_format",
+                CryptoError::WrongPassword => "wrong_password",
+                CryptoError::CorruptData => "corrupt",
+                CryptoError::UnsupportedVersion(_) => "unsupported_version",
+                CryptoError::Io(_) => "io",
+            },
+            CliError::Unverified(_) => "unverified",
+            CliError::Conflict(_) => "conflict",
+            CliError::Format(_) => "format",
+            CliError::Rekey { .. } => "rekey",
+            CliError::Migrate { .. } => "migrate",
+            CliError::Import { .. } => "import",
+            CliError::Export { .. } => "export",
+            CliError::Mirror { .. } => "mirror",
+            CliError::SelfTest { .. } => "selftest",
+            CliError::Bench { .. } => "bench",
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct ErrorReport {
+    kind: &'static str,
+    message: String,
+}
+
+impl From<&CliError> for ErrorReport {
+    fn from(error: &CliError) -> Self {
+        Self {
+            kind: error.kind(),
+            message: error.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct DocumentReport {
+    path: PathBuf,
+    /// Where the document was written, if not over `path`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    output: Option<PathBuf>,
+    /// The copy of the document from before it was rewritten in a newer
+    /// format.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    backup: Option<PathBuf>,
+    /// Whether the document was rekeyed to a password it had before.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    reused_password: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<ErrorReport>,
+}
+
+/// What a command did, printed with `--json`. Paths are `None` for
+/// standard input and output.
+#[derive(Debug, Serialize)]
+struct Report {
+    command: &'static str,
+    ok: bool,
+    input: Option<PathBuf>,
+    output: Option<PathBuf>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    header: Option<FormatInfo>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    documents: Vec<DocumentReport>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    checks: Vec<Check>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    bench: Option<bench::Results>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<ErrorReport>,
+}
+
+/// Runs a subcommand and returns the process exit code.
+pub fn run(command: Command, json: bool) -> i32 {
+    let (name, writes_stdout) = match &command {
+        Command::Encrypt { files, .. } => ("encrypt", files.writes_stdout()),
+        Command::Decrypt { files, .. } => ("decrypt", files.writes_stdout()),
+        Command::Cat { .. } => ("cat", true),
+        Command::Rekey { .. } => ("rekey", false),
+        Command::Migrate { .. } => ("migrate", false),
+        Command::Import { .. } => ("import", false),
+        Command::Export { .. } => ("export", false),
+        Command::Mirror { .. } => ("mirror", false),
+        Command::Watch { .. } => ("watch", false),
+        Command::Selftest => ("selftest", false),
+        Command::Bench => ("bench", false),
+    };
+
+    let mut report = Report {
+        command: name,
+        ok: false,
+        input: None,
+        output: None,
+        header: None,
+        documents: vec![],
+        checks: vec![],
+        bench: None,
+        error: None,
+    };
+
+    let result = match command {
+        Command::Encrypt {
+            files,
+            from,
+            #[cfg(feature = "openpgp")]
+            openpgp,
+            armor,
+            options,
+        } => {
+            #[cfg(feature = "openpgp")]
+            let container = if openpgp {
+                Container::OpenPgp
+            } else {
+                Container::CryptoDoc
+            };
+            #[cfg(not(feature = "openpgp"))]
+            let container = Container::CryptoDoc;
+
+            encrypt_file(
+                &files,
+                from.as_deref(),
+                container,
+                armor,
+                &options,
+                &mut report,
+            )
+        }
+        Command::Decrypt { files, to, options } => {
+            decrypt_file(&files, to.as_deref(), &options, &mut report)
+        }
+        Command::Cat { input, to, options } => {
+            cat_file(&input, to.as_deref(), &options, &mut report)
+        }
+        Command::Rekey {
+            path,
+            old_password_file,
+            new_password_file,
+        } => rekey(
+            &path,
+            old_password_file,
+            new_password_file,
+            json,
+            &mut report,
+        ),
+        Command::Migrate { path, options } => migrate(&path, &options, json, &mut report),
+        Command::Import {
+            archive,
+            from,
+            into,
+            archive_password_file,
+            options,
+        } => import_archive(
+            &archive,
+            &from,
+            &into,
+            archive_password_file.as_deref(),
+            &options,
+            json,
+            &mut report,
+        ),
+        Command::Export {
+            folder,
+            to,
+            output,
+            force,
+            options,
+        } => export_archive(&folder, &to, &output, force, &options, json, &mut report),
+        Command::Mirror {
+            notes,
+            mirror,
+            decrypt,
+            force,
+            options,
+        } => {
+            let direction = if decrypt {
+                Direction::Decrypt
+            } else {
+                Direction::Encrypt
+            };
+
+            mirror_notes(
+                &notes,
+                &mirror,
+                direction,
+                force,
+                &options,
+                json,
+                &mut report,
+            )
+        }
+        Command::Watch {
+            folder,
+            output,
+            interval,
+            options,
+        } => watch(
+            &folder,
+            output.as_deref(),
+            Duration::from_secs(interval.max(1)),
+            &options,
+            json,
+        ),
+        Command::Selftest => self_test(&mut report),
+        Command::Bench => run_bench(&mut report),
+    };
+
+    report.ok = result.is_ok();
+    report.error = result.as_ref().err().map(ErrorReport::from);
+
+    if json {
+        let json = serde_json::to_string_pretty(&report).expect("Failed to serialize report");
+
+        // Keep the report out of the way of data written to standard output.
+        if writes_stdout {
+            eprintln!("{}", json);
+        } else {
+            println!("{}", json);
+        }
+    } else {
+        for check in &report.checks {
+            let status = if check.passed { "ok" } else { "FAILED" };
+            println!("{:<6} {}", status, check.name);
+        }
+
+        if let Some(results) = &report.bench {
+            println!("key derivation  {:.3} ms", results.key_derivation);
+
+            for throughput in &results.throughput {
+                println!(
+                    "{:<8} encrypt {:>8.1} MB/s  decrypt {:>8.1} MB/s{}",
+                    format_size(throughput.size as u64),
+                    throughput.encrypt,
+                    throughput.decrypt,
+                    if throughput.verified { "" } else { "  FAILED" }
+                );
+            }
+        }
+
+        for document in &report.documents {
+            if let Some(output) = &document.output {
+                println!("{} -> {}", document.path.display(), output.display());
+            }
+
+            if let Some(backup) = &document.backup {
+                println!(
+                    "{}: kept the old version as {}",
+                    document.path.display(),
+                    backup.display()
+                );
+            }
+
+            if document.reused_password {
+                eprintln!(
+                    "cryptodoc: warning: {} had the new password before",
+                    document.path.display()
+                );
+            }
+        }
+
+        for error in report
+            .documents
+            .iter()
+            .filter_map(|document| document.error.as_ref())
+        {
+            eprintln!("cryptodoc: {}", error.message);
+        }
+
+        if let Err(error) = &result {
+            eprintln!("cryptodoc: {}", error);
+        }
+    }
+
+    if result.is_ok() {
+        0
+    } else {
+        1
+    }
+}
+
+fn encrypt_file(
+    files: &Files,
+    from: Option<&str>,
+    container: Container,
+    armored: bool,
+    options: &Options,
+    report: &mut Report,
+) -> Result<(), CliError> {
+    let armored = armored && container == Container::CryptoDoc;
+    let output = files.sink(if armored {
+        armor::EXTENSION
+    } else {
+        container.extension()
+    });
+    report.input = files.source().map(Path::to_path_buf);
+    report.output = output.clone();
+
+    check_output(output.as_deref(), files.force)?;
+
+    // Without a format, plaintext is encrypted byte for byte, so binary data
+    // survives too.
+    let mut plaintext = read_input(files.source())?;
+
+    if let Some(importer) = from.and_then(importer) {
+        let document = importer.import(plaintext).map_err(CliError::Format)?;
+        plaintext = document.line_ending.apply(&document.text).into_bytes();
+    }
+
+    let password = password(options, true)?;
+
+    let encrypted = match container {
+        Container::CryptoDoc => encrypt(&plaintext, &password),
+        #[cfg(feature = "openpgp")]
+        Container::OpenPgp => openpgp::encrypt(&plaintext, &password),
+    };
+    report.header = inspect(&encrypted).ok();
+
+    if armored {
+        write_output(output.as_deref(), armor::armor(&encrypted))
+    } else {
+        write_output(output.as_deref(), encrypted)
+    }
+}
+
+fn decrypt_file(
+    files: &Files,
+    to: Option<&str>,
+    options: &Options,
+    report: &mut Report,
+) -> Result<(), CliError> {
+    let exporter = to.and_then(exporter);
+    let output = files.sink(exporter.map_or("txt", |exporter| exporter.extension()));
+    report.input = files.source().map(Path::to_path_buf);
+    report.output = output.clone();
+
+    check_output(output.as_deref(), files.force)?;
+
+    let plaintext = decrypt_input(files.source(), options, report)?;
+    write_output(output.as_deref(), convert(plaintext, to)?)
+}
+
+fn cat_file(
+    input: &Path,
+    to: Option<&str>,
+    options: &Options,
+    report: &mut Report,
+) -> Result<(), CliError> {
+    let input = Some(input).filter(|path| *path != Path::new(STDIO));
+    report.input = input.map(Path::to_path_buf);
+
+    let plaintext = decrypt_input(input, options, report)?;
+    write_output(None, convert(plaintext, to)?)
+}
+
+/// Runs decrypted plaintext through the exporter named `to`, if any.
+fn convert(plaintext: Vec<u8>, to: Option<&str>) -> Result<Vec<u8>, CliError> {
+    let Some(exporter) = to.and_then(exporter) else {
+        return Ok(plaintext);
+    };
+
+    let document = PlainText.import(plaintext).map_err(CliError::Format)?;
+
+    formats::export(exporter, &document).map_err(CliError::Format)
+}
+
+fn decrypt_input(
+    input: Option<&Path>,
+    options: &Options,
+    report: &mut Report,
+) -> Result<Vec<u8>, CliError> {
+    let name = || input.map_or_else(|| PathBuf::from("<stdin>"), Path::to_path_buf);
+
+    let bytes = read_input(input)?;
+
+    #[cfg(feature = "openpgp")]
+    let bytes = match bytes {
+        bytes if openpgp::is_binary(&bytes) => openpgp::armor(&bytes)
+            .map_err(|error| CliError::Decrypt(name(), error))?
+            .into_bytes(),
+        bytes => bytes,
+    };
+
+    let mut encrypted =
+        String::from_utf8(bytes).map_err(|_| CliError::Decrypt(name(), CryptoError::BadFormat))?;
+
+    if armor::is_armored(&encrypted) {
+        encrypted = armor::dearmor(&encrypted).map_err(|error| CliError::Decrypt(name(), error))?;
+    }
+
+    report.header = inspect(encrypted.trim_end()).ok();
+
+    let password = password(options, false)?;
+
+    let plaintext = match Container::detect(&encrypted) {
+        Container::CryptoDoc => decrypt(encrypted.trim_end(), &password),
+        #[cfg(feature = "openpgp")]
+        Container::OpenPgp => openpgp::decrypt(&encrypted, &password),
+    };
+
+    plaintext.map_err(|error| CliError::Decrypt(name(), error))
+}
+
+/// Reads `input`, or standard input when it's `None`.
+fn read_input(input: Option<&Path>) -> Result<Vec<u8>, CliError> {
+    match input {
+        Some(path) => fs::read(path).map_err(|error| CliError::Io(path.into(), error)),
+        None => {
+            let mut bytes = vec![];
+            io::stdin()
+                .lock()
+                .read_to_end(&mut bytes)
+                .map_err(CliError::Stdin)?;
+
+            Ok(bytes)
+        }
+    }
+}
+
+/// Writes to `output`, or standard output when it's `None`.
+fn write_output(output: Option<&Path>, contents: impl AsRef<[u8]>) -> Result<(), CliError> {
+    let Some(path) = output else {
+        let mut stdout = io::stdout().lock();
+
+        return match stdout
+            .write_all(contents.as_ref())
+            .and_then(|()| stdout.flush())
+        {
+            // The reader went away early, as `head` or `less` do.
+            Err(error) if error.kind() == io::ErrorKind::BrokenPipe => Ok(()),
+            result => result.map_err(CliError::Stdout),
+        };
+    };
+
+    write(path, contents)
+}
+
+/// Re-encrypts `path`, or every document directly inside it if it's a folder,
+/// under a new password. Each document gets a fresh IV, and a document that
+/// fails is reported and left untouched while the rest carry on.
+fn rekey(
+    path: &Path,
+    old_password_file: Option<PathBuf>,
+    new_password_file: Option<PathBuf>,
+    json: bool,
+    report: &mut Report,
+) -> Result<(), CliError> {
+    report.input = Some(path.to_path_buf());
+
+    let documents = documents_in(path)?;
+
+    let old_password = read_password(
+        old_password_file.as_deref(),
+        PASSWORD_VARIABLE,
+        "Current password: ",
+        false,
+    )?;
+    let new_password = read_password(
+        new_password_file.as_deref(),
+        NEW_PASSWORD_VARIABLE,
+        "New password: ",
+        true,
+    )?;
+
+    let results = in_parallel(&documents, "rekeying", json, |document| {
+        rekey_file(document, &old_password, &new_password)
+    });
+
+    let audit = AuditLog::new(DEFAULT_PATH);
+
+    for (document, result) in documents.iter().zip(results) {
+        if result.is_ok() {
+            // The audit log is best effort, a document that was rekeyed
+            // shouldn't be reported as failed because of it.
+            let _ = audit.append(Event::PasswordChanged {
+                document: document.display().to_string(),
+            });
+        }
+
+        report.documents.push(DocumentReport {
+            path: document.clone(),
+            output: None,
+            backup: result
+                .as_ref()
+                .ok()
+                .and_then(|rekeyed| rekeyed.backup.clone()),
+            reused_password: result.as_ref().is_ok_and(|rekeyed| rekeyed.reused_password),
+            error: result.as_ref().err().map(ErrorReport::from),
+        });
+    }
+
+    match failed_documents(report) {
+        0 => Ok(()),
+        failed => Err(CliError::Rekey {
+            failed,
+            total: documents.len(),
+        }),
+    }
+}
+
+struct Rekeyed {
+    /// The backup made of a document in an older format, if any.
+    backup: Option<PathBuf>,
+    /// Whether the new password is one the document had before.
+    reused_password: bool,
+}
+
+/// Changes the password, adding the old one to the document's
+/// [password history](cryptodoc::history).
+fn rekey_file(path: &Path, old_password: &str, new_password: &str) -> Result<Rekeyed, CliError> {
+    let encrypted = fs::read_to_string(path).map_err(|error| CliError::Io(path.into(), error))?;
+
+    let plaintext = decrypt(encrypted.trim_end(), old_password)
+        .map_err(|error| CliError::Decrypt(path.into(), error))?;
+
+    let mut history = history::read(&encrypted);
+    let reused_password = new_password == old_password || history.contains(new_password);
+
+    if new_password != old_password {
+        history.remember(old_password);
+    }
+
+    let backup = backup_if_outdated(path).map_err(|error| CliError::Io(path.into(), error))?;
+
+    // Write next to the original and rename over it, so an interrupted run
+    // never leaves a half-written document behind.
+    let temporary = path.with_extension("rekey");
+    write(
+        &temporary,
+        history.append_to(encrypt(&plaintext, new_password)),
+    )?;
+    fs::rename(&temporary, path).map_err(|error| CliError::Io(path.into(), error))?;
+
+    Ok(Rekeyed {
+        backup,
+        reused_password,
+    })
+}
+
+fn import_archive(
+    archive: &Path,
+    from: &str,
+    folder: &Path,
+    archive_password_file: Option<&Path>,
+    options: &Options,
+    json: bool,
+    report: &mut Report,
+) -> Result<(), CliError> {
+    let importer = archive_importer(from).expect("clap only accepts known formats");
+    report.input = Some(archive.to_path_buf());
+    report.output = Some(folder.to_path_buf());
+
+    let bytes = fs::read(archive).map_err(|error| CliError::Io(archive.into(), error))?;
+
+    let archive_password = if importer.needs_password() {
+        Some(read_password(
+            archive_password_file,
+            ARCHIVE_PASSWORD_VARIABLE,
+            "Archive password: ",
+            false,
+        )?)
+    } else {
+        None
+    };
+
+    let documents = importer
+        .import(&bytes, archive_password.as_deref())
+        .map_err(CliError::Format)?;
+
+    let password = password(options, true)?;
+    let vault = Vault::new(folder);
+
+    // Only the encryption runs in parallel. The documents are written one
+    // at a time, so two with the same name can't be given the same path.
+    let encrypted = in_parallel(&documents, "encrypting", json, |imported| {
+        imported.document.encrypt(&password)
+    });
+
+    // Tags, like the folders of a password manager, go in the vault's index
+    // as in the app.
+    let mut index = VaultIndex::load(folder);
+    let mut tagged = false;
+
+    for (imported, encrypted) in documents.iter().zip(encrypted) {
+        let path = vault.unused_path_for(&imported.name);
+        let result = write(&path, encrypted);
+
+        if result.is_ok() && !imported.tags.is_empty() {
+            index.update(&path, |meta| {
+                for tag in &imported.tags {
+                    meta.add_tag(tag);
+                }
+            });
+            tagged = true;
+        }
+
+        report.documents.push(DocumentReport {
+            path: PathBuf::from(&imported.name),
+            output: result.is_ok().then_some(path),
+            backup: None,
+            reused_password: false,
+            error: result.as_ref().err().map(ErrorReport::from),
+        });
+    }
+
+    if tagged {
+        if let Err(error) = index.save(folder) {
+            eprintln!(
+                "cryptodoc: couldn't save the tags to {}: {}",
+                index_path(folder).display(),
+                error
+            );
+        }
+    }
+
+    match failed_documents(report) {
+        0 => Ok(()),
+        failed => Err(CliError::Import {
+            failed,
+            total: documents.len(),
+        }),
+    }
+}
+
+fn export_archive(
+    folder: &Path,
+    to: &str,
+    output: &Path,
+    force: bool,
+    options: &Options,
+    json: bool,
+    report: &mut Report,
+) -> Result<(), CliError> {
+    let exporter = archive_exporter(to).expect("clap only accepts known formats");
+    report.input = Some(folder.to_path_buf());
+    report.output = Some(output.to_path_buf());
+
+    check_output(Some(output), force)?;
+
+    let paths = documents_in(folder)?;
+    let password = password(options, false)?;
+    let mut documents = vec![];
+
+    let results = in_parallel(&paths, "decrypting", json, |path| {
+        export_document(path, &password)
+    });
+
+    for (path, result) in paths.iter().zip(results) {
+        report.documents.push(DocumentReport {
+            path: path.clone(),
+            output: None,
+            backup: None,
+            reused_password: false,
+            error: result.as_ref().err().map(ErrorReport::from),
+        });
+
+        if let Ok(document) = result {
+            documents.push(document);
+        }
+    }
+
+    let archive = formats::export_archive(exporter, &documents).map_err(CliError::Format)?;
+    write(output, archive)?;
+
+    // The archive is still written without the documents that failed, so
+    // one damaged document doesn't hold up the rest.
+    match failed_documents(report) {
+        0 => Ok(()),
+        failed => Err(CliError::Export {
+            failed,
+            total: paths.len(),
+        }),
+    }
+}
+
+fn export_document(path: &Path, password: &str) -> Result<NamedDocument, CliError> {
+    let encrypted = fs::read_to_string(path).map_err(|error| CliError::Io(path.into(), error))?;
+    let plaintext = decrypt(encrypted.trim_end(), password)
+        .map_err(|error| CliError::Decrypt(path.into(), error))?;
+
+    Ok(NamedDocument {
+        name: path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+        document: PlainText.import(plaintext).map_err(CliError::Format)?,
+        modified: fs::metadata(path)
+            .and_then(|metadata| metadata.modified())
+            .ok(),
+        tags: vec![],
+    })
+}
+
+fn migrate(
+    path: &Path,
+    options: &Options,
+    json: bool,
+    report: &mut Report,
+) -> Result<(), CliError> {
+    report.input = Some(path.to_path_buf());
+
+    let documents = documents_in(path)?;
+    let password = password(options, false)?;
+
+    let results = in_parallel(&documents, "migrating", json, |document| {
+        migrate_document(document, &password)
+            .map_err(|error| CliError::Decrypt(document.clone(), error))
+    });
+
+    for (document, result) in documents.iter().zip(results) {
+        report.documents.push(DocumentReport {
+            path: document.clone(),
+            output: None,
+            backup: result.as_ref().ok().cloned().flatten(),
+            reused_password: false,
+            error: result.as_ref().err().map(ErrorReport::from),
+        });
+    }
+
+    match failed_documents(report) {
+        0 => Ok(()),
+        failed => Err(CliError::Migrate {
+            failed,
+            total: documents.len(),
+        }),
+    }
+}
+
+/// Encrypts the changed files in `notes` into `mirror`, or decrypts the
+/// changed files in `mirror` back into `notes`. A file that fails, or that
+/// was changed on the other side since it was last mirrored, is reported and
+/// the rest carry on. `force` overwrites those changes.
+fn mirror_notes(
+    notes: &Path,
+    mirror: &Path,
+    direction: Direction,
+    force: bool,
+    options: &Options,
+    json: bool,
+    report: &mut Report,
+) -> Result<(), CliError> {
+    let (from, to) = match direction {
+        Direction::Encrypt => (notes, mirror),
+        Direction::Decrypt => (mirror, notes),
+    };
+    report.input = Some(from.to_path_buf());
+    report.output = Some(to.to_path_buf());
+
+    let mut state = mirror::State::load();
+    let changes = mirror::changes(from, to, direction, &state)
+        .map_err(|error| CliError::Io(from.into(), error))?;
+
+    if changes.is_empty() {
+        return Ok(());
+    }
+
+    let password = password(options, direction == Direction::Encrypt)?;
+
+    if direction == Direction::Encrypt && mirror.is_dir() {
+        mirror::check_password(mirror, &password)
+            .map_err(|error| CliError::Decrypt(mirror.into(), error))?;
+    }
+
+    let verb = match direction {
+        Direction::Encrypt => "encrypting",
+        Direction::Decrypt => "decrypting",
+    };
+    let results = in_parallel(&changes, verb, json, |change| {
+        if change.conflict && !force {
+            return Err(CliError::Conflict(change.target.clone()));
+        }
+
+        mirror::apply(change, direction, &password)
+            .map_err(|error| CliError::Decrypt(change.source.clone(), error))
+    });
+
+    for (change, result) in changes.iter().zip(&results) {
+        if result.is_ok() {
+            state.record(&change.target);
+        }
+    }
+
+    if let Err(error) = state.save() {
+        eprintln!("cryptodoc: couldn't save {}: {}", mirror::STATE_PATH, error);
+    }
+
+    for (change, result) in changes.iter().zip(results) {
+        report.documents.push(DocumentReport {
+            path: change.source.clone(),
+            output: result.is_ok().then(|| change.target.clone()),
+            backup: None,
+            reused_password: false,
+            error: result.as_ref().err().map(ErrorReport::from),
+        });
+    }
+
+    match failed_documents(report) {
+        0 => Ok(()),
+        failed => Err(CliError::Mirror {
+            failed,
+            total: changes.len(),
+        }),
+    }
+}
+
+/// A file's size and modification time. A dropped file is only encrypted
+/// once this stops changing, so one that's still being copied in is left
+/// alone.
+type FileState = (u64, Option<SystemTime>);
+
+fn watch(
+    folder: &Path,
+    output: Option<&Path>,
+    interval: Duration,
+    options: &Options,
+    json: bool,
+) -> Result<(), CliError> {
+    let output = output.unwrap_or(folder);
+    let password = password(options, true)?;
+
+    // Files seen on the last pass, and files that failed and shouldn't be
+    // retried until they change.
+    let mut pending: HashMap<PathBuf, FileState> = HashMap::new();
+    let mut failed: HashMap<PathBuf, FileState> = HashMap::new();
+
+    if !json {
+        eprintln!("watching {} (press Ctrl+C to stop)", folder.display());
+    }
+
+    loop {
+        let mut seen = HashMap::new();
+
+        for path in dropped_files(folder)? {
+            let Ok(metadata) = fs::metadata(&path) else {
+                continue;
+            };
+            let state = (metadata.len(), metadata.modified().ok());
+
+            if failed.get(&path) == Some(&state) {
+                continue;
+            }
+
+            if pending.get(&path) != Some(&state) {
+                seen.insert(path, state);
+                continue;
+            }
+
+            let result = encrypt_dropped(&path, output, &password);
+
+            if result.is_err() {
+                failed.insert(path.clone(), state);
+            }
+
+            let document = DocumentReport {
+                path,
+                output: result.as_ref().ok().cloned(),
+                backup: None,
+                reused_password: false,
+                error: result.as_ref().err().map(ErrorReport::from),
+            };
+
+            if json {
+                let json = serde_json::to_string(&document).expect("Failed to serialize report");
+                println!("{}", json);
+            } else if let Some(error) = &document.error {
+                eprintln!("cryptodoc: {}", error.message);
+            } else if let Some(output) = &document.output {
+                println!("{} -> {}", document.path.display(), output.display());
+            }
+        }
+
+        pending = seen;
+        failed.retain(|path, _| path.exists());
+
+        thread::sleep(interval);
+    }
+}
+
+/// Files in `folder` waiting to be encrypted. Documents are skipped, and so
+/// are hidden and temporary files, which are usually still being written.
+fn dropped_files(folder: &Path) -> Result<Vec<PathBuf>, CliError> {
+    const TEMPORARY_SUFFIXES: [&str; 6] = ["~", ".tmp", ".part", ".crdownload", ".swp", ".bak"];
+
+    let files = fs::read_dir(folder)
+        .map_err(|error| CliError::Io(folder.into(), error))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            let name = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .unwrap_or_default();
+
+            path.is_file()
+                && !name.is_empty()
+                && !name.starts_with('.')
+                && !TEMPORARY_SUFFIXES
+                    .iter()
+                    .any(|suffix| name.ends_with(suffix))
+                && path.extension().and_then(|extension| extension.to_str())
+                    != Some(DOCUMENT_EXTENSION)
+        })
+        .collect();
+
+    Ok(files)
+}
+
+/// Encrypts a dropped file into `folder` and shreds the original. Returns
+/// the new document.
+fn encrypt_dropped(path: &Path, folder: &Path, password: &str) -> Result<PathBuf, CliError> {
+    let name = Path::new(path.file_name().unwrap_or_default());
+    let document = folder.join(name.with_extension(DOCUMENT_EXTENSION));
+
+    // Never replace a document; the file stays put until it's renamed.
+    check_output(Some(&document), false)?;
+
+    let plaintext = fs::read(path).map_err(|error| CliError::Io(path.into(), error))?;
+
+    // Written to a hidden file first, so a pass that runs while it's being
+    // written skips it.
+    let encrypted = encrypt(&plaintext, password);
+    file::replace_with(&document, |writer| writer.write_all(encrypted.as_bytes()))
+        .map_err(|error| CliError::Io(document.clone(), error))?;
+
+    // The plaintext is the only other copy, so it's only shredded once the
+    // document on disk is known to give it back.
+    let verified = fs::read_to_string(&document)
+        .ok()
+        .and_then(|written| decrypt(written.trim_end(), password).ok())
+        .is_some_and(|decrypted| decrypted == plaintext);
+
+    if !verified {
+        let _ = fs::remove_file(&document);
+        return Err(CliError::Unverified(path.into()));
+    }
+
+    shred(path).map_err(|error| CliError::Io(path.into(), error))?;
+
+    Ok(document)
+}
+
+/// Overwrites a file with zeros before deleting it. On SSDs and
+/// copy-on-write filesystems the old blocks can survive anyway, so this
+/// only makes recovering the plaintext harder, not impossible.
+fn shred(path: &Path) -> io::Result<()> {
+    let length = fs::metadata(path)?.len();
+    let mut file = fs::OpenOptions::new().write(true).open(path)?;
+
+    io::copy(&mut io::repeat(0).take(length), &mut file)?;
+    file.sync_all()?;
+    drop(file);
+
+    fs::remove_file(path)
+}
+
+/// The document at `path`, or every document in it if it's a folder.
+fn documents_in(path: &Path) -> Result<Vec<PathBuf>, CliError> {
+    if !path.is_dir() {
+        return Ok(vec![path.to_path_buf()]);
+    }
+
+    let mut documents: Vec<PathBuf> = fs::read_dir(path)
+        .map_err(|error| CliError::Io(path.into(), error))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.is_file()
+                && path.extension().and_then(|extension| extension.to_str())
+                    == Some(DOCUMENT_EXTENSION)
+        })
+        .collect();
+
+    documents.sort();
+
+    Ok(documents)
+}
+
+fn failed_documents(report: &Report) -> usize {
+    report
+        .documents
+        .iter()
+        .filter(|document| document.error.is_some())
+        .count()
+}
+
+/// Runs `process` on the items across every core, returning the results in
+/// the order of `items`. How many are done is shown on the terminal unless
+/// the report is JSON.
+fn in_parallel<T, R>(
+    items: &[T],
+    verb: &str,
+    json: bool,
+    process: impl Fn(&T) -> R + Sync,
+) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+{
+    let show_progress = !json && items.len() > 1 && io::stderr().is_terminal();
+    let done = AtomicUsize::new(0);
+
+    let results = items
+        .par_iter()
+        .map(|item| {
+            let result = process(item);
+            let done = done.fetch_add(1, Ordering::Relaxed) + 1;
+
+            if show_progress {
+                eprint!("\r{} {}/{}", verb, done, items.len());
+            }
+
+            result
+        })
+        .collect();
+
+    if show_progress {
+        eprintln!();
+    }
+
+    results
+}
+
+fn self_test(report: &mut Report) -> Result<(), CliError> {
+    report.checks = selftest::run();
+
+    match report.checks.iter().filter(|check| !check.passed).count() {
+        0 => Ok(()),
+        failed => Err(CliError::SelfTest { failed }),
+    }
+}
+
+fn run_bench(report: &mut Report) -> Result<(), CliError> {
+    let results = report.bench.insert(bench::run());
+
+    match results
+        .throughput
+        .iter()
+        .filter(|throughput| !throughput.verified)
+        .count()
+    {
+        0 => Ok(()),
+        failed => Err(CliError::Bench { failed }),
+    }
+}
+
+fn check_output(output: Option<&Path>, force: bool) -> Result<(), CliError> {
+    if let Some(output) = output.filter(|output| output.exists() && !force) {
+        return Err(CliError::Exists(output.into()));
+    }
+
+    Ok(())
+}
+
+fn write(path: &Path, contents: impl AsRef<[u8]>) -> Result<(), CliError> {
+    fs::write(path, contents).map_err(|error| CliError::Io(path.into(), error))
+}
+
+fn password(options: &Options, confirm: bool) -> Result<String, CliError> {
+    read_password(
+        options.password_file.as_deref(),
+        PASSWORD_VARIABLE,
+        "Password: ",
+        confirm,
+    )
+}
+
+/// Takes the password from a file, the environment or the terminal, in that
+/// order. Only the terminal asks twice when `confirm` is set, since the
+/// other sources can't have typos.
+fn read_password(
+    file: Option<&Path>,
+    variable: &str,
+    prompt: &str,
+    confirm: bool,
+) -> Result<String, CliError> {
+    let password = if let Some(path) = file {
+        let contents =
+            fs::read_to_string(path).map_err(|error| CliError::Io(path.into(), error))?;
+
+        contents.lines().next().unwrap_or_default().to_string()
+    } else if let Ok(password) = std::env::var(variable) {
+        password
+    } else {
+        let password = rpassword::prompt_password(prompt).map_err(CliError::Prompt)?;
+
+        if confirm {
+            let again =
+                rpassword::prompt_password("Confirm password: ").map_err(CliError::Prompt)?;
+
+            if again != password {
+                return Err(CliError::PasswordMismatch);
+            }
+        }
+
+        password
+    };
+
+    if password.is_empty() {
+        return Err(CliError::EmptyPassword);
+    }
+
+    Ok(password)
+}
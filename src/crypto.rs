@@ -1,44 +1,343 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{self, Write};
+use std::iter::repeat;
+use std::sync::{Arc, Mutex};
+
 use crypto::aead::{AeadDecryptor, AeadEncryptor};
 use crypto::aes_gcm::AesGcm;
-use std::error::Error;
-use std::io::ErrorKind;
-use std::iter::repeat;
-use std::{io, str};
+use crypto::digest::Digest;
+use crypto::hmac::Hmac;
+use crypto::mac::Mac;
+use crypto::pbkdf2::pbkdf2;
+use crypto::sha2::Sha256;
+use serde::Serialize;
+use thiserror::Error;
+use zeroize::Zeroize;
+
+use crate::history;
+use crate::lockout;
+
+/// The format [`encrypt`] writes, except for documents of at least
+/// [`CHUNKED_THRESHOLD`]. [`decrypt`] also reads every version before it.
+pub const FORMAT_VERSION: u32 = 2;
+
+/// Version 3 splits the plaintext into chunks encrypted on their own, one
+/// per line after the header, with a last line sealing the list of chunk
+/// MACs so they can't be dropped or reordered. Saving a document again with
+/// the same [`SessionKey`] reuses the encrypted chunks whose plaintext
+/// hasn't changed, so an edit to a huge document only encrypts a chunk or
+/// two.
+const CHUNKED_VERSION: u32 = 3;
+
+/// Documents this large are written in the chunked format version.
+pub const CHUNKED_THRESHOLD: usize = 16 * 1024 * 1024;
+
+/// Chunks end at a line break chosen by the line's contents, so inserting
+/// or deleting text only moves the chunk boundaries around it. The choice is
+/// keyed with a subkey of the document key, so the chunk sizes, which show
+/// in the ciphertext, say nothing about the text to anyone without it.
+const MIN_CHUNK_SIZE: usize = 32 * 1024;
+const MAX_CHUNK_SIZE: usize = 256 * 1024;
+/// On average one line in this many ends a chunk, once it's long enough.
+const CHUNK_BOUNDARY_ODDS: u32 = 64;
+/// Tells the subkey picking chunk boundaries apart from other uses of the
+/// document key.
+const CHUNK_BOUNDARY_CONTEXT: &[u8] = b"cryptodoc chunk boundaries";
+
+/// Later format versions start with `cryptodoc:<version>:`. Version 1
+/// predates the header and is recognised by its absence.
+const VERSION_PREFIX: &str = "cryptodoc:";
+/// Enough of the start of a document for [`header_version`].
+pub const HEADER_SIZE: usize = 64;
+
+const IV_SIZE: usize = 12;
+const MAC_SIZE: usize = 16;
+const SALT_SIZE: usize = 16;
+const KEY_SIZE: usize = 32;
+
+/// How much ciphertext [`encrypt_to`] hex encodes at a time.
+const HEX_CHUNK_SIZE: usize = 32 * 1024;
+
+/// PBKDF2 rounds for new documents. Each document records its own count, so
+/// raising this doesn't need a new format version.
+pub const KDF_ITERATIONS: u32 = 100_000;
+
+#[derive(Debug, Error)]
+pub enum CryptoError {
+    /// The contents aren't a CryptoDoc document at all.
+    #[error("the file isn't a CryptoDoc document")]
+    BadFormat,
+    /// The MAC doesn't match. AES-GCM can't tell a wrong password from a
+    /// modified ciphertext, but a wrong password is by far the likelier cause.
+    #[error("the password is incorrect")]
+    WrongPassword,
+    /// The document has the right shape but parts of it are damaged.
+    #[error("the document is damaged")]
+    CorruptData,
+    #[error("format version {0} isn't supported by this version of CryptoDoc")]
+    UnsupportedVersion(u32),
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FormatInfo {
+    pub version: u32,
+    pub cipher: &'static str,
+    pub kdf: String,
+    pub iv_size: usize,
+    pub mac_size: usize,
+    pub ciphertext_size: usize,
+}
 
-fn split_iv_data_mac(orig: &str) -> Result<(Vec<u8>, Vec<u8>, Vec<u8>), Box<dyn Error>> {
-    let split: Vec<&str> = orig.split('/').into_iter().collect();
+/// How the AES key is derived from the password.
+enum Kdf {
+    /// Version 1: the password itself, padded or cut to 16 bytes.
+    Padded,
+    /// Version 2: PBKDF2-HMAC-SHA256 with a per-document salt.
+    Pbkdf2 { iterations: u32, salt: Vec<u8> },
+}
 
-    if split.len() != 3 {
-        return Err(Box::new(io::Error::from(ErrorKind::Other)));
+impl Kdf {
+    fn derive(&self, password: &str) -> Vec<u8> {
+        match self {
+            Kdf::Padded => get_valid_key(password),
+            Kdf::Pbkdf2 { iterations, salt } => derive_key(password, salt, *iterations),
+        }
     }
 
-    let iv_res = hex::decode(split[0]);
-    if iv_res.is_err() {
-        return Err(Box::new(io::Error::from(ErrorKind::Other)));
+    fn describe(&self) -> String {
+        match self {
+            Kdf::Padded => "None (password padded to 16 bytes)".to_string(),
+            Kdf::Pbkdf2 { iterations, .. } => {
+                format!("PBKDF2-HMAC-SHA256, {} iterations", iterations)
+            }
+        }
     }
+}
 
-    let iv = iv_res.unwrap();
+/// The parts of an encrypted document.
+struct Parsed<'a> {
+    version: u32,
+    kdf: Kdf,
+    /// The ciphertext, in one chunk before version 3. Version 3 ends with
+    /// the sealed list of chunk MACs.
+    chunks: Vec<Sealed<'a>>,
+}
+
+/// One AES-GCM ciphertext. The data is only decoded from hex when it's
+/// opened, so reading the header of a large document doesn't copy it.
+struct Sealed<'a> {
+    iv: Vec<u8>,
+    data: &'a [u8],
+    mac: Vec<u8>,
+}
+
+impl<'a> Sealed<'a> {
+    fn parse(iv: &[u8], data: &'a [u8], mac: &[u8]) -> Result<Self, CryptoError> {
+        let decode = |part: &[u8]| hex::decode(part).map_err(|_| CryptoError::CorruptData);
+        let (iv, mac) = (decode(iv)?, decode(mac)?);
+
+        let is_hex = data.len() % 2 == 0 && data.iter().all(|byte| byte.is_ascii_hexdigit());
 
-    let data_res = hex::decode(split[1]);
+        if iv.len() != IV_SIZE || mac.len() != MAC_SIZE || !is_hex {
+            return Err(CryptoError::CorruptData);
+        }
 
-    if data_res.is_err() {
-        return Err(Box::new(io::Error::from(ErrorKind::Other)));
+        Ok(Self { iv, data, mac })
     }
 
-    let data = data_res.unwrap();
+    fn size(&self) -> usize {
+        self.data.len() / 2
+    }
 
-    let mac_res = hex::decode(split[2]);
+    fn open(&self, key: &[u8]) -> Option<Vec<u8>> {
+        let data = hex::decode(self.data).ok()?;
 
-    if mac_res.is_err() {
-        return Err(Box::new(io::Error::from(ErrorKind::Other)));
+        match open(key, &self.iv, &data, &self.mac) {
+            (true, plaintext) => Some(plaintext),
+            (false, _) => None,
+        }
     }
+}
 
-    let mac = mac_res.unwrap();
+/// Returns the format version `contents` was written in, without checking
+/// the rest of it.
+pub fn format_version(contents: impl AsRef<[u8]>) -> Result<u32, CryptoError> {
+    detect_version(contents.as_ref()).map(|(version, _)| version)
+}
+
+/// Like [`format_version`], but from only the first [`HEADER_SIZE`] bytes of
+/// a document, so a large one needn't be read. `None` if it doesn't start
+/// like a document.
+pub fn header_version(header: &str) -> Option<u32> {
+    let version = format_version(header).ok()?;
+
+    // Without a header, only the IV version 1 starts with tells it apart
+    // from any other file.
+    let is_document = version > 1
+        || header.split_once('/').is_some_and(|(iv, _)| {
+            iv.len() == IV_SIZE * 2 && iv.bytes().all(|byte| byte.is_ascii_hexdigit())
+        });
+
+    is_document.then_some(version)
+}
+
+/// Splits off the version header, if there is one.
+fn detect_version(contents: &[u8]) -> Result<(u32, &[u8]), CryptoError> {
+    let contents = strip_line(
+        strip_line(contents, lockout::RECORD_PREFIX),
+        history::LINE_PREFIX,
+    );
+
+    let Some(rest) = contents.strip_prefix(VERSION_PREFIX.as_bytes()) else {
+        return Ok((1, contents));
+    };
+
+    let (version, body) = split_once(rest, b':').ok_or(CryptoError::BadFormat)?;
+    let version = number(version).ok_or(CryptoError::BadFormat)?;
+
+    Ok((version, body))
+}
+
+/// Documents are parsed as bytes, not text, as a large one is mapped from
+/// its file, which another program could change while it's read.
+fn parse(contents: &[u8]) -> Result<Parsed<'_>, CryptoError> {
+    let (version, body) = detect_version(contents)?;
+
+    if version == CHUNKED_VERSION {
+        return parse_chunked(body);
+    }
+
+    let parts: Vec<&[u8]> = body.split(|&byte| byte == b'/').collect();
+
+    // Version 1 is `iv/data/mac`; version 2 adds `iterations/salt/` in front.
+    let (kdf, [iv, data, mac]) = match version {
+        1 => (
+            Kdf::Padded,
+            parts.try_into().map_err(|_| CryptoError::BadFormat)?,
+        ),
+        2 => {
+            let [iterations, salt, iv, data, mac]: [&[u8]; 5] =
+                parts.try_into().map_err(|_| CryptoError::BadFormat)?;
+
+            (parse_pbkdf2(iterations, salt)?, [iv, data, mac])
+        }
+        version => return Err(CryptoError::UnsupportedVersion(version)),
+    };
+
+    Ok(Parsed {
+        version,
+        kdf,
+        chunks: vec![Sealed::parse(iv, data, mac)?],
+    })
+}
+
+/// Version 3: `iterations/salt`, then a line of `iv/data/mac` per chunk,
+/// then the sealed chunk MACs in the same form.
+fn parse_chunked(body: &[u8]) -> Result<Parsed<'_>, CryptoError> {
+    let mut lines = body
+        .split(|&byte| byte == b'\n')
+        .map(trim)
+        .filter(|line| !line.is_empty());
+
+    let (iterations, salt) = lines
+        .next()
+        .and_then(|line| split_once(line, b'/'))
+        .ok_or(CryptoError::BadFormat)?;
+    let kdf = parse_pbkdf2(iterations, salt)?;
+
+    let chunks = lines
+        .map(|line| {
+            let parts: Vec<&[u8]> = line.split(|&byte| byte == b'/').collect();
+            let [iv, data, mac]: [&[u8]; 3] =
+                parts.try_into().map_err(|_| CryptoError::BadFormat)?;
+
+            Sealed::parse(iv, data, mac)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if chunks.is_empty() {
+        return Err(CryptoError::BadFormat);
+    }
 
-    Ok((iv, data, mac))
+    Ok(Parsed {
+        version: CHUNKED_VERSION,
+        kdf,
+        chunks,
+    })
 }
 
-fn get_valid_key(key: &str) -> Vec<u8> {
+fn parse_pbkdf2(iterations: &[u8], salt: &[u8]) -> Result<Kdf, CryptoError> {
+    let iterations = number(iterations)
+        .filter(|&iterations| iterations > 0)
+        .ok_or(CryptoError::CorruptData)?;
+    let salt = hex::decode(salt).map_err(|_| CryptoError::CorruptData)?;
+
+    if salt.len() != SALT_SIZE {
+        return Err(CryptoError::CorruptData);
+    }
+
+    Ok(Kdf::Pbkdf2 { iterations, salt })
+}
+
+/// `contents` without a last line starting with `prefix`, like the lockout
+/// record or password history kept after the document.
+fn strip_line<'a>(contents: &'a [u8], prefix: &str) -> &'a [u8] {
+    let contents = trim(contents);
+
+    match contents.iter().rposition(|&byte| byte == b'\n') {
+        Some(end) if contents[end + 1..].starts_with(prefix.as_bytes()) => trim(&contents[..end]),
+        _ => contents,
+    }
+}
+
+fn split_once(bytes: &[u8], separator: u8) -> Option<(&[u8], &[u8])> {
+    let index = bytes.iter().position(|&byte| byte == separator)?;
+
+    Some((&bytes[..index], &bytes[index + 1..]))
+}
+
+fn trim(mut bytes: &[u8]) -> &[u8] {
+    while let [first, rest @ ..] = bytes {
+        if !first.is_ascii_whitespace() {
+            break;
+        }
+        bytes = rest;
+    }
+
+    while let [rest @ .., last] = bytes {
+        if !last.is_ascii_whitespace() {
+            break;
+        }
+        bytes = rest;
+    }
+
+    bytes
+}
+
+fn number(bytes: &[u8]) -> Option<u32> {
+    if bytes.is_empty() || !bytes.iter().all(u8::is_ascii_digit) {
+        return None;
+    }
+
+    bytes.iter().try_fold(0u32, |number, &digit| {
+        number.checked_mul(10)?.checked_add(u32::from(digit - b'0'))
+    })
+}
+
+/// Derives a 256-bit key from `password` with PBKDF2-HMAC-SHA256.
+pub(crate) fn derive_key(password: &str, salt: &[u8], iterations: u32) -> Vec<u8> {
+    let mut mac = Hmac::new(Sha256::new(), password.as_bytes());
+    let mut key = vec![0; KEY_SIZE];
+
+    pbkdf2(&mut mac, salt, iterations, &mut key);
+
+    key
+}
+
+pub(crate) fn get_valid_key(key: &str) -> Vec<u8> {
     let mut bytes = key.as_bytes().to_vec();
 
     if bytes.len() < 16 {
@@ -63,40 +362,326 @@ fn get_iv(size: usize) -> Vec<u8> {
     iv
 }
 
-pub fn decrypt(iv_data_mac: &str, key: &str) -> Result<(bool, Vec<u8>), Box<dyn Error>> {
-    let (iv, data, mac) = split_iv_data_mac(iv_data_mac)?;
+/// Reads the public parts of an encrypted document without needing the password.
+pub fn inspect(iv_data_mac: impl AsRef<[u8]>) -> Result<FormatInfo, CryptoError> {
+    let parsed = parse(iv_data_mac.as_ref())?;
+
+    let data = match parsed.version {
+        CHUNKED_VERSION => &parsed.chunks[..parsed.chunks.len() - 1],
+        _ => &parsed.chunks[..],
+    };
+
+    Ok(FormatInfo {
+        version: parsed.version,
+        cipher: "AES-256-GCM",
+        kdf: parsed.kdf.describe(),
+        iv_size: IV_SIZE,
+        mac_size: MAC_SIZE,
+        ciphertext_size: data.iter().map(Sealed::size).sum(),
+    })
+}
 
-    let key = get_valid_key(key);
+pub fn decrypt(iv_data_mac: impl AsRef<[u8]>, key: &str) -> Result<Vec<u8>, CryptoError> {
+    let parsed = parse(iv_data_mac.as_ref())?;
 
-    let key_size = crypto::aes::KeySize::KeySize256;
+    let key = parsed.kdf.derive(key);
 
-    let mut decipher = AesGcm::new(key_size, &key, &iv, &[]);
+    if parsed.version != CHUNKED_VERSION {
+        return parsed.chunks[0]
+            .open(&key)
+            .ok_or(CryptoError::WrongPassword);
+    }
 
-    let mut dst: Vec<u8> = repeat(0).take(data.len()).collect();
+    // The MACs are checked first, as they're what tells a wrong password
+    // apart from a chunk that's been tampered with.
+    let (sealed_macs, chunks) = parsed
+        .chunks
+        .split_last()
+        .expect("Parsing checks there's one");
+    let macs = sealed_macs.open(&key).ok_or(CryptoError::WrongPassword)?;
+
+    if !macs
+        .chunks(MAC_SIZE)
+        .eq(chunks.iter().map(|chunk| &chunk.mac[..]))
+    {
+        return Err(CryptoError::CorruptData);
+    }
 
-    let result = decipher.decrypt(&data, &mut dst, &mac);
+    let mut plaintext = Vec::with_capacity(chunks.iter().map(Sealed::size).sum());
 
-    Ok((result, dst))
+    for chunk in chunks {
+        plaintext.extend(chunk.open(&key).ok_or(CryptoError::CorruptData)?);
+    }
+
+    Ok(plaintext)
 }
 
+/// Encrypts `data` in the current format version.
 pub fn encrypt(data: &[u8], password: &str) -> String {
+    // Hex doubles the size; the header and other fields are well under 200.
+    let mut encrypted = Vec::with_capacity(data.len() * 2 + 200);
+    encrypt_to(data, password, &mut encrypted).expect("Writing to a Vec can't fail");
+
+    String::from_utf8(encrypted).expect("The format is ASCII")
+}
+
+/// Like [`encrypt`], but writes the document to `writer` as the ciphertext
+/// is hex encoded, so a large document is never held as text in memory.
+pub fn encrypt_to(data: &[u8], password: &str, writer: &mut impl Write) -> io::Result<()> {
+    SessionKey::derive(password).encrypt_to(data, writer)
+}
+
+/// A key derived from a password with a salt of its own, kept so a document
+/// can be saved again without repeating the key derivation. Each save still
+/// gets a fresh IV. The key is wiped from memory when dropped.
+pub struct SessionKey {
+    password: String,
+    salt: Vec<u8>,
+    key: Vec<u8>,
+    /// The lines written for each chunk the last time a document was saved
+    /// in the chunked format, by the SHA-256 of the chunk's plaintext.
+    chunks: HashMap<[u8; 32], String>,
+}
+
+impl SessionKey {
+    pub fn derive(password: &str) -> Self {
+        let salt = get_iv(SALT_SIZE);
+        let key = derive_key(password, &salt, KDF_ITERATIONS);
+
+        Self {
+            password: password.to_string(),
+            salt,
+            key,
+            chunks: HashMap::new(),
+        }
+    }
+
+    fn is_for(&self, password: &str) -> bool {
+        self.password == password
+    }
+
+    /// Encrypts `data` in the current format version, or the chunked one if
+    /// it's at least [`CHUNKED_THRESHOLD`], writing it to `writer`.
+    pub fn encrypt_to(&mut self, data: &[u8], writer: &mut impl Write) -> io::Result<()> {
+        if data.len() >= CHUNKED_THRESHOLD {
+            return self.encrypt_chunked_to(data, writer);
+        }
+
+        let iv = get_iv(IV_SIZE);
+
+        let (encrypted, mac) = seal(&self.key, &iv, data);
+
+        write!(
+            writer,
+            "{}{}:{}/{}/{}/",
+            VERSION_PREFIX,
+            FORMAT_VERSION,
+            KDF_ITERATIONS,
+            hex::encode(&self.salt),
+            hex::encode(iv),
+        )?;
+
+        write_hex(&encrypted, writer)?;
+
+        write!(writer, "/{}", hex::encode(mac))
+    }
+
+    pub fn encrypt(&mut self, data: &[u8]) -> String {
+        let mut encrypted = Vec::with_capacity(data.len() * 2 + 200);
+        self.encrypt_to(data, &mut encrypted)
+            .expect("Writing to a Vec can't fail");
+
+        String::from_utf8(encrypted).expect("The format is ASCII")
+    }
+
+    fn encrypt_chunked_to(&mut self, data: &[u8], writer: &mut impl Write) -> io::Result<()> {
+        writeln!(
+            writer,
+            "{}{}:{}/{}",
+            VERSION_PREFIX,
+            CHUNKED_VERSION,
+            KDF_ITERATIONS,
+            hex::encode(&self.salt),
+        )?;
+
+        let mut previous = std::mem::take(&mut self.chunks);
+        let mut macs = vec![];
+
+        let mut boundary_key = hmac_sha256(&self.key, CHUNK_BOUNDARY_CONTEXT);
+        let chunks = split_chunks(data, &boundary_key);
+        boundary_key.zeroize();
+
+        for chunk in chunks {
+            let hash = sha256(chunk);
+
+            let line = match previous.remove(&hash) {
+                Some(line) => line,
+                None => {
+                    let iv = get_iv(IV_SIZE);
+                    let (encrypted, mac) = seal(&self.key, &iv, chunk);
+
+                    format!(
+                        "{}/{}/{}",
+                        hex::encode(iv),
+                        hex::encode(encrypted),
+                        hex::encode(mac)
+                    )
+                }
+            };
+
+            // The MAC is the last field of the line.
+            let mac = line.rsplit('/').next().expect("A line always has a MAC");
+            macs.extend(hex::decode(mac).expect("The line was written as hex"));
+
+            writeln!(writer, "{}", line)?;
+            self.chunks.insert(hash, line);
+        }
+
+        let iv = get_iv(IV_SIZE);
+        let (sealed, mac) = seal(&self.key, &iv, &macs);
+
+        write!(
+            writer,
+            "{}/{}/{}",
+            hex::encode(iv),
+            hex::encode(sealed),
+            hex::encode(mac)
+        )
+    }
+}
+
+/// Splits `data` after line breaks picked by [`CHUNK_BOUNDARY_ODDS`] and
+/// `key`, or anywhere once a chunk reaches [`MAX_CHUNK_SIZE`].
+fn split_chunks<'a>(data: &'a [u8], key: &[u8]) -> Vec<&'a [u8]> {
+    let mut chunks = vec![];
+    let (mut start, mut line_start) = (0, 0);
+
+    for (i, &byte) in data.iter().enumerate() {
+        let end = i + 1;
+        let size = end - start;
+
+        let at_boundary = byte == b'\n'
+            && size >= MIN_CHUNK_SIZE
+            && boundary_hash(key, &data[line_start..end]) % CHUNK_BOUNDARY_ODDS == 0;
+
+        if at_boundary || size >= MAX_CHUNK_SIZE {
+            chunks.push(&data[start..end]);
+            start = end;
+        }
+
+        if byte == b'\n' {
+            line_start = end;
+        }
+    }
+
+    if start < data.len() {
+        chunks.push(&data[start..]);
+    }
+
+    chunks
+}
+
+fn boundary_hash(key: &[u8], line: &[u8]) -> u32 {
+    let hash = hmac_sha256(key, line);
+
+    u32::from_le_bytes(hash[..4].try_into().expect("HMAC-SHA256 is 32 bytes"))
+}
+
+fn hmac_sha256(key: &[u8], message: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::new(Sha256::new(), key);
+    mac.input(message);
+
+    mac.result().code().to_vec()
+}
+
+fn sha256(bytes: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.input(bytes);
+
+    let mut hash = [0; 32];
+    hasher.result(&mut hash);
+
+    hash
+}
+
+impl Drop for SessionKey {
+    fn drop(&mut self) {
+        self.password.zeroize();
+        self.key.zeroize();
+    }
+}
+
+impl fmt::Debug for SessionKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SessionKey").finish_non_exhaustive()
+    }
+}
+
+/// The [`SessionKey`] of an open document, shared with the tasks saving it.
+/// A key is only reused for the password it was derived from, so changing
+/// the password can't save under the old one.
+#[derive(Debug, Clone, Default)]
+pub struct KeyCache(Arc<Mutex<Option<SessionKey>>>);
+
+impl KeyCache {
+    /// Encrypts `data`, deriving a key first unless one for `password` is
+    /// already cached.
+    pub fn encrypt(&self, data: &[u8], password: &str) -> String {
+        let mut cached = self.0.lock().unwrap_or_else(|error| error.into_inner());
+
+        if !cached.as_ref().is_some_and(|key| key.is_for(password)) {
+            *cached = Some(SessionKey::derive(password));
+        }
+
+        cached
+            .as_mut()
+            .expect("A key was just cached")
+            .encrypt(data)
+    }
+
+    /// Forgets the key, such as when the document is closed or locked.
+    pub fn clear(&self) {
+        *self.0.lock().unwrap_or_else(|error| error.into_inner()) = None;
+    }
+}
+
+/// Hex encodes `data` into `writer` a chunk at a time.
+fn write_hex(data: &[u8], writer: &mut impl Write) -> io::Result<()> {
+    let mut buffer = vec![0; 2 * HEX_CHUNK_SIZE];
+
+    for chunk in data.chunks(HEX_CHUNK_SIZE) {
+        let hex = &mut buffer[..2 * chunk.len()];
+        hex::encode_to_slice(chunk, hex).expect("The buffer fits the chunk");
+        writer.write_all(hex)?;
+    }
+
+    Ok(())
+}
+
+/// AES-GCM encryption of `data`, returning the ciphertext and the MAC.
+pub(crate) fn seal(key: &[u8], iv: &[u8], data: &[u8]) -> (Vec<u8>, Vec<u8>) {
     let key_size = crypto::aes::KeySize::KeySize256;
 
-    let valid_key = get_valid_key(password);
-    let iv = get_iv(12);
-    let mut cipher = AesGcm::new(key_size, &valid_key, &iv, &[]);
+    let mut cipher = AesGcm::new(key_size, key, iv, &[]);
 
     let mut encrypted: Vec<u8> = repeat(0).take(data.len()).collect();
 
-    let mut mac: Vec<u8> = repeat(0).take(16).collect();
+    let mut mac: Vec<u8> = repeat(0).take(MAC_SIZE).collect();
 
     cipher.encrypt(data, &mut encrypted, &mut mac[..]);
 
-    let hex_iv = hex::encode(iv);
-    let hex_cipher = hex::encode(encrypted);
-    let hex_mac = hex::encode(mac);
+    (encrypted, mac)
+}
+
+/// AES-GCM decryption of `data`. The flag is false if the MAC doesn't match.
+pub(crate) fn open(key: &[u8], iv: &[u8], data: &[u8], mac: &[u8]) -> (bool, Vec<u8>) {
+    let key_size = crypto::aes::KeySize::KeySize256;
+
+    let mut decipher = AesGcm::new(key_size, key, iv, &[]);
+
+    let mut dst: Vec<u8> = repeat(0).take(data.len()).collect();
 
-    let output = format!("{}/{}/{}", hex_iv, hex_cipher, hex_mac);
+    let result = decipher.decrypt(data, &mut dst, mac);
 
-    output
+    (result, dst)
 }
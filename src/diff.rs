@@ -0,0 +1,117 @@
+//! Line-by-line differences between two versions of a document, for
+//! comparing it with a backup and putting parts of the backup back.
+
+use std::ops::Range;
+
+/// Texts whose changed middle would need a table larger than this many
+/// cells are shown as one change, rather than diffed line by line.
+const MAX_CELLS: usize = 4_000_000;
+
+/// Lines that differ between the two texts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hunk {
+    /// The lines in the old text.
+    pub old: Range<usize>,
+    /// The lines in the new text that replaced them.
+    pub new: Range<usize>,
+}
+
+/// The changes from `old` to `new`, in order.
+pub fn diff(old: &str, new: &str) -> Vec<Hunk> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+
+    // Most edits are in one place, so only the lines between the common
+    // start and end need comparing.
+    let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let old_middle = &old[prefix..old.len() - suffix];
+    let new_middle = &new[prefix..new.len() - suffix];
+
+    if old_middle.is_empty() && new_middle.is_empty() {
+        return vec![];
+    }
+
+    let (n, m) = (old_middle.len(), new_middle.len());
+
+    if (n + 1).saturating_mul(m + 1) > MAX_CELLS {
+        return vec![Hunk {
+            old: prefix..prefix + n,
+            new: prefix..prefix + m,
+        }];
+    }
+
+    // The length of the longest common subsequence of the lines from each
+    // position onwards.
+    let width = m + 1;
+    let mut common = vec![0u32; (n + 1) * width];
+
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            common[i * width + j] = if old_middle[i] == new_middle[j] {
+                common[(i + 1) * width + j + 1] + 1
+            } else {
+                common[(i + 1) * width + j].max(common[i * width + j + 1])
+            };
+        }
+    }
+
+    let mut hunks = vec![];
+    let mut start = None;
+    let (mut i, mut j) = (0, 0);
+
+    while i < n || j < m {
+        if i < n && j < m && old_middle[i] == new_middle[j] {
+            if let Some((old_start, new_start)) = start.take() {
+                hunks.push(Hunk {
+                    old: prefix + old_start..prefix + i,
+                    new: prefix + new_start..prefix + j,
+                });
+            }
+
+            i += 1;
+            j += 1;
+        } else {
+            start.get_or_insert((i, j));
+
+            if j < m && (i == n || common[i * width + j + 1] >= common[(i + 1) * width + j]) {
+                j += 1;
+            } else {
+                i += 1;
+            }
+        }
+    }
+
+    if let Some((old_start, new_start)) = start {
+        hunks.push(Hunk {
+            old: prefix + old_start..prefix + n,
+            new: prefix + new_start..prefix + m,
+        });
+    }
+
+    hunks
+}
+
+/// `new` with the lines of `hunk` put back the way they were in `old`.
+pub fn restore(old: &str, new: &str, hunk: &Hunk) -> String {
+    let old: Vec<&str> = old.lines().collect();
+    let lines: Vec<&str> = new.lines().collect();
+
+    let mut restored = lines[..hunk.new.start].to_vec();
+    restored.extend_from_slice(&old[hunk.old.clone()]);
+    restored.extend_from_slice(&lines[hunk.new.end..]);
+
+    let mut text = restored.join("\n");
+
+    if new.ends_with('\n') {
+        text.push('\n');
+    }
+
+    text
+}
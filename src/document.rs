@@ -0,0 +1,312 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+
+use crate::armor;
+use crate::crypto::{decrypt, encrypt, CryptoError, KeyCache};
+#[cfg(feature = "openpgp")]
+use crate::file::write_file;
+use crate::file::{load_file, write_encrypted, Contents, FileError, DOCUMENT_EXTENSION};
+use crate::history::{self, PasswordHistory};
+use crate::migrate::backup_in_background;
+#[cfg(feature = "openpgp")]
+use crate::openpgp;
+use crate::redact::redact;
+use crate::text::{decode, Encoding, LineEnding};
+
+/// A decrypted document together with how its text was stored, so saving it
+/// again writes the same line endings back.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Document {
+    pub text: String,
+    /// The encoding the plaintext was read from. Documents are always
+    /// written as UTF-8.
+    pub encoding: Encoding,
+    pub line_ending: LineEnding,
+    pub container: Container,
+    /// The passwords the document had before, written back when it's saved.
+    pub history: PasswordHistory,
+}
+
+/// The kind of file a document is encrypted into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Container {
+    /// A `.cryptodoc` file, in the current format version.
+    CryptoDoc,
+    /// A passphrase-encrypted OpenPGP message that GnuPG can open.
+    #[cfg(feature = "openpgp")]
+    OpenPgp,
+}
+
+impl Container {
+    pub const ALL: &'static [Self] = &[
+        Self::CryptoDoc,
+        #[cfg(feature = "openpgp")]
+        Self::OpenPgp,
+    ];
+
+    /// The extension of a new file in this container.
+    pub fn extension(self) -> &'static str {
+        match self {
+            Container::CryptoDoc => DOCUMENT_EXTENSION,
+            #[cfg(feature = "openpgp")]
+            Container::OpenPgp => openpgp::EXTENSION,
+        }
+    }
+
+    /// Tells the container apart by the encrypted contents alone.
+    #[cfg_attr(not(feature = "openpgp"), allow(unused_variables))]
+    pub fn detect(encrypted: &str) -> Self {
+        #[cfg(feature = "openpgp")]
+        if openpgp::is_armored(encrypted) {
+            return Container::OpenPgp;
+        }
+
+        Container::CryptoDoc
+    }
+}
+
+impl fmt::Display for Container {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Container::CryptoDoc => "CryptoDoc",
+            #[cfg(feature = "openpgp")]
+            Container::OpenPgp => "OpenPGP",
+        }
+        .fmt(f)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DocumentError {
+    /// The password doesn't match, or the ciphertext was modified. AES-GCM
+    /// can't tell these apart.
+    WrongPassword,
+    /// The file isn't a CryptoDoc document.
+    BadFormat,
+    /// The file is a CryptoDoc document, but parts of it are damaged.
+    Corrupt,
+    /// The document was written by a newer version of CryptoDoc.
+    UnsupportedVersion(u32),
+    /// Too many wrong passwords were tried, see [`lockout`](crate::lockout).
+    /// It can be tried again from then.
+    Locked(DateTime<Utc>),
+    Io(io::ErrorKind),
+}
+
+impl fmt::Display for DocumentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DocumentError::WrongPassword => {
+                write!(f, "The password is incorrect or the document was modified.")
+            }
+            DocumentError::BadFormat => write!(f, "The file isn't a CryptoDoc document."),
+            DocumentError::Corrupt => write!(f, "The document is damaged."),
+            DocumentError::UnsupportedVersion(version) => write!(
+                f,
+                "The document uses format version {}, which this version of CryptoDoc can't read.",
+                version
+            ),
+            DocumentError::Locked(until) => write!(
+                f,
+                "Too many failed attempts. Try again after {}.",
+                until.format("%H:%M:%S UTC")
+            ),
+            DocumentError::Io(kind) => write!(f, "The operation failed: {}.", kind),
+        }
+    }
+}
+
+impl Error for DocumentError {}
+
+impl From<CryptoError> for DocumentError {
+    fn from(error: CryptoError) -> Self {
+        match error {
+            CryptoError::BadFormat => DocumentError::BadFormat,
+            CryptoError::WrongPassword => DocumentError::WrongPassword,
+            CryptoError::CorruptData => DocumentError::Corrupt,
+            CryptoError::UnsupportedVersion(version) => DocumentError::UnsupportedVersion(version),
+            CryptoError::Io(error) => DocumentError::Io(error.kind()),
+        }
+    }
+}
+
+impl From<FileError> for DocumentError {
+    fn from(error: FileError) -> Self {
+        match error {
+            FileError::IOFailed(kind) => DocumentError::Io(kind),
+            FileError::DialogClosed | FileError::Cancelled => {
+                DocumentError::Io(io::ErrorKind::Interrupted)
+            }
+        }
+    }
+}
+
+impl Document {
+    pub fn new(text: impl Into<String>) -> Self {
+        let text = text.into();
+
+        Self {
+            line_ending: LineEnding::detect(&text),
+            encoding: Encoding::Utf8,
+            container: Container::CryptoDoc,
+            history: PasswordHistory::default(),
+            text,
+        }
+    }
+
+    /// A copy with the redacted text replaced, for exporting. See
+    /// [`redact`](crate::redact).
+    pub fn redacted(&self) -> Self {
+        Self {
+            text: redact(&self.text).into_owned(),
+            ..self.clone()
+        }
+    }
+
+    /// Decrypts the contents of a `.cryptodoc` file, or of an armored
+    /// OpenPGP message. Either may be wrapped in [`armor`].
+    pub fn decrypt(encrypted: &str, password: &str) -> Result<Self, DocumentError> {
+        if armor::is_armored(encrypted) {
+            return Self::decrypt(&armor::dearmor(encrypted)?, password);
+        }
+
+        let container = Container::detect(encrypted);
+
+        let (plaintext, history) = match container {
+            Container::CryptoDoc => (decrypt(encrypted, password)?, history::read(encrypted)),
+            #[cfg(feature = "openpgp")]
+            Container::OpenPgp => (
+                openpgp::decrypt(encrypted, password)?,
+                PasswordHistory::default(),
+            ),
+        };
+
+        let (text, encoding) = decode(plaintext);
+
+        Ok(Self {
+            line_ending: LineEnding::detect(&text),
+            encoding,
+            container,
+            history,
+            text,
+        })
+    }
+
+    /// Like [`Document::decrypt`], but for a file as [`load_file`] read it.
+    /// A mapped document is decrypted from its bytes.
+    pub fn decrypt_contents(encrypted: &Contents, password: &str) -> Result<Self, DocumentError> {
+        let map = match encrypted {
+            Contents::Text(text) => return Self::decrypt(text, password),
+            Contents::Mapped(map) => map,
+        };
+
+        // Only CryptoDoc documents are mapped.
+        let (text, encoding) = decode(decrypt(&map[..], password)?);
+
+        Ok(Self {
+            line_ending: LineEnding::detect(&text),
+            encoding,
+            container: Container::CryptoDoc,
+            history: history::read(&encrypted.tail()),
+            text,
+        })
+    }
+
+    /// Encrypts the document into the contents of a file in its container.
+    pub fn encrypt(&self, password: &str) -> String {
+        let plaintext = self.line_ending.apply(&self.text);
+
+        match self.container {
+            Container::CryptoDoc => self
+                .history
+                .append_to(encrypt(plaintext.as_bytes(), password)),
+            #[cfg(feature = "openpgp")]
+            Container::OpenPgp => openpgp::encrypt(plaintext.as_bytes(), password),
+        }
+    }
+
+    /// Like [`Document::encrypt`], but reuses the key in `keys` if it was
+    /// derived from `password`, which saves repeating the key derivation.
+    pub fn encrypt_cached(&self, password: &str, keys: &KeyCache) -> String {
+        match self.container {
+            Container::CryptoDoc => self
+                .history
+                .append_to(keys.encrypt(self.line_ending.apply(&self.text).as_bytes(), password)),
+            #[cfg(feature = "openpgp")]
+            Container::OpenPgp => self.encrypt(password),
+        }
+    }
+
+    /// Encrypts the document as text that survives being pasted into an
+    /// email or chat. OpenPGP messages are already armored. The password
+    /// history stays behind, as it's no business of whoever receives it.
+    pub fn encrypt_armored(&self, password: &str) -> String {
+        match self.container {
+            Container::CryptoDoc => armor::armor(&encrypt(
+                self.line_ending.apply(&self.text).as_bytes(),
+                password,
+            )),
+            #[cfg(feature = "openpgp")]
+            Container::OpenPgp => self.encrypt(password),
+        }
+    }
+
+    /// Like [`Document::decrypt`], but on a blocking thread so a large
+    /// document doesn't hold up the async runtime it's awaited on.
+    pub async fn decrypt_in_background(
+        encrypted: Arc<Contents>,
+        password: String,
+    ) -> Result<Self, DocumentError> {
+        tokio::task::spawn_blocking(move || Self::decrypt_contents(&encrypted, &password))
+            .await
+            .map_err(|_| DocumentError::Io(io::ErrorKind::Other))?
+    }
+
+    /// Like [`Document::encrypt_cached`], but on a blocking thread.
+    pub async fn encrypt_in_background(
+        self,
+        password: String,
+        keys: KeyCache,
+    ) -> Result<String, DocumentError> {
+        tokio::task::spawn_blocking(move || self.encrypt_cached(&password, &keys))
+            .await
+            .map_err(|_| DocumentError::Io(io::ErrorKind::Other))
+    }
+
+    pub async fn open(path: PathBuf, password: &str) -> Result<Self, DocumentError> {
+        let (_, encrypted) = load_file(path).await?;
+
+        Self::decrypt_in_background(encrypted, password.to_string()).await
+    }
+
+    /// Encrypts the document into `path`. A document already there in an
+    /// older format is backed up first.
+    pub async fn save(&self, path: PathBuf, password: &str) -> Result<PathBuf, DocumentError> {
+        backup_in_background(path.clone()).await?;
+
+        match self.container {
+            Container::CryptoDoc => {
+                let plaintext = self.line_ending.apply(&self.text).into_bytes();
+
+                Ok(
+                    write_encrypted(path, plaintext, password.to_string(), self.history.clone())
+                        .await?,
+                )
+            }
+            #[cfg(feature = "openpgp")]
+            Container::OpenPgp => {
+                let encrypted = self
+                    .clone()
+                    .encrypt_in_background(password.to_string(), KeyCache::default())
+                    .await?;
+
+                Ok(write_file(path, encrypted).await?)
+            }
+        }
+    }
+}
@@ -1,20 +1,192 @@
-use std::io;
-use std::path::PathBuf;
+use std::borrow::Cow;
+use std::ffi::OsString;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::SystemTime;
+
+use memmap2::Mmap;
+
+use crate::crypto::{encrypt_to, header_version, HEADER_SIZE};
+use crate::history::PasswordHistory;
+#[cfg(feature = "openpgp")]
+use crate::openpgp;
+
+pub const DOCUMENT_EXTENSION: &str = "cryptodoc";
+
+/// Files are read and written this much at a time.
+const BUFFER_SIZE: usize = 64 * 1024;
+
+/// Files at least this large are mapped into memory rather than read, so
+/// opening one doesn't need a copy of it, and only the parts being decrypted
+/// are paged in.
+const MAP_THRESHOLD: u64 = 256 * 1024 * 1024;
+
+/// The contents of an encrypted file.
+#[derive(Debug)]
+pub enum Contents {
+    Text(String),
+    /// A large CryptoDoc document, mapped into memory. Another program can
+    /// change the file while it's mapped, so it's only ever parsed as bytes
+    /// and never handed out as text.
+    Mapped(Mmap),
+}
+
+impl Contents {
+    pub fn as_bytes(&self) -> &[u8] {
+        match self {
+            Contents::Text(text) => text.as_bytes(),
+            Contents::Mapped(map) => map,
+        }
+    }
+
+    /// The last lines, where the password history and lockout record are
+    /// kept. They're copied out of a mapped file.
+    pub fn tail(&self) -> Cow<'_, str> {
+        let map = match self {
+            Contents::Text(text) => return Cow::Borrowed(text),
+            Contents::Mapped(map) => map,
+        };
+
+        let end = map
+            .iter()
+            .rposition(|byte| !byte.is_ascii_whitespace())
+            .map_or(0, |last| last + 1);
+
+        // The lockout record, the password history and the line before
+        // them, which ends in the MAC the record is tied to.
+        let start = map[..end]
+            .iter()
+            .enumerate()
+            .rev()
+            .filter(|&(_, &byte)| byte == b'\n')
+            .nth(2)
+            .map_or(0, |(index, _)| index + 1);
+
+        Cow::Owned(String::from_utf8_lossy(&map[start..end]).into_owned())
+    }
+}
+
+impl From<String> for Contents {
+    fn from(text: String) -> Self {
+        Contents::Text(text)
+    }
+}
 
 #[derive(Debug, Clone)]
 pub enum FileError {
     DialogClosed,
+    /// The user cancelled a long-running operation.
+    Cancelled,
     IOFailed(io::ErrorKind),
 }
 
-pub fn get_file_path() -> io::Result<PathBuf> {
-    let save_path_content = std::fs::read_to_string("./save_path.dat")?;
-    Ok(PathBuf::from(save_path_content))
+impl fmt::Display for FileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FileError::DialogClosed => write!(f, "No file was selected."),
+            FileError::Cancelled => write!(f, "The operation was cancelled."),
+            FileError::IOFailed(io::ErrorKind::NotFound) => write!(
+                f,
+                "The file or folder doesn't exist. It may have been moved or deleted."
+            ),
+            FileError::IOFailed(io::ErrorKind::PermissionDenied) => write!(
+                f,
+                "Permission denied. Check that you have access to this location."
+            ),
+            FileError::IOFailed(io::ErrorKind::InvalidData) => write!(
+                f,
+                "The file isn't readable text. It may be corrupted or not a CryptoDoc document."
+            ),
+            FileError::IOFailed(kind) => write!(f, "The operation failed: {}.", kind),
+        }
+    }
 }
 
-pub fn get_save_file_path() -> PathBuf {
-    PathBuf::from("./save_path.dat")
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NameError {
+    Empty,
+    InvalidChar(char),
+    Reserved,
+    TrailingDotOrSpace,
+}
+
+const INVALID_NAME_CHARS: &[char] = &['/', '\\', ':', '*', '?', '"', '<', '>', '|'];
+
+// Windows refuses these as file names regardless of extension.
+const RESERVED_NAMES: &[&str] = &[
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+#[derive(Debug, Clone)]
+pub struct DocumentInfo {
+    pub path: PathBuf,
+    pub size: u64,
+    pub modified: Option<SystemTime>,
+}
+
+pub fn document_name(path: &PathBuf) -> String {
+    path.file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_else(|| pathbuf_to_string(path))
+}
+
+/// Checks that `name` can be used as a file name on every platform, since
+/// documents are often synced between machines.
+pub fn validate_document_name(name: &str) -> Result<(), NameError> {
+    if name.trim().is_empty() {
+        return Err(NameError::Empty);
+    }
+
+    if let Some(c) = name
+        .chars()
+        .find(|c| INVALID_NAME_CHARS.contains(c) || c.is_control())
+    {
+        return Err(NameError::InvalidChar(c));
+    }
+
+    if name.ends_with('.') || name.ends_with(' ') {
+        return Err(NameError::TrailingDotOrSpace);
+    }
+
+    let stem = name.split('.').next().unwrap_or(name);
+
+    if RESERVED_NAMES
+        .iter()
+        .any(|reserved| reserved.eq_ignore_ascii_case(stem.trim_end()))
+    {
+        return Err(NameError::Reserved);
+    }
+
+    Ok(())
+}
+
+/// Turns `name` into something [`validate_document_name`] accepts by
+/// replacing or trimming whatever it would reject. The result is empty if
+/// nothing usable was left, so callers can pick their own default.
+pub fn sanitize_document_name(name: &str) -> String {
+    let mut name: String = name
+        .trim()
+        .chars()
+        .map(|c| {
+            if INVALID_NAME_CHARS.contains(&c) || c.is_control() {
+                '_'
+            } else {
+                c
+            }
+        })
+        .collect();
+
+    name.truncate(name.trim_end_matches(['.', ' ']).len());
+
+    if validate_document_name(&name) == Err(NameError::Reserved) {
+        name.insert(0, '_');
+    }
+
+    name
 }
 
 pub fn pathbuf_to_string(path: &PathBuf) -> String {
@@ -23,51 +195,189 @@ pub fn pathbuf_to_string(path: &PathBuf) -> String {
         .to_string()
 }
 
-pub async fn load_file(path: PathBuf) -> Result<(PathBuf, Arc<String>), FileError> {
-    let contents = tokio::fs::read_to_string(&path)
-        .await
-        .map(Arc::new)
-        .map_err(|error| error.kind())
-        .map_err(FileError::IOFailed)?;
+/// Formats a size in bytes, like `1.5 MB`.
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
 
-    Ok((path, contents))
+    let mut size = bytes as f64;
+    let mut unit = 0;
+
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
 }
 
-pub async fn pick_file() -> Result<(PathBuf, Arc<String>), FileError> {
-    let handle = rfd::AsyncFileDialog::new()
-        .set_title("Select file")
-        .pick_file()
-        .await
-        .ok_or(FileError::DialogClosed)?;
+/// Reads an encrypted file. Binary OpenPGP messages are converted to ASCII
+/// armor, so every document can be handled as text.
+pub async fn load_file(path: PathBuf) -> Result<(PathBuf, Arc<Contents>), FileError> {
+    let contents = in_background({
+        let path = path.clone();
+        move || read_contents(&path)
+    })
+    .await?;
 
-    load_file(handle.path().to_owned()).await
+    Ok((path, Arc::new(contents)))
 }
 
-pub async fn pick_folder() -> Result<PathBuf, FileError> {
-    let handle = rfd::AsyncFileDialog::new()
-        .set_title("Select folder")
-        .pick_folder()
-        .await
-        .ok_or(FileError::DialogClosed)?;
+/// Replaces the file at `path` with `text`.
+pub async fn write_file(path: PathBuf, text: String) -> Result<PathBuf, FileError> {
+    write_with(path, move |writer| writer.write_all(text.as_bytes())).await
+}
 
-    Ok(handle.path().to_owned())
+/// Encrypts `plaintext` straight into the file at `path`, followed by its
+/// password history, without building the whole encrypted document in
+/// memory first.
+pub async fn write_encrypted(
+    path: PathBuf,
+    plaintext: Vec<u8>,
+    password: String,
+    history: PasswordHistory,
+) -> Result<PathBuf, FileError> {
+    write_with(path, move |writer| {
+        encrypt_to(&plaintext, &password, writer)?;
+        history.write_to(writer)
+    })
+    .await
 }
 
-pub async fn save_file(path: Option<PathBuf>, text: String) -> Result<PathBuf, FileError> {
-    let path = if let Some(path) = path {
-        path
-    } else {
-        rfd::AsyncFileDialog::new()
-            .set_title("Choose a file")
-            .save_file()
-            .await
-            .ok_or(FileError::DialogClosed)
-            .map(|handle| handle.path().to_owned())?
-    };
-
-    tokio::fs::write(&path, text)
+async fn write_with<F>(path: PathBuf, write: F) -> Result<PathBuf, FileError>
+where
+    F: FnOnce(&mut BufWriter<File>) -> io::Result<()> + Send + 'static,
+{
+    in_background(move || replace_with(&path, write).map(|()| path)).await
+}
+
+/// Replaces the file at `path` with whatever `write` writes. It goes to a
+/// hidden file next to it first, is flushed to disk and only then renamed
+/// over it, so a crash, power cut or full disk never leaves a half-written
+/// document behind.
+pub fn replace_with<F>(path: &Path, write: F) -> io::Result<()>
+where
+    F: FnOnce(&mut BufWriter<File>) -> io::Result<()>,
+{
+    let mut name = OsString::from(".");
+    name.push(path.file_name().unwrap_or_default());
+    name.push(".tmp");
+    let temporary = path.with_file_name(name);
+
+    let result = File::create(&temporary).and_then(|file| {
+        let mut writer = BufWriter::with_capacity(BUFFER_SIZE, file);
+        write(&mut writer)?;
+
+        writer.into_inner()?.sync_all()?;
+        fs::rename(&temporary, path)?;
+        sync_folder(path)
+    });
+
+    if result.is_err() {
+        let _ = fs::remove_file(&temporary);
+    }
+
+    result
+}
+
+/// Flushes the folder holding `path`, so a rename into it survives a power
+/// cut. Windows has no way to do this, and doesn't need one.
+fn sync_folder(path: &Path) -> io::Result<()> {
+    #[cfg(unix)]
+    {
+        let folder = match path.parent() {
+            Some(folder) if !folder.as_os_str().is_empty() => folder,
+            _ => Path::new("."),
+        };
+
+        File::open(folder)?.sync_all()?;
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+
+    Ok(())
+}
+
+fn read_contents(path: &Path) -> io::Result<Contents> {
+    let file = File::open(path)?;
+    let size = file.metadata().map_or(0, |metadata| metadata.len());
+
+    if size >= MAP_THRESHOLD {
+        // SAFETY: Another program truncating the file while it's mapped
+        // would crash the app, which is the price of not copying it. The
+        // map is only read as bytes, see `Contents::Mapped`.
+        let map = unsafe { Mmap::map(&file)? };
+        let header = String::from_utf8_lossy(&map[..map.len().min(HEADER_SIZE)]);
+
+        // Only CryptoDoc documents are mapped. Anything else, like an
+        // OpenPGP message, is read as usual.
+        if header_version(&header).is_some() {
+            return Ok(Contents::Mapped(map));
+        }
+    }
+
+    let mut bytes = Vec::with_capacity(size as usize);
+    BufReader::with_capacity(BUFFER_SIZE, file).read_to_end(&mut bytes)?;
+
+    let invalid = || io::Error::from(io::ErrorKind::InvalidData);
+
+    match String::from_utf8(bytes) {
+        Ok(text) => Ok(Contents::Text(text)),
+        #[cfg(feature = "openpgp")]
+        Err(error) if openpgp::is_binary(error.as_bytes()) => openpgp::armor(error.as_bytes())
+            .map(Contents::Text)
+            .map_err(|_| invalid()),
+        Err(_) => Err(invalid()),
+    }
+}
+
+/// Runs blocking file I/O off the async runtime.
+async fn in_background<T, F>(f: F) -> Result<T, FileError>
+where
+    T: Send + 'static,
+    F: FnOnce() -> io::Result<T> + Send + 'static,
+{
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(|_| FileError::IOFailed(io::ErrorKind::Other))?
+        .map_err(|error| FileError::IOFailed(error.kind()))
+}
+
+pub async fn list_documents(folder: PathBuf) -> Result<Vec<DocumentInfo>, FileError> {
+    let mut entries = tokio::fs::read_dir(&folder)
         .await
         .map_err(|error| FileError::IOFailed(error.kind()))?;
 
-    Ok(path)
+    let mut documents = vec![];
+
+    while let Some(entry) = entries
+        .next_entry()
+        .await
+        .map_err(|error| FileError::IOFailed(error.kind()))?
+    {
+        let path = entry.path();
+
+        if path
+            .extension()
+            .map_or(false, |ext| ext == DOCUMENT_EXTENSION)
+        {
+            let metadata = entry
+                .metadata()
+                .await
+                .map_err(|error| FileError::IOFailed(error.kind()))?;
+
+            documents.push(DocumentInfo {
+                path,
+                size: metadata.len(),
+                modified: metadata.modified().ok(),
+            });
+        }
+    }
+
+    documents.sort_by(|a, b| a.path.cmp(&b.path));
+
+    Ok(documents)
 }
@@ -0,0 +1,228 @@
+//! Import and export formats for plaintext.
+//!
+//! Each format lives in its own module and is listed once in [`EXPORTERS`],
+//! [`IMPORTERS`], [`ARCHIVE_IMPORTERS`] or [`ARCHIVE_EXPORTERS`], which is
+//! all it takes for it to show up in the export and import menus and the
+//! command line.
+
+use std::error::Error;
+use std::fmt;
+use std::time::SystemTime;
+
+use crate::Document;
+
+mod bitwarden;
+mod html;
+#[cfg(feature = "keepass")]
+mod keepass;
+mod plain;
+mod standard_notes;
+
+pub use bitwarden::Bitwarden;
+pub use html::Html;
+#[cfg(feature = "keepass")]
+pub use keepass::KeePass;
+pub use plain::PlainText;
+pub use standard_notes::StandardNotes;
+
+pub static EXPORTERS: &[&dyn Exporter] = &[&PlainText, &Html];
+pub static IMPORTERS: &[&dyn Importer] = &[&PlainText];
+pub static ARCHIVE_IMPORTERS: &[&dyn ArchiveImporter] = &[
+    &StandardNotes,
+    &Bitwarden,
+    #[cfg(feature = "keepass")]
+    &KeePass,
+];
+pub static ARCHIVE_EXPORTERS: &[&dyn ArchiveExporter] = &[&StandardNotes];
+
+/// Turns a decrypted document into a file in some other format. Exports go
+/// through [`export`], so redacted text never reaches an exporter.
+pub trait Exporter: Sync {
+    /// A short, stable name used on the command line, such as `html`.
+    fn id(&self) -> &'static str;
+    fn name(&self) -> &'static str;
+    fn extension(&self) -> &'static str;
+    fn export(&self, document: &Document) -> Result<Vec<u8>, FormatError>;
+}
+
+/// Reads a file in some other format into a document.
+pub trait Importer: Sync {
+    /// A short, stable name used on the command line, such as `text`.
+    fn id(&self) -> &'static str;
+    fn name(&self) -> &'static str;
+    fn extensions(&self) -> &'static [&'static str];
+    fn import(&self, bytes: Vec<u8>) -> Result<Document, FormatError>;
+}
+
+/// One document out of a file that holds many.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NamedDocument {
+    /// The name the other app gave it, which may not be a valid file name.
+    pub name: String,
+    pub document: Document,
+    /// When it was last changed, if the format keeps track.
+    pub modified: Option<SystemTime>,
+    /// Labels the other app filed it under, like a folder name.
+    pub tags: Vec<String>,
+}
+
+/// Reads a file holding many documents, like a password manager database
+/// or another app's backup, into one document each.
+pub trait ArchiveImporter: Sync {
+    /// A short, stable name used on the command line, such as `keepass`.
+    fn id(&self) -> &'static str;
+    fn name(&self) -> &'static str;
+    fn extensions(&self) -> &'static [&'static str];
+    /// Whether the file is encrypted, so [`ArchiveImporter::import`] needs
+    /// its password.
+    fn needs_password(&self) -> bool;
+    fn import(
+        &self,
+        bytes: &[u8],
+        password: Option<&str>,
+    ) -> Result<Vec<NamedDocument>, FormatError>;
+}
+
+/// Writes many documents into one file another app can read, like a backup
+/// it can restore. Exports go through [`export_archive`].
+pub trait ArchiveExporter: Sync {
+    /// A short, stable name used on the command line, such as
+    /// `standard-notes`.
+    fn id(&self) -> &'static str;
+    fn name(&self) -> &'static str;
+    fn extension(&self) -> &'static str;
+    fn export(&self, documents: &[NamedDocument]) -> Result<Vec<u8>, FormatError>;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormatError(pub String);
+
+impl fmt::Display for FormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl Error for FormatError {}
+
+/// Exports `document` with its redacted text replaced.
+pub fn export(exporter: &dyn Exporter, document: &Document) -> Result<Vec<u8>, FormatError> {
+    exporter.export(&document.redacted())
+}
+
+/// Exports `documents` with their redacted text replaced.
+pub fn export_archive(
+    exporter: &dyn ArchiveExporter,
+    documents: &[NamedDocument],
+) -> Result<Vec<u8>, FormatError> {
+    let documents: Vec<NamedDocument> = documents
+        .iter()
+        .map(|named| NamedDocument {
+            document: named.document.redacted(),
+            ..named.clone()
+        })
+        .collect();
+
+    exporter.export(&documents)
+}
+
+pub fn exporter(id: &str) -> Option<&'static dyn Exporter> {
+    EXPORTERS
+        .iter()
+        .copied()
+        .find(|exporter| exporter.id() == id)
+}
+
+pub fn importer(id: &str) -> Option<&'static dyn Importer> {
+    IMPORTERS
+        .iter()
+        .copied()
+        .find(|importer| importer.id() == id)
+}
+
+pub fn archive_importer(id: &str) -> Option<&'static dyn ArchiveImporter> {
+    ARCHIVE_IMPORTERS
+        .iter()
+        .copied()
+        .find(|importer| importer.id() == id)
+}
+
+pub fn archive_exporter(id: &str) -> Option<&'static dyn ArchiveExporter> {
+    ARCHIVE_EXPORTERS
+        .iter()
+        .copied()
+        .find(|exporter| exporter.id() == id)
+}
+
+// Formats are compared by id so they can be picked from a list.
+impl PartialEq for dyn Exporter {
+    fn eq(&self, other: &Self) -> bool {
+        self.id() == other.id()
+    }
+}
+
+impl fmt::Debug for dyn Exporter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Exporter").field(&self.id()).finish()
+    }
+}
+
+impl fmt::Display for dyn Exporter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.name().fmt(f)
+    }
+}
+
+impl PartialEq for dyn Importer {
+    fn eq(&self, other: &Self) -> bool {
+        self.id() == other.id()
+    }
+}
+
+impl fmt::Debug for dyn Importer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Importer").field(&self.id()).finish()
+    }
+}
+
+impl fmt::Display for dyn Importer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.name().fmt(f)
+    }
+}
+
+impl PartialEq for dyn ArchiveImporter {
+    fn eq(&self, other: &Self) -> bool {
+        self.id() == other.id()
+    }
+}
+
+impl fmt::Debug for dyn ArchiveImporter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ArchiveImporter").field(&self.id()).finish()
+    }
+}
+
+impl fmt::Display for dyn ArchiveImporter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.name().fmt(f)
+    }
+}
+
+impl PartialEq for dyn ArchiveExporter {
+    fn eq(&self, other: &Self) -> bool {
+        self.id() == other.id()
+    }
+}
+
+impl fmt::Debug for dyn ArchiveExporter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ArchiveExporter").field(&self.id()).finish()
+    }
+}
+
+impl fmt::Display for dyn ArchiveExporter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.name().fmt(f)
+    }
+}
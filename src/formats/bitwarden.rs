@@ -0,0 +1,107 @@
+use std::collections::HashMap;
+use std::time::SystemTime;
+
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+use crate::formats::{ArchiveImporter, FormatError, NamedDocument};
+use crate::Document;
+
+/// Bitwarden's item type for secure notes. Logins, cards and identities are
+/// better left in the password manager.
+const SECURE_NOTE: u8 = 2;
+
+/// An unencrypted Bitwarden JSON export. Each secure note becomes a
+/// document, tagged with the folder it was in.
+pub struct Bitwarden;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Export {
+    #[serde(default)]
+    encrypted: bool,
+    #[serde(default)]
+    folders: Vec<Folder>,
+    #[serde(default)]
+    items: Vec<Item>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Folder {
+    id: String,
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Item {
+    #[serde(rename = "type")]
+    kind: u8,
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    notes: Option<String>,
+    #[serde(default)]
+    folder_id: Option<String>,
+    #[serde(default)]
+    revision_date: Option<DateTime<Utc>>,
+    #[serde(default)]
+    deleted_date: Option<DateTime<Utc>>,
+}
+
+impl ArchiveImporter for Bitwarden {
+    fn id(&self) -> &'static str {
+        "bitwarden"
+    }
+
+    fn name(&self) -> &'static str {
+        "Bitwarden"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["json"]
+    }
+
+    fn needs_password(&self) -> bool {
+        false
+    }
+
+    fn import(
+        &self,
+        bytes: &[u8],
+        _password: Option<&str>,
+    ) -> Result<Vec<NamedDocument>, FormatError> {
+        let export: Export = serde_json::from_slice(bytes)
+            .map_err(|error| FormatError(format!("not a Bitwarden JSON export: {}", error)))?;
+
+        if export.encrypted {
+            return Err(FormatError(
+                "the export is encrypted; export as unencrypted JSON".to_string(),
+            ));
+        }
+
+        let folders: HashMap<String, String> = export
+            .folders
+            .into_iter()
+            .map(|folder| (folder.id, folder.name))
+            .collect();
+
+        let documents = export
+            .items
+            .into_iter()
+            .filter(|item| item.kind == SECURE_NOTE && item.deleted_date.is_none())
+            .map(|item| NamedDocument {
+                tags: item
+                    .folder_id
+                    .and_then(|id| folders.get(&id).cloned())
+                    .into_iter()
+                    .collect(),
+                name: item.name,
+                document: Document::new(item.notes.unwrap_or_default()),
+                modified: item.revision_date.map(SystemTime::from),
+            })
+            .collect();
+
+        Ok(documents)
+    }
+}
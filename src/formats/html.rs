@@ -0,0 +1,49 @@
+use crate::formats::{Exporter, FormatError};
+use crate::Document;
+
+/// A standalone page with the text preformatted, so it prints and displays
+/// exactly as written.
+pub struct Html;
+
+impl Exporter for Html {
+    fn id(&self) -> &'static str {
+        "html"
+    }
+
+    fn name(&self) -> &'static str {
+        "HTML"
+    }
+
+    fn extension(&self) -> &'static str {
+        "html"
+    }
+
+    fn export(&self, document: &Document) -> Result<Vec<u8>, FormatError> {
+        let html = format!(
+            "<!DOCTYPE html>\n\
+             <html>\n\
+             <head>\n<meta charset=\"utf-8\">\n</head>\n\
+             <body>\n<pre>{}</pre>\n</body>\n\
+             </html>\n",
+            escape(&document.text)
+        );
+
+        Ok(document.line_ending.apply(&html).into_bytes())
+    }
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
@@ -0,0 +1,99 @@
+use keepass::db::NodeRef;
+use keepass::{Database, DatabaseKey};
+
+use crate::formats::{ArchiveImporter, FormatError, NamedDocument};
+use crate::Document;
+
+/// Fields every entry has, written out in this order before any custom ones.
+const STANDARD_FIELDS: [(&str, &str); 3] = [
+    ("UserName", "Username"),
+    ("Password", "Password"),
+    ("URL", "URL"),
+];
+
+/// A KeePass 2 database. Each entry becomes a document with its fields at
+/// the top and its notes below them.
+pub struct KeePass;
+
+impl ArchiveImporter for KeePass {
+    fn id(&self) -> &'static str {
+        "keepass"
+    }
+
+    fn name(&self) -> &'static str {
+        "KeePass"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["kdbx"]
+    }
+
+    fn needs_password(&self) -> bool {
+        true
+    }
+
+    fn import(
+        &self,
+        mut bytes: &[u8],
+        password: Option<&str>,
+    ) -> Result<Vec<NamedDocument>, FormatError> {
+        let key = DatabaseKey::new().with_password(password.unwrap_or_default());
+        let database =
+            Database::open(&mut bytes, key).map_err(|error| FormatError(error.to_string()))?;
+
+        let documents = (&database.root)
+            .into_iter()
+            .filter_map(|node| match node {
+                NodeRef::Entry(entry) => Some(entry),
+                NodeRef::Group(_) => None,
+            })
+            .map(|entry| {
+                let mut lines: Vec<String> = STANDARD_FIELDS
+                    .iter()
+                    .filter_map(|(field, label)| {
+                        entry
+                            .get(field)
+                            .filter(|value| !value.is_empty())
+                            .map(|value| format!("{}: {}", label, value))
+                    })
+                    .collect();
+
+                let mut custom: Vec<&String> = entry
+                    .fields
+                    .keys()
+                    .filter(|field| {
+                        !matches!(field.as_str(), "Title" | "Notes")
+                            && !STANDARD_FIELDS
+                                .iter()
+                                .any(|(standard, _)| standard == field)
+                    })
+                    .collect();
+                custom.sort();
+
+                lines.extend(custom.into_iter().filter_map(|field| {
+                    entry
+                        .get(field)
+                        .filter(|value| !value.is_empty())
+                        .map(|value| format!("{}: {}", field, value))
+                }));
+
+                if let Some(notes) = entry.get("Notes").filter(|notes| !notes.is_empty()) {
+                    if !lines.is_empty() {
+                        lines.push(String::new());
+                    }
+
+                    lines.push(notes.to_string());
+                }
+
+                NamedDocument {
+                    name: entry.get_title().unwrap_or_default().to_string(),
+                    document: Document::new(lines.join("\n")),
+                    modified: None,
+                    tags: vec![],
+                }
+            })
+            .collect();
+
+        Ok(documents)
+    }
+}
@@ -0,0 +1,47 @@
+use crate::formats::{Exporter, FormatError, Importer};
+use crate::text::decode;
+use crate::Document;
+
+/// The document text as it is, with its own line endings.
+pub struct PlainText;
+
+impl Exporter for PlainText {
+    fn id(&self) -> &'static str {
+        "text"
+    }
+
+    fn name(&self) -> &'static str {
+        "Text"
+    }
+
+    fn extension(&self) -> &'static str {
+        "txt"
+    }
+
+    fn export(&self, document: &Document) -> Result<Vec<u8>, FormatError> {
+        Ok(document.line_ending.apply(&document.text).into_bytes())
+    }
+}
+
+impl Importer for PlainText {
+    fn id(&self) -> &'static str {
+        "text"
+    }
+
+    fn name(&self) -> &'static str {
+        "Text"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["txt", "md", "text"]
+    }
+
+    fn import(&self, bytes: Vec<u8>) -> Result<Document, FormatError> {
+        let (text, encoding) = decode(bytes);
+
+        Ok(Document {
+            encoding,
+            ..Document::new(text)
+        })
+    }
+}
@@ -0,0 +1,170 @@
+use std::time::SystemTime;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::formats::{ArchiveExporter, ArchiveImporter, FormatError, NamedDocument};
+use crate::Document;
+
+/// The backup format version written by current Standard Notes apps.
+const BACKUP_VERSION: &str = "004";
+
+/// A decrypted Standard Notes backup. Only notes are read; tags, settings
+/// and anything in the trash are skipped.
+pub struct StandardNotes;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Backup {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    version: Option<String>,
+    items: Vec<Item>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Item {
+    uuid: String,
+    content_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    created_at: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    updated_at: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing)]
+    deleted: bool,
+    // Encrypted backups have a string here instead.
+    content: serde_json::Value,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct NoteContent {
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    text: String,
+    #[serde(default, skip_serializing)]
+    trashed: bool,
+    #[serde(default)]
+    references: Vec<serde_json::Value>,
+    #[serde(default)]
+    app_data: serde_json::Map<String, serde_json::Value>,
+}
+
+impl ArchiveImporter for StandardNotes {
+    fn id(&self) -> &'static str {
+        "standard-notes"
+    }
+
+    fn name(&self) -> &'static str {
+        "Standard Notes"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["txt", "json"]
+    }
+
+    fn needs_password(&self) -> bool {
+        false
+    }
+
+    fn import(
+        &self,
+        bytes: &[u8],
+        _password: Option<&str>,
+    ) -> Result<Vec<NamedDocument>, FormatError> {
+        let backup: Backup = serde_json::from_slice(bytes)
+            .map_err(|error| FormatError(format!("not a Standard Notes backup: {}", error)))?;
+
+        let mut documents = vec![];
+
+        for item in backup.items {
+            if item.content_type != "Note" || item.deleted {
+                continue;
+            }
+
+            let content: NoteContent = serde_json::from_value(item.content).map_err(|_| {
+                FormatError("the backup is encrypted; export a decrypted backup".to_string())
+            })?;
+
+            if content.trashed {
+                continue;
+            }
+
+            documents.push(NamedDocument {
+                name: content.title,
+                document: Document::new(content.text),
+                modified: item.updated_at.map(SystemTime::from),
+                tags: vec![],
+            });
+        }
+
+        Ok(documents)
+    }
+}
+
+impl ArchiveExporter for StandardNotes {
+    fn id(&self) -> &'static str {
+        "standard-notes"
+    }
+
+    fn name(&self) -> &'static str {
+        "Standard Notes"
+    }
+
+    fn extension(&self) -> &'static str {
+        "json"
+    }
+
+    fn export(&self, documents: &[NamedDocument]) -> Result<Vec<u8>, FormatError> {
+        let items = documents
+            .iter()
+            .map(|named| {
+                let modified = named
+                    .modified
+                    .map(DateTime::<Utc>::from)
+                    .unwrap_or_else(Utc::now);
+
+                let content = NoteContent {
+                    title: named.name.clone(),
+                    text: named.document.text.clone(),
+                    ..NoteContent::default()
+                };
+
+                Ok(Item {
+                    uuid: random_uuid(),
+                    content_type: "Note".to_string(),
+                    created_at: Some(modified),
+                    updated_at: Some(modified),
+                    deleted: false,
+                    content: serde_json::to_value(content)
+                        .map_err(|error| FormatError(error.to_string()))?,
+                })
+            })
+            .collect::<Result<_, FormatError>>()?;
+
+        let backup = Backup {
+            version: Some(BACKUP_VERSION.to_string()),
+            items,
+        };
+
+        serde_json::to_vec_pretty(&backup).map_err(|error| FormatError(error.to_string()))
+    }
+}
+
+/// A random (version 4) UUID, which is all Standard Notes needs to tell
+/// items apart.
+fn random_uuid() -> String {
+    let mut bytes: [u8; 16] = rand::random();
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+
+    let hex = hex::encode(bytes);
+
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+    )
+}
@@ -0,0 +1,6707 @@
+mod audit;
+mod browser;
+mod capture;
+mod checklist;
+mod compare;
+mod config;
+mod dialogs;
+mod error;
+mod hook;
+mod i18n;
+mod icons;
+mod images;
+mod index;
+mod instance;
+mod journal;
+mod keypad;
+mod links;
+mod logging;
+mod merge;
+mod modal;
+mod outline;
+mod pager;
+mod profile;
+mod progress;
+mod properties;
+mod qr;
+mod recovery;
+mod report;
+mod retention;
+mod search;
+mod secrets;
+mod session;
+mod stats;
+mod toast;
+mod toolbar;
+mod tray;
+mod update;
+mod vaults;
+
+use std::collections::{BTreeSet, HashSet};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use audit::AuditView;
+use browser::{format_age, format_size, sorted_documents, SortOrder};
+use capture::Capture;
+use checklist::{checklist, parse_line, remaining, ChecklistItem};
+use chrono::{DateTime, Local, NaiveDate, Utc};
+use compare::{find_revisions, Compare, Revision};
+use config::{save_config, Config, MAX_UI_SCALE, MIN_UI_SCALE, TAB_WIDTHS};
+use dialogs::{
+    export_archive, export_armored, export_file, import_file, pick_archive, pick_file, pick_folder,
+    pick_image, pick_profile, pick_profile_destination, pick_profile_folder, save_file,
+};
+use error::ErrorReport;
+use hook::{run_post_save, HookError};
+use i18n::{name_error, tr, tr_with, Language};
+use icons::{action, home_icon, new_icon, open_icon, save_icon, settings_icon};
+use images::{find_images, EmbeddedImage, MAX_IMAGE_SIZE};
+use index::{save_index, DocumentMeta, Label, LabelColor, VaultIndex};
+use journal::Calendar;
+use keypad::Keypad;
+use links::{find_backlinks, links};
+use merge::Merge;
+use modal::modal;
+use outline::{outline, Heading};
+use pager::{Pager, LARGE_DOCUMENT_SIZE};
+use profile::Imported;
+use progress::{Operation, Progress};
+use properties::{format_now, format_timestamp, load_properties, DocumentProperties};
+use recovery::Recovery;
+use report::{build_report, save_report, ReportFormat};
+use retention::{Retained, Retention};
+use search::{search_documents, SearchHit};
+use secrets::Field;
+use session::SessionEvent;
+use stats::TextStats;
+use toast::{Status, Toast};
+use toolbar::ToolbarAction;
+use tray::TrayAction;
+use update::{Release, ReleaseChannel};
+use vaults::{check_password, hash_password, vault_prompt_id, VaultPrompt};
+
+use cryptodoc::armor;
+use cryptodoc::audit::{Entry as AuditEntry, Event as AuditEvent};
+use cryptodoc::bench;
+use cryptodoc::crypto::{format_version, inspect, KeyCache, FORMAT_VERSION};
+use cryptodoc::file::{
+    document_name, list_documents, load_file, pathbuf_to_string, sanitize_document_name,
+    validate_document_name, Contents, DocumentInfo, FileError, NameError,
+};
+use cryptodoc::formats::{
+    ArchiveExporter, ArchiveImporter, Exporter, FormatError, Importer, NamedDocument,
+    ARCHIVE_EXPORTERS, ARCHIVE_IMPORTERS, EXPORTERS, IMPORTERS,
+};
+use cryptodoc::history::PasswordHistory;
+use cryptodoc::lockout::{self, Failures};
+use cryptodoc::merge::Side;
+use cryptodoc::migrate::backup_in_background;
+use cryptodoc::redact;
+use cryptodoc::selftest;
+use cryptodoc::share;
+use cryptodoc::storage::Backend;
+use cryptodoc::table::{SecretRow, SecretTable};
+use cryptodoc::text::{Encoding, LineEnding};
+use cryptodoc::totp::{find_seeds, Totp};
+use cryptodoc::{Container, Document, DocumentError, Vault};
+use iced::clipboard;
+use iced::event::{self, Event};
+use iced::font;
+use iced::futures::SinkExt;
+use iced::highlighter;
+use iced::keyboard;
+use iced::mouse;
+use iced::widget::{
+    button, checkbox, column, container, focus_next, focus_previous, horizontal_space, pick_list,
+    progress_bar, row, scrollable, slider, text, text_editor, text_input,
+};
+use iced::window;
+use iced::Alignment;
+use iced::Color;
+use iced::Theme;
+use iced::{Element, Font, Length, Size, Subscription, Task};
+use image::GenericImageView;
+use zeroize::Zeroize;
+
+/// Loaded once the first frame is up, as icons missing for a moment beat a
+/// slower start.
+static ICON_FONT: &[u8] = include_bytes!("../assets/icons.ttf");
+
+/// Opens the editor, with `path` loaded if it's given.
+pub fn run(path: Option<PathBuf>) -> iced::Result {
+    if !instance::acquire(path) {
+        return Ok(());
+    }
+
+    static ICON: &[u8] = include_bytes!("../assets/app_icon.png");
+
+    // Decoded while logging starts up.
+    let decode_icon = std::thread::spawn(|| {
+        let image = image::load_from_memory(ICON).unwrap();
+        let (width, height) = image.dimensions();
+
+        (image.into_rgba8().into_raw(), width, height)
+    });
+
+    let _log = logging::init();
+    tracing::info!(version = env!("CARGO_PKG_VERSION"), "started");
+
+    let (rgba, width, height) = decode_icon.join().expect("Decoding the icon panicked");
+    let icon = window::icon::from_rgba(rgba.clone(), width, height).unwrap();
+
+    let win_settings = window::Settings {
+        icon: Some(icon),
+        exit_on_close_request: false,
+        ..Default::default()
+    };
+
+    let _tray = tray::spawn(rgba, width, height);
+    let _hotkey = capture::register();
+
+    iced::application("CryptoDoc", CryptoDoc::update, CryptoDoc::view)
+        .subscription(CryptoDoc::subscription)
+        .theme(CryptoDoc::theme)
+        .scale_factor(CryptoDoc::scale_factor)
+        .window(win_settings)
+        .window_size((900.0, 700.0))
+        .run()
+}
+
+/// Fires once, when the app is up and running.
+fn started() -> Subscription<Message> {
+    struct Started;
+
+    iced::subscription::channel(
+        std::any::TypeId::of::<Started>(),
+        1,
+        |mut output| async move {
+            let _ = output.send(Message::Started).await;
+
+            loop {
+                std::future::pending::<()>().await;
+            }
+        },
+    )
+}
+
+struct CryptoDoc {
+    current_page: Page,
+    content: text_editor::Content,
+    /// Shown instead of the editor for a document too large to edit.
+    pager: Option<Pager>,
+    audit: AuditView,
+    /// Whether an exported profile takes the vault's documents along.
+    profile_documents: bool,
+    prompt: Option<PasswordPrompt>,
+    overwrite: Option<OverwritePrompt>,
+    reauth: Option<ReauthPrompt>,
+    /// The toolbar action with keyboard focus, see [`toolbar`].
+    toolbar_focus: Option<ToolbarAction>,
+    vault_prompt: Option<VaultPrompt>,
+    /// Whether removing the active vault profile is waiting to be confirmed.
+    vault_removal: bool,
+    /// The name typed for a new vault profile in Settings.
+    new_vault_name: String,
+    /// The password typed in Settings for the active vault profile.
+    vault_password: String,
+    external_edit: Option<ExternalEditPrompt>,
+    archive_import: Option<ArchivePrompt>,
+    archive_export: Option<ArchiveExportPrompt>,
+    journal_prompt: Option<JournalPrompt>,
+    clipboard_prompt: Option<ClipboardPrompt>,
+    share: Option<SharePrompt>,
+    calendar: Option<Calendar>,
+    capture: Option<Capture>,
+    /// The inbox's password, once a capture has been saved to it. Forgotten
+    /// on lock like the open document's.
+    inbox_password: String,
+    /// Edits allowed for other programs, waiting for the text to come back.
+    external_edits: Vec<ExternalEdit>,
+    doc_name: String,
+    password: String,
+    /// The key the open document was last saved with, so saving it again
+    /// skips the key derivation. Cleared when the document is closed.
+    keys: KeyCache,
+    error: Option<ErrorReport>,
+    path: Option<PathBuf>,
+    toasts: Vec<Toast>,
+    is_dirty: bool,
+    theme: highlighter::Theme,
+    search_query: String,
+    search_password: String,
+    search_results: Vec<SearchHit>,
+    is_searching: bool,
+    operation: Option<Operation>,
+    /// Recent log lines shown in Settings, if the viewer is open.
+    log: Option<String>,
+    bench: Option<bench::Results>,
+    is_benchmarking: bool,
+    is_self_testing: bool,
+    /// Edits left behind by a crash, offered for restoring at startup.
+    recovery: Option<Recovery>,
+    /// Whether the start-up work left until after the first frame is under
+    /// way.
+    started: bool,
+    config: Config,
+    documents: Vec<DocumentInfo>,
+    browser_filter: String,
+    browser_sort: SortOrder,
+    properties: Option<DocumentProperties>,
+    /// A tag being typed on the properties page.
+    tag_input: String,
+    caps_lock: bool,
+    name_error: Option<NameError>,
+    stats: Option<TextStats>,
+    /// Shows the document read-only with its redacted text blacked out,
+    /// to check what an export will leave out.
+    show_redacted: bool,
+    /// Shown instead of the editor while comparing with a backup.
+    compare: Option<Compare>,
+    /// The text as it was last opened or saved, which both sides of a
+    /// [`Merge`] started from.
+    base: String,
+    /// When the open document's file was last written by the app, to notice
+    /// something else writing it in the meantime.
+    disk_modified: Option<(PathBuf, SystemTime)>,
+    merge: Option<Merge>,
+    checklist: Vec<ChecklistItem>,
+    outline: Vec<Heading>,
+    show_outline: bool,
+    /// The open document as a table, if it's one rather than free text.
+    table: Option<SecretTable>,
+    /// Rows of the table whose secret is shown.
+    revealed: HashSet<usize>,
+    /// Two-factor seeds written in the document text, with their labels.
+    totp_seeds: Vec<(String, Totp)>,
+    images: Vec<EmbeddedImage>,
+    /// The documents linked to with `[[Name]]`.
+    links: Vec<String>,
+    /// The documents in the save folder linking to the open one, found in
+    /// the background when it's opened.
+    backlinks: Vec<PathBuf>,
+    index: VaultIndex,
+    line_ending: LineEnding,
+    container: Container,
+    history: PasswordHistory,
+    /// The last document closed, while [`Config::plaintext_retention`]
+    /// allows keeping it.
+    retained: Option<Retained>,
+    /// Counts the documents retained, to match them with their timers.
+    retention_generation: u64,
+}
+
+#[derive(Debug, Clone)]
+enum Page {
+    StartPage,
+    NewDocumentPage,
+    DocumentViewer,
+    Settings,
+    Search,
+    Browser,
+    Properties,
+}
+
+#[derive(Debug, Clone)]
+enum Message {
+    NewDocumentPressed,
+    OpenDocumentPressed,
+    SaveDocumentPressed,
+    SettingsPressed,
+    HomePressed,
+    SearchPressed,
+    JournalPressed,
+    JournalPasswordInput(String),
+    JournalSubmitted,
+    JournalCancelled,
+    JournalDaySelected(NaiveDate),
+    JournalEntryOpened(NaiveDate, Result<Option<Document>, DocumentError>),
+    CalendarPressed,
+    CalendarLoaded(Result<BTreeSet<NaiveDate>, FileError>),
+    CalendarMonthChanged(i32),
+    CalendarClosed,
+    BrowsePressed,
+    PropertiesPressed,
+    PropertiesClosed,
+    NewDocumentSubmitted,
+    TryDecrypt,
+    Decrypted(Result<Document, DocumentError>),
+    Encrypted(Result<(PathBuf, String), DocumentError>),
+    SelectFolderPressed,
+    CloseToast(usize),
+    ErrorDismissed,
+    DocumentInput(String),
+    PromptPasswordInput(String),
+    PromptCancelled,
+    EscapePressed,
+    CapsLockDetected(bool),
+    CapsLockToggled,
+    FocusNext,
+    FocusPrevious,
+    FocusToolbar,
+    ToolbarActivated,
+    ToolbarUnfocused,
+    PasswordInput(String),
+    Edit(text_editor::Action),
+    FileOpened(Result<(PathBuf, Arc<Contents>), FileError>),
+    FileSaved(Result<PathBuf, FileError>),
+    FolderPathFileSaved(Result<PathBuf, FileError>),
+    FolderSelected(Result<PathBuf, FileError>),
+    ThemeSelected(highlighter::Theme),
+    SearchQueryInput(String),
+    SearchPasswordInput(String),
+    SearchSubmitted,
+    SearchCompleted(Result<Vec<SearchHit>, FileError>),
+    SearchHitSelected(usize),
+    SearchHitOpened(Result<(PathBuf, Arc<Contents>), FileError>),
+    DocumentsListed(Result<Vec<DocumentInfo>, FileError>),
+    BrowserFilterInput(String),
+    BrowserSortSelected(SortOrder),
+    DocumentSelected(PathBuf),
+    PinToggled(PathBuf),
+    ConfigSaved(Result<PathBuf, FileError>),
+    PropertiesLoaded(Result<DocumentProperties, FileError>),
+    LanguageSelected(Language),
+    UiScaleChanged(f64),
+    LargeTextToggled(bool),
+    SaveConfig,
+    LockPressed,
+    Tray(TrayAction),
+    CloseRequested(window::Id),
+    MinimizeToTrayToggled(bool),
+    OnScreenKeypadToggled(bool),
+    KeypadToggled,
+    KeypadPressed(char),
+    KeypadBackspace,
+    KeypadShift,
+    Instance(instance::Request),
+    ExternalEditPasswordInput(String),
+    ExternalEditAllowed,
+    ExternalEditDenied,
+    ExternalEditDecrypted(Result<Document, DocumentError>),
+    ExternalEditReturned(u64, Result<Option<String>, io::ErrorKind>),
+    ExternalEditSaved(u64, Result<PathBuf, DocumentError>),
+    OverwriteConfirmed,
+    OverwriteRenamed,
+    OverwriteCancelled,
+    PlaintextRequested(PlaintextAction),
+    ArmorRequested(ArmorAction),
+    Armored(ArmorAction, Result<String, DocumentError>),
+    ShareSealed(Result<(String, String), DocumentError>),
+    ShareExported(String, Result<PathBuf, FileError>),
+    ShareKeyCopied,
+    ShareClosed,
+    ClipboardPasswordInput(String),
+    ClipboardSubmitted,
+    ClipboardCancelled,
+    ClipboardRead(Option<String>),
+    ClipboardProcessed(Result<String, DocumentError>),
+    ArmoredExported(Result<PathBuf, FileError>),
+    PasteArmoredPressed,
+    ArmoredPasted(Option<String>),
+    ReauthPasswordInput(String),
+    ReauthSubmitted,
+    ReauthCancelled,
+    Exported(Result<PathBuf, FileError>),
+    ImportRequested(&'static dyn Importer),
+    ArchiveImportRequested(&'static dyn ArchiveImporter),
+    ArchivePicked(&'static dyn ArchiveImporter, Result<PathBuf, FileError>),
+    ArchivePasswordInput(String),
+    ArchiveDocumentPasswordInput(String),
+    ArchiveImportSubmitted,
+    ArchiveImportCancelled,
+    ArchiveImported(Result<Vec<ImportedDocument>, FormatError>),
+    ArchiveExportRequested(&'static dyn ArchiveExporter),
+    ArchiveExportPasswordInput(String),
+    ArchiveExportSubmitted,
+    ArchiveExportCancelled,
+    /// The documents that could be decrypted, and how many couldn't.
+    VaultDecrypted(Result<(Vec<NamedDocument>, usize), DocumentError>),
+    ArchiveExported(Result<PathBuf, FileError>, usize),
+    Imported(Result<(PathBuf, Document), FileError>),
+    RequireReauthToggled(bool),
+    Session(SessionEvent),
+    LockOnSessionToggled(bool),
+    PlaintextRetentionSelected(Retention),
+    VaultSelected(String),
+    VaultPasswordInput(String),
+    VaultPasswordSubmitted,
+    VaultPasswordChecked(bool),
+    VaultPromptCancelled,
+    NewVaultNameInput(String),
+    AddVaultPressed,
+    RemoveVaultPressed,
+    RemoveVaultConfirmed,
+    RemoveVaultCancelled,
+    VaultMasterPasswordInput(String),
+    SetVaultPasswordPressed,
+    VaultPasswordHashed(Result<Option<String>, FileError>),
+    RetentionExpired(u64),
+    TabWidthSelected(u8),
+    IndentWithSpacesToggled(bool),
+    AutoIndentToggled(bool),
+    InsertDatePressed,
+    StatsToggled,
+    InsertImagePressed,
+    ImagePicked(Result<(String, Vec<u8>), FileError>),
+    OutlineToggled,
+    HeadingSelected(usize),
+    LinkSelected(String),
+    BacklinkSelected(PathBuf),
+    LinkOpened(Result<(PathBuf, Arc<Contents>), FileError>),
+    BacklinksFound(PathBuf, Vec<PathBuf>),
+    RedactPressed,
+    RedactedViewToggled,
+    ComparePressed,
+    RevisionsFound(Vec<Revision>),
+    RevisionSelected(Revision),
+    RevisionOpened(Result<Document, DocumentError>),
+    HunkRestored(usize),
+    CompareClosed,
+    TheirsOpened(Result<Document, DocumentError>, Option<SystemTime>),
+    MergeSideChosen(usize, Side),
+    MergeCompleted,
+    MergeCancelled,
+    ChecklistItemToggled(usize),
+    NewTableSubmitted,
+    TableCellEdited(usize, Field, String),
+    TableCellCopied(usize, Field),
+    TableRowAdded,
+    TableRowRemoved(usize),
+    SecretRevealToggled(usize),
+    TotpTick,
+    TotpCopied(String),
+    LabelColorSelected(LabelColor),
+    LabelEmojiInput(String),
+    LabelCleared,
+    TagInput(String),
+    TagSubmitted,
+    TagRemoved(String),
+    InboxToggled(bool),
+    CaptureHotkey,
+    CaptureOpened(window::Id, window::Mode, Size),
+    CaptureInput(String),
+    CapturePasswordInput(String),
+    CaptureSubmitted,
+    CaptureCancelled,
+    CaptureSaved(Result<(), DocumentError>),
+    IndexSaved(Result<PathBuf, FileError>),
+    LineEndingSelected(LineEnding),
+    ContainerSelected(Container),
+    ReportRequested(ReportFormat),
+    ReportSaved(Result<PathBuf, FileError>),
+    ToggleTaskPressed,
+    DateFormatInput(String),
+    PostSaveHookToggled(bool),
+    PostSaveHookInput(String),
+    PostSaveHookFinished(Result<(), HookError>),
+    SelfTestPressed,
+    SelfTestFinished(Option<Vec<selftest::Check>>),
+    ProfileDocumentsToggled(bool),
+    ExportProfilePressed,
+    ProfileExported(Result<PathBuf, FileError>),
+    ImportProfilePressed,
+    ProfileImported(Result<Imported, FileError>),
+    BenchPressed,
+    BenchFinished(Option<bench::Results>),
+    OperationProgress(Progress),
+    OperationCancelled,
+    LogToggled,
+    LogRefreshed,
+    LogLoaded(Result<String, FileError>),
+    RecoveryTick,
+    RecoverySaved(Result<PathBuf, FileError>),
+    RecoveryRestored,
+    RecoveryDiscarded,
+    RecoveryPostponed,
+    RecoveryCleared(Result<(), FileError>),
+    CheckForUpdatesToggled(bool),
+    ReleaseChannelSelected(ReleaseChannel),
+    StorageBackendSelected(Backend),
+    UpdateChecked(Result<Option<Release>, String>),
+    PagerPageSelected(usize),
+    PagerEditPressed,
+    AuditPasswordInput(String),
+    AuditEnablePressed,
+    AuditEnabled(Result<(), FileError>),
+    AuditShowPressed,
+    AuditRead(Result<Vec<AuditEntry>, DocumentError>),
+    AuditHidden,
+    Audited(Result<(), FileError>),
+    FailureRecorded(Result<Failures, FileError>),
+    FailuresCleared(PathBuf, Result<(), FileError>),
+    Started,
+    StartupLoaded(VaultIndex, Option<Recovery>),
+    FontLoaded(Result<(), font::Error>),
+}
+
+impl CryptoDoc {
+    /// Only reads the config, which the first frame needs for its language
+    /// and scale. Everything else is loaded once the app is running, see
+    /// [`Message::Started`].
+    fn new() -> Self {
+        let (config, unreadable) = Config::load();
+        i18n::set_language(config.language);
+
+        let toasts = unreadable
+            .map(|unreadable| Toast {
+                title: tr("toast.failed").into(),
+                body: match unreadable.backup {
+                    Some(backup) => tr_with("msg.config_unreadable", backup.display()),
+                    None => tr("msg.config_unreadable_kept").into(),
+                },
+                status: Status::Danger,
+            })
+            .into_iter()
+            .collect();
+
+        Self {
+            toasts,
+            current_page: Page::StartPage,
+            content: text_editor::Content::new(),
+            pager: None,
+            audit: AuditView::default(),
+            profile_documents: false,
+            prompt: None,
+            overwrite: None,
+            reauth: None,
+            toolbar_focus: None,
+            vault_prompt: None,
+            vault_removal: false,
+            new_vault_name: String::new(),
+            vault_password: String::new(),
+            external_edit: None,
+            archive_import: None,
+            archive_export: None,
+            journal_prompt: None,
+            clipboard_prompt: None,
+            share: None,
+            calendar: None,
+            capture: None,
+            inbox_password: String::new(),
+            external_edits: vec![],
+            doc_name: String::new(),
+            password: String::new(),
+            keys: KeyCache::default(),
+            error: None,
+            path: None,
+            is_dirty: false,
+            theme: highlighter::Theme::SolarizedDark,
+            search_query: String::new(),
+            search_password: String::new(),
+            search_results: vec![],
+            is_searching: false,
+            operation: None,
+            log: None,
+            bench: None,
+            is_benchmarking: false,
+            is_self_testing: false,
+            recovery: None,
+            started: false,
+            config,
+            documents: vec![],
+            browser_filter: String::new(),
+            browser_sort: SortOrder::default(),
+            properties: None,
+            tag_input: String::new(),
+            caps_lock: false,
+            name_error: None,
+            stats: None,
+            show_redacted: false,
+            compare: None,
+            base: String::new(),
+            disk_modified: None,
+            merge: None,
+            checklist: vec![],
+            outline: vec![],
+            show_outline: true,
+            table: None,
+            revealed: HashSet::new(),
+            totp_seeds: vec![],
+            images: vec![],
+            links: vec![],
+            backlinks: vec![],
+            index: VaultIndex::default(),
+            line_ending: LineEnding::native(),
+            container: Container::CryptoDoc,
+            history: PasswordHistory::default(),
+            retained: None,
+            retention_generation: 0,
+        }
+    }
+
+    fn update(&mut self, message: Message) -> Task<Message> {
+        match message {
+            Message::ThemeSelected(theme) => {
+                self.theme = theme;
+
+                Task::none()
+            }
+
+            Message::HomePressed => {
+                let retain = self.retain_document();
+
+                self.doc_name = String::new();
+                self.set_text(String::new());
+                self.password.zeroize();
+                self.keys.clear();
+                self.backlinks.clear();
+                self.document_changed();
+                self.path = None;
+                self.is_dirty = false;
+                self.current_page = Page::StartPage;
+
+                retain
+            }
+            Message::NewDocumentPressed => {
+                self.set_text(String::new());
+                self.doc_name = String::new();
+                self.password = String::new();
+                self.keys.clear();
+                self.backlinks.clear();
+                self.name_error = None;
+                self.line_ending = LineEnding::native();
+                self.container = Container::CryptoDoc;
+                self.history = PasswordHistory::default();
+                self.document_changed();
+
+                self.current_page = Page::NewDocumentPage;
+
+                self.focus_page()
+            }
+
+            Message::SelectFolderPressed => Task::perform(pick_folder(), Message::FolderSelected),
+
+            Message::SettingsPressed => {
+                self.current_page = Page::Settings;
+                self.audit.enabled = audit::is_enabled();
+
+                Task::none()
+            }
+
+            Message::JournalPressed => {
+                if self.config.save_path.is_empty() {
+                    self.toasts.push(Toast {
+                        title: tr("toast.failed").into(),
+                        body: tr("msg.select_folder_first").into(),
+                        status: Status::Danger,
+                    });
+
+                    return Task::none();
+                }
+
+                // The open entry's password is the journal's.
+                if self.journal_date().is_some() {
+                    return self.update(Message::JournalDaySelected(journal::today()));
+                }
+
+                self.journal_prompt = Some(JournalPrompt {
+                    password: String::new(),
+                    error: None,
+                    opening: false,
+                });
+
+                text_input::focus(journal_prompt_id())
+            }
+
+            Message::JournalPasswordInput(password) => {
+                if let Some(prompt) = &mut self.journal_prompt {
+                    prompt.password = password;
+                }
+
+                Task::none()
+            }
+
+            Message::JournalSubmitted => {
+                let Some(prompt) = &mut self.journal_prompt else {
+                    return Task::none();
+                };
+
+                if prompt.opening || prompt.password.is_empty() {
+                    return Task::none();
+                }
+
+                prompt.opening = true;
+                prompt.error = None;
+
+                let save_path = self.config.save_path.clone();
+                let password = prompt.password.clone();
+                let today = journal::today();
+
+                Task::perform(
+                    async move {
+                        journal::unlock(save_path.clone(), password.clone()).await?;
+
+                        journal::open_entry(save_path, today, password).await
+                    },
+                    move |result| Message::JournalEntryOpened(today, result),
+                )
+            }
+
+            Message::JournalCancelled => {
+                if self
+                    .journal_prompt
+                    .as_ref()
+                    .is_some_and(|prompt| !prompt.opening)
+                {
+                    self.journal_prompt = None;
+                }
+
+                Task::none()
+            }
+
+            Message::JournalDaySelected(date) => {
+                self.calendar = None;
+
+                let Some(current) = self.journal_date() else {
+                    return Task::none();
+                };
+
+                if current == date {
+                    return Task::none();
+                }
+
+                let save_path = self.config.save_path.clone();
+                let password = self.password.clone();
+                // Moving to another day saves the one being left, like locking.
+                let unsaved = self
+                    .is_dirty
+                    .then(|| (self.document(), self.document_path()));
+
+                Task::perform(
+                    async move {
+                        if let Some((document, path)) = unsaved {
+                            document.save(path, &password).await?;
+                        }
+
+                        journal::open_entry(save_path, date, password).await
+                    },
+                    move |result| Message::JournalEntryOpened(date, result),
+                )
+            }
+
+            Message::JournalEntryOpened(date, Ok(document)) => {
+                if let Some(prompt) = self.journal_prompt.take() {
+                    self.password = prompt.password;
+                    self.keys.clear();
+                }
+
+                let path = journal::entry_path(&self.config.save_path, date);
+                tracing::info!(file = document_name(&path), "opened journal entry");
+
+                let document = match document {
+                    Some(document) => {
+                        self.path = Some(path.clone());
+                        document
+                    }
+                    None => {
+                        self.path = None;
+                        Document::new("")
+                    }
+                };
+
+                self.doc_name = pathbuf_to_string(&path);
+                self.line_ending = document.line_ending;
+                self.container = document.container;
+                self.history = document.history;
+                self.set_text(document.text);
+                self.is_dirty = false;
+                self.current_page = Page::DocumentViewer;
+                self.document_changed();
+
+                Task::none()
+            }
+
+            Message::JournalEntryOpened(_, Err(error)) => {
+                tracing::warn!(%error, "couldn't open journal entry");
+
+                match &mut self.journal_prompt {
+                    Some(prompt) => {
+                        prompt.opening = false;
+                        prompt.password.clear();
+                        prompt.error = Some(match error {
+                            DocumentError::WrongPassword => tr("prompt.wrong_password").into(),
+                            DocumentError::Locked(until) => locked_message(until),
+                            error => error.to_string(),
+                        });
+
+                        text_input::focus(journal_prompt_id())
+                    }
+                    None => {
+                        self.error = Some(ErrorReport::new(tr("error.open_journal"), error));
+
+                        Task::none()
+                    }
+                }
+            }
+
+            Message::CalendarPressed => Task::perform(
+                journal::entry_dates(journal::folder(&self.config.save_path)),
+                Message::CalendarLoaded,
+            ),
+
+            Message::CalendarLoaded(Ok(entries)) => {
+                if let Some(date) = self.journal_date() {
+                    self.calendar = Some(Calendar::new(date, entries));
+                }
+
+                Task::none()
+            }
+
+            Message::CalendarLoaded(Err(error)) => {
+                self.report_error("error.open_journal", error);
+
+                Task::none()
+            }
+
+            Message::CalendarMonthChanged(delta) => {
+                if let Some(calendar) = &mut self.calendar {
+                    calendar.change_month(delta);
+                }
+
+                Task::none()
+            }
+
+            Message::CalendarClosed => {
+                self.calendar = None;
+
+                Task::none()
+            }
+
+            Message::SearchPressed => {
+                self.current_page = Page::Search;
+
+                self.focus_page()
+            }
+
+            Message::BrowsePressed => {
+                self.current_page = Page::Browser;
+
+                if self.config.save_path.is_empty() {
+                    self.documents.clear();
+
+                    return self.focus_page();
+                }
+
+                Task::batch([
+                    Task::perform(
+                        list_documents(PathBuf::from(&self.config.save_path)),
+                        Message::DocumentsListed,
+                    ),
+                    self.focus_page(),
+                ])
+            }
+
+            Message::FocusNext if self.toolbar_focus.is_some() => {
+                self.toolbar_focus =
+                    ToolbarAction::next(self.toolbar_focus, |action| self.toolbar_enabled(action));
+
+                if self.toolbar_focus.is_some() {
+                    Task::none()
+                } else {
+                    focus_next()
+                }
+            }
+
+            Message::FocusPrevious if self.toolbar_focus.is_some() => {
+                self.toolbar_focus = ToolbarAction::previous(self.toolbar_focus, |action| {
+                    self.toolbar_enabled(action)
+                });
+
+                if self.toolbar_focus.is_some() {
+                    Task::none()
+                } else {
+                    focus_previous()
+                }
+            }
+
+            Message::FocusNext if self.editor_has_keyboard() => {
+                let (_, column) = self.content.cursor_position();
+                let indent = self.config.indent_at(column);
+
+                self.update(Message::Edit(text_editor::Action::Edit(
+                    text_editor::Edit::Paste(Arc::new(indent)),
+                )))
+            }
+
+            Message::FocusNext => focus_next(),
+
+            Message::FocusPrevious => focus_previous(),
+
+            Message::FocusToolbar => {
+                self.toolbar_focus =
+                    ToolbarAction::next(None, |action| self.toolbar_enabled(action));
+
+                // Focusing an id no input has takes focus away from the one
+                // that has it, so typing doesn't go there too.
+                text_input::focus(text_input::Id::new("toolbar"))
+            }
+
+            Message::ToolbarActivated => match self.toolbar_focus.take() {
+                Some(action) => self.update(action.message()),
+                None => Task::none(),
+            },
+
+            Message::ToolbarUnfocused => {
+                self.toolbar_focus = None;
+
+                Task::none()
+            }
+
+            Message::DocumentsListed(Ok(documents)) => {
+                self.documents = documents;
+
+                Task::none()
+            }
+
+            Message::DocumentsListed(Err(error)) => {
+                self.documents.clear();
+                self.report_error("error.read_folder", error);
+
+                Task::none()
+            }
+
+            Message::PropertiesPressed => match &self.path {
+                Some(path) => {
+                    Task::perform(load_properties(path.clone()), Message::PropertiesLoaded)
+                }
+                None => {
+                    self.toasts.push(Toast {
+                        title: tr("toast.properties").into(),
+                        body: tr("msg.save_first").into(),
+                        status: Status::Secondary,
+                    });
+
+                    Task::none()
+                }
+            },
+
+            Message::PropertiesLoaded(Ok(properties)) => {
+                self.properties = Some(properties);
+                self.current_page = Page::Properties;
+
+                Task::none()
+            }
+
+            Message::PropertiesLoaded(Err(error)) => {
+                self.report_error("error.read_properties", error);
+
+                Task::none()
+            }
+
+            Message::PropertiesClosed => {
+                self.properties = None;
+                self.current_page = Page::DocumentViewer;
+
+                Task::none()
+            }
+
+            Message::LanguageSelected(language) => {
+                self.config.language = language;
+                i18n::set_language(language);
+
+                Task::perform(save_config(self.config.clone()), Message::ConfigSaved)
+            }
+
+            Message::UiScaleChanged(scale) => {
+                self.config.ui_scale = scale;
+
+                Task::none()
+            }
+
+            Message::LargeTextToggled(large_text) => {
+                self.config.large_text = large_text;
+
+                self.update(Message::SaveConfig)
+            }
+
+            Message::SaveConfig => {
+                Task::perform(save_config(self.config.clone()), Message::ConfigSaved)
+            }
+
+            Message::LockPressed => self.lock(),
+
+            Message::Session(_) => self.lock(),
+
+            Message::LockOnSessionToggled(lock_on_session_events) => {
+                self.config.lock_on_session_events = lock_on_session_events;
+
+                self.update(Message::SaveConfig)
+            }
+
+            Message::PlaintextRetentionSelected(retention) => {
+                self.config.plaintext_retention = retention;
+
+                // A shorter time only applies to documents closed from now
+                // on, except that nothing is kept at all anymore.
+                if retention.duration().is_none() {
+                    self.retained = None;
+                }
+
+                self.update(Message::SaveConfig)
+            }
+
+            Message::VaultSelected(name) => {
+                if name == self.config.vault_name {
+                    return Task::none();
+                }
+
+                match self
+                    .config
+                    .vault(&name)
+                    .and_then(|vault| vault.password_hash.clone())
+                {
+                    Some(hash) => {
+                        self.vault_prompt = Some(VaultPrompt {
+                            name,
+                            hash,
+                            password: String::new(),
+                            error: None,
+                            checking: false,
+                            remove_current: false,
+                        });
+
+                        text_input::focus(vault_prompt_id())
+                    }
+                    None => self.switch_vault(&name, String::new()),
+                }
+            }
+
+            Message::VaultPasswordInput(password) => {
+                if let Some(prompt) = &mut self.vault_prompt {
+                    prompt.password = password;
+                }
+
+                Task::none()
+            }
+
+            Message::VaultPasswordSubmitted => {
+                let Some(prompt) = &mut self.vault_prompt else {
+                    return Task::none();
+                };
+
+                if prompt.checking {
+                    return Task::none();
+                }
+
+                prompt.checking = true;
+
+                Task::perform(
+                    check_password(prompt.password.clone(), prompt.hash.clone()),
+                    Message::VaultPasswordChecked,
+                )
+            }
+
+            Message::VaultPasswordChecked(true) => match self.vault_prompt.take() {
+                Some(prompt) if prompt.remove_current => self.remove_vault(prompt.password),
+                Some(prompt) => self.switch_vault(&prompt.name, prompt.password),
+                None => Task::none(),
+            },
+
+            Message::VaultPasswordChecked(false) => {
+                let Some(prompt) = &mut self.vault_prompt else {
+                    return Task::none();
+                };
+
+                prompt.checking = false;
+                prompt.password.clear();
+                prompt.error = Some(tr("prompt.wrong_password").into());
+
+                text_input::focus(vault_prompt_id())
+            }
+
+            Message::VaultPromptCancelled => {
+                self.vault_prompt = None;
+
+                Task::none()
+            }
+
+            Message::NewVaultNameInput(name) => {
+                self.new_vault_name = name;
+
+                Task::none()
+            }
+
+            Message::AddVaultPressed => {
+                if !self
+                    .config
+                    .add_vault(&self.new_vault_name, &tr("vaults.default_name"))
+                {
+                    self.toasts.push(Toast {
+                        title: tr("toast.failed").into(),
+                        body: tr("msg.vault_exists").into(),
+                        status: Status::Danger,
+                    });
+
+                    return Task::none();
+                }
+
+                self.toasts.push(Toast {
+                    title: tr("toast.success").into(),
+                    body: tr_with("msg.vault_added", self.new_vault_name.trim()),
+                    status: Status::Success,
+                });
+                self.new_vault_name.clear();
+
+                self.update(Message::SaveConfig)
+            }
+
+            Message::RemoveVaultPressed => {
+                if self.is_dirty {
+                    self.toasts.push(Toast {
+                        title: tr("toast.failed").into(),
+                        body: tr("msg.save_before_switching").into(),
+                        status: Status::Danger,
+                    });
+
+                    return Task::none();
+                }
+
+                self.vault_removal = !self.config.vaults.is_empty();
+
+                Task::none()
+            }
+
+            Message::RemoveVaultCancelled => {
+                self.vault_removal = false;
+
+                Task::none()
+            }
+
+            Message::RemoveVaultConfirmed => {
+                self.vault_removal = false;
+
+                let Some(next) = self.config.vaults.first() else {
+                    return Task::none();
+                };
+
+                // Removing switches to the next profile, which mustn't get
+                // around its password.
+                match next.password_hash.clone() {
+                    Some(hash) => {
+                        self.vault_prompt = Some(VaultPrompt {
+                            name: next.name.clone(),
+                            hash,
+                            password: String::new(),
+                            error: None,
+                            checking: false,
+                            remove_current: true,
+                        });
+
+                        text_input::focus(vault_prompt_id())
+                    }
+                    None => self.remove_vault(String::new()),
+                }
+            }
+
+            Message::VaultMasterPasswordInput(password) => {
+                self.vault_password = password;
+
+                Task::none()
+            }
+
+            Message::SetVaultPasswordPressed => Task::perform(
+                hash_password(std::mem::take(&mut self.vault_password)),
+                Message::VaultPasswordHashed,
+            ),
+
+            Message::VaultPasswordHashed(Ok(hash)) => {
+                self.toasts.push(Toast {
+                    title: tr("toast.success").into(),
+                    body: if hash.is_some() {
+                        tr("msg.vault_password_set")
+                    } else {
+                        tr("msg.vault_password_removed")
+                    }
+                    .into(),
+                    status: Status::Success,
+                });
+
+                self.config.vault_password_hash = hash;
+
+                self.update(Message::SaveConfig)
+            }
+
+            Message::VaultPasswordHashed(Err(error)) => {
+                self.report_error("error.vault_password", error);
+
+                Task::none()
+            }
+
+            Message::RetentionExpired(generation) => {
+                if self
+                    .retained
+                    .as_ref()
+                    .is_some_and(|retained| retained.generation == generation)
+                {
+                    self.retained = None;
+                }
+
+                Task::none()
+            }
+
+            Message::Tray(TrayAction::Show) => show_window(),
+
+            Message::Tray(TrayAction::Lock) => self.lock(),
+
+            Message::Tray(TrayAction::OpenRecent) => {
+                // Closing would lose the changes, so the document is shown
+                // instead to save them first.
+                if self.is_dirty {
+                    self.current_page = Page::DocumentViewer;
+                    self.toasts.push(Toast {
+                        title: tr("toast.failed").into(),
+                        body: tr("msg.save_first").into(),
+                        status: Status::Danger,
+                    });
+
+                    return show_window();
+                }
+
+                Task::batch([self.update(Message::HomePressed), show_window()])
+            }
+
+            Message::Tray(TrayAction::NewDocument) => {
+                Task::batch([show_window(), self.update(Message::NewDocumentPressed)])
+            }
+
+            Message::Tray(TrayAction::EncryptClipboard) => {
+                self.open_clipboard_prompt(ClipboardAction::Encrypt)
+            }
+
+            Message::Tray(TrayAction::DecryptClipboard) => {
+                self.open_clipboard_prompt(ClipboardAction::Decrypt)
+            }
+
+            Message::Tray(TrayAction::Quit) => window::get_oldest().and_then(window::close),
+
+            Message::CloseRequested(id) => {
+                if self.config.minimize_to_tray {
+                    window::change_mode(id, window::Mode::Hidden)
+                } else {
+                    window::close(id)
+                }
+            }
+
+            Message::Instance(instance::Request::Show) => show_window(),
+
+            Message::Instance(instance::Request::Open(path)) => Task::batch([
+                show_window(),
+                Task::perform(load_file(path), Message::FileOpened),
+            ]),
+
+            Message::Instance(instance::Request::Edit { id, path }) => {
+                // One request at a time; the client can ask again.
+                if self.external_edit.is_some() {
+                    instance::deny_edit(id);
+
+                    return Task::none();
+                }
+
+                let path = std::fs::canonicalize(&path).unwrap_or(path);
+                tracing::info!(
+                    file = document_name(&path),
+                    "edit requested by another program"
+                );
+
+                self.external_edit = Some(ExternalEditPrompt {
+                    id,
+                    path,
+                    password: String::new(),
+                    error: None,
+                    decrypting: false,
+                });
+
+                Task::batch([show_window(), text_input::focus(external_edit_prompt_id())])
+            }
+
+            Message::ExternalEditPasswordInput(password) => {
+                if let Some(prompt) = &mut self.external_edit {
+                    if !prompt.decrypting {
+                        prompt.password = password;
+                    }
+                }
+
+                Task::none()
+            }
+
+            Message::ExternalEditAllowed => {
+                let Some(prompt) = &mut self.external_edit else {
+                    return Task::none();
+                };
+
+                if prompt.decrypting || prompt.password.is_empty() {
+                    return Task::none();
+                }
+
+                prompt.decrypting = true;
+                let path = prompt.path.clone();
+                let password = prompt.password.clone();
+
+                // Through the lockout like the main prompt, as this is just
+                // as good a place to guess passwords.
+                Task::perform(
+                    async move { lockout::open(path, &password).await },
+                    Message::ExternalEditDecrypted,
+                )
+            }
+
+            Message::ExternalEditDenied => {
+                if let Some(prompt) = self.external_edit.take() {
+                    instance::deny_edit(prompt.id);
+                }
+
+                Task::none()
+            }
+
+            Message::ExternalEditDecrypted(result) => {
+                let Some(prompt) = &mut self.external_edit else {
+                    return Task::none();
+                };
+
+                prompt.decrypting = false;
+
+                match result {
+                    Ok(document) => {
+                        let prompt = self.external_edit.take().expect("Prompt is open");
+                        let id = prompt.id;
+                        let text = document.text.clone();
+
+                        self.external_edits.push(ExternalEdit {
+                            id,
+                            path: prompt.path,
+                            password: prompt.password,
+                            document,
+                        });
+
+                        Task::perform(instance::exchange_text(id, text), move |result| {
+                            Message::ExternalEditReturned(id, result)
+                        })
+                    }
+                    Err(DocumentError::WrongPassword) => {
+                        prompt.error = Some(tr("prompt.wrong_password").into());
+                        prompt.password.clear();
+
+                        Task::batch([
+                            audit::record(AuditEvent::FailedUnlock {
+                                document: pathbuf_to_string(&prompt.path),
+                            }),
+                            text_input::focus(external_edit_prompt_id()),
+                        ])
+                    }
+                    Err(DocumentError::Locked(until)) => {
+                        prompt.error = Some(locked_message(until));
+                        prompt.password.clear();
+
+                        text_input::focus(external_edit_prompt_id())
+                    }
+                    Err(error) => {
+                        let prompt = self.external_edit.take().expect("Prompt is open");
+                        instance::deny_edit(prompt.id);
+
+                        self.error = Some(ErrorReport::new(tr("error.open_document"), error));
+
+                        Task::none()
+                    }
+                }
+            }
+
+            Message::ExternalEditReturned(id, Ok(Some(text))) => {
+                let Some(edit) = self.external_edits.iter_mut().find(|edit| edit.id == id) else {
+                    return Task::none();
+                };
+
+                edit.document.text = text;
+                let document = edit.document.clone();
+                let path = edit.path.clone();
+                let password = edit.password.clone();
+
+                Task::perform(
+                    async move { document.save(path, &password).await },
+                    move |result| Message::ExternalEditSaved(id, result),
+                )
+            }
+
+            Message::ExternalEditReturned(id, result) => {
+                if let Err(error) = result {
+                    tracing::warn!(error = %io::Error::from(error), "external edit failed");
+                }
+
+                self.external_edits.retain(|edit| edit.id != id);
+
+                Task::none()
+            }
+
+            Message::ExternalEditSaved(id, result) => {
+                instance::finish_edit(id, result.is_ok());
+
+                let Some(index) = self.external_edits.iter().position(|edit| edit.id == id) else {
+                    return Task::none();
+                };
+                let edit = self.external_edits.remove(index);
+
+                match result {
+                    Ok(path) => {
+                        tracing::info!(file = document_name(&path), "saved external edit");
+
+                        // Show the new text if the document is open here and
+                        // nothing would be lost by replacing it.
+                        if self.path.as_ref() == Some(&path) && !self.is_dirty {
+                            self.set_text(edit.document.text.clone());
+                            self.document_changed();
+                        }
+
+                        self.toasts.push(Toast {
+                            title: tr("toast.success").into(),
+                            body: tr_with("msg.external_edit_saved", document_name(&path)),
+                            status: Status::Success,
+                        });
+                    }
+                    Err(error) => {
+                        tracing::error!(%error, "couldn't save external edit");
+                        self.error = Some(ErrorReport::new(tr("error.save_document"), error));
+                    }
+                }
+
+                Task::none()
+            }
+
+            Message::MinimizeToTrayToggled(minimize_to_tray) => {
+                self.config.minimize_to_tray = minimize_to_tray;
+
+                self.update(Message::SaveConfig)
+            }
+
+            Message::OnScreenKeypadToggled(on_screen_keypad) => {
+                self.config.on_screen_keypad = on_screen_keypad;
+
+                self.update(Message::SaveConfig)
+            }
+
+            Message::BrowserFilterInput(filter) => {
+                self.browser_filter = filter;
+
+                Task::none()
+            }
+
+            Message::BrowserSortSelected(sort) => {
+                self.browser_sort = sort;
+
+                Task::none()
+            }
+
+            Message::DocumentSelected(path) => Task::perform(load_file(path), Message::FileOpened),
+
+            Message::PinToggled(path) => {
+                self.config.toggle_pin(path);
+
+                Task::perform(save_config(self.config.clone()), Message::ConfigSaved)
+            }
+
+            Message::LabelColorSelected(color) => self.update_meta(|meta| meta.color = Some(color)),
+
+            Message::LabelEmojiInput(emoji) => {
+                // Enough for flag and skin tone sequences, not for a sentence.
+                let emoji: String = emoji.trim().chars().take(8).collect();
+
+                self.update_meta(|meta| meta.emoji = (!emoji.is_empty()).then_some(emoji))
+            }
+
+            Message::LabelCleared => self.update_meta(|meta| {
+                meta.color = None;
+                meta.emoji = None;
+            }),
+
+            Message::TagInput(tag) => {
+                self.tag_input = tag;
+
+                Task::none()
+            }
+
+            Message::TagSubmitted => {
+                let tag = std::mem::take(&mut self.tag_input);
+
+                self.update_meta(|meta| meta.add_tag(&tag))
+            }
+
+            Message::TagRemoved(tag) => {
+                self.update_meta(|meta| meta.tags.retain(|existing| existing != &tag))
+            }
+
+            Message::InboxToggled(is_inbox) => {
+                let Some(properties) = &self.properties else {
+                    return Task::none();
+                };
+
+                self.config.inbox = is_inbox.then(|| properties.path.clone());
+                self.inbox_password.clear();
+
+                self.update(Message::SaveConfig)
+            }
+
+            Message::CaptureHotkey => {
+                if let Some(capture) = &self.capture {
+                    return window::gain_focus(capture.window);
+                }
+
+                window::get_oldest().and_then(|id| {
+                    window::get_mode(id).then(move |mode| {
+                        window::get_size(id).map(move |size| Message::CaptureOpened(id, mode, size))
+                    })
+                })
+            }
+
+            Message::CaptureOpened(id, mode, size) => {
+                let Some(inbox) = &self.config.inbox else {
+                    self.toasts.push(Toast {
+                        title: tr("toast.failed").into(),
+                        body: tr("msg.no_inbox").into(),
+                        status: Status::Danger,
+                    });
+
+                    return show_window();
+                };
+
+                // The inbox may be the open document, which already has its
+                // password.
+                let password = if self.path.as_ref() == Some(inbox) {
+                    self.password.clone()
+                } else {
+                    self.inbox_password.clone()
+                };
+
+                self.capture = Some(Capture {
+                    window: id,
+                    mode,
+                    size,
+                    text: String::new(),
+                    unlocked: !password.is_empty(),
+                    password,
+                    error: None,
+                    saving: false,
+                });
+
+                Task::batch([
+                    window::change_mode(id, window::Mode::Windowed),
+                    window::resize(id, capture::WINDOW_SIZE),
+                    window::change_level(id, window::Level::AlwaysOnTop),
+                    window::gain_focus(id),
+                    text_input::focus(capture::input_id()),
+                ])
+            }
+
+            Message::CaptureInput(input) => {
+                if let Some(capture) = &mut self.capture {
+                    capture.text = input;
+                }
+
+                Task::none()
+            }
+
+            Message::CapturePasswordInput(password) => {
+                if let Some(capture) = &mut self.capture {
+                    capture.password = password;
+                }
+
+                Task::none()
+            }
+
+            Message::CaptureSubmitted => {
+                let (Some(capture), Some(inbox)) = (&mut self.capture, &self.config.inbox) else {
+                    return Task::none();
+                };
+
+                if capture.saving || capture.text.trim().is_empty() || capture.password.is_empty() {
+                    return Task::none();
+                }
+
+                let entry = match format_now(&self.config.date_format) {
+                    Some(date) => format!("{}\n{}", date, capture.text.trim()),
+                    None => capture.text.trim().to_string(),
+                };
+
+                // Appending to the open document goes through the editor, so
+                // its unsaved edits and undo history are kept.
+                if self.path.as_ref() == Some(inbox)
+                    && matches!(self.current_page, Page::DocumentViewer)
+                    && self.pager.is_none()
+                {
+                    let text = self.content.text();
+                    let separator = match text.trim_end().len() {
+                        0 => "",
+                        length if length == text.len() => "\n\n",
+                        _ => "\n",
+                    };
+
+                    self.content
+                        .perform(text_editor::Action::Move(text_editor::Motion::DocumentEnd));
+                    self.content
+                        .perform(text_editor::Action::Edit(text_editor::Edit::Paste(
+                            Arc::new(format!("{}{}\n", separator, entry)),
+                        )));
+                    self.is_dirty = true;
+                    self.document_changed();
+
+                    let restore = self.close_capture();
+
+                    return Task::batch([restore, self.update(Message::SaveDocumentPressed)]);
+                }
+
+                capture.saving = true;
+                capture.error = None;
+
+                Task::perform(
+                    capture::append(inbox.clone(), capture.password.clone(), entry),
+                    Message::CaptureSaved,
+                )
+            }
+
+            Message::CaptureCancelled => {
+                if self.capture.as_ref().is_some_and(|capture| capture.saving) {
+                    return Task::none();
+                }
+
+                self.close_capture()
+            }
+
+            Message::CaptureSaved(Ok(())) => {
+                if let Some(capture) = &self.capture {
+                    if self.path != self.config.inbox {
+                        self.inbox_password = capture.password.clone();
+                    }
+                }
+
+                tracing::info!("captured a note to the inbox");
+
+                self.close_capture()
+            }
+
+            Message::CaptureSaved(Err(error)) => {
+                tracing::warn!(%error, "couldn't capture a note");
+
+                let Some(capture) = &mut self.capture else {
+                    return Task::none();
+                };
+
+                capture.saving = false;
+                capture.error = Some(match error {
+                    DocumentError::WrongPassword => {
+                        // A remembered password has since been changed.
+                        capture.unlocked = false;
+                        capture.password.clear();
+                        self.inbox_password.clear();
+
+                        tr("prompt.wrong_password").into()
+                    }
+                    DocumentError::Locked(until) => locked_message(until),
+                    error => error.to_string(),
+                });
+
+                Task::none()
+            }
+
+            Message::LineEndingSelected(line_ending) => {
+                // Converting only changes what is written on the next save.
+                self.is_dirty = self.is_dirty || line_ending != self.line_ending;
+                self.line_ending = line_ending;
+
+                Task::none()
+            }
+
+            Message::ContainerSelected(container) => {
+                // The next save writes a new file next to the old one, since
+                // the extension changes with the container.
+                self.is_dirty = self.is_dirty || container != self.container;
+                self.container = container;
+
+                Task::none()
+            }
+
+            Message::ReportRequested(format) => {
+                if self.operation.is_some() {
+                    return Task::none();
+                }
+
+                let folder = PathBuf::from(&self.config.save_path);
+                let index = self.index.clone();
+
+                let (operation, task) = progress::start(
+                    "progress.report",
+                    |reporter| async move {
+                        let report = build_report(folder, index, format, reporter).await?;
+
+                        save_report(format, report).await
+                    },
+                    Message::OperationProgress,
+                    Message::ReportSaved,
+                );
+
+                self.operation = Some(operation);
+
+                task
+            }
+
+            Message::OperationProgress(progress) => {
+                if let Some(operation) = &mut self.operation {
+                    operation.progress = Some(progress);
+                }
+
+                Task::none()
+            }
+
+            Message::LogToggled => {
+                if self.log.take().is_some() {
+                    return Task::none();
+                }
+
+                self.log = Some(String::new());
+
+                self.update(Message::LogRefreshed)
+            }
+
+            Message::LogRefreshed => Task::perform(logging::read_recent(), Message::LogLoaded),
+
+            Message::LogLoaded(Ok(log)) => {
+                // Ignore a load that finishes after the viewer was closed.
+                if self.log.is_some() {
+                    self.log = Some(log);
+                }
+
+                Task::none()
+            }
+
+            Message::LogLoaded(Err(error)) => {
+                self.report_error("error.read_log", error);
+
+                Task::none()
+            }
+
+            Message::RecoveryTick => {
+                if !self.is_dirty || !matches!(self.current_page, Page::DocumentViewer) {
+                    return Task::none();
+                }
+
+                let path = self.document_path();
+                let document = self.document();
+                let password = self.password.clone();
+                let keys = self.keys.clone();
+
+                Task::perform(
+                    async move {
+                        let encrypted = document
+                            .encrypt_in_background(password, keys)
+                            .await
+                            .map_err(|_| FileError::IOFailed(io::ErrorKind::Other))?;
+
+                        recovery::save(Recovery { path, encrypted }).await
+                    },
+                    Message::RecoverySaved,
+                )
+            }
+
+            // A failed journal write isn't worth interrupting the user for;
+            // the next tick tries again.
+            Message::RecoverySaved(result) => {
+                if let Err(error) = result {
+                    tracing::warn!(%error, "couldn't write the recovery journal");
+                }
+
+                Task::none()
+            }
+
+            Message::RecoveryRestored => {
+                let Some(recovery) = self.recovery.take() else {
+                    return Task::none();
+                };
+
+                self.prompt = Some(PasswordPrompt {
+                    path: recovery.path,
+                    encrypted: Arc::new(recovery.encrypted.into()),
+                    password: String::new(),
+                    error: None,
+                    attempts: 0,
+                    locked_until: None,
+                    keypad: self.config.on_screen_keypad.then(Keypad::new),
+                    decrypting: false,
+                    recovered: true,
+                });
+
+                text_input::focus(password_prompt_id())
+            }
+
+            Message::RecoveryDiscarded => {
+                self.recovery = None;
+
+                Task::perform(recovery::discard(), Message::RecoveryCleared)
+            }
+
+            // Leaves the journal in place, so it's offered again next launch.
+            Message::RecoveryPostponed => {
+                self.recovery = None;
+
+                Task::none()
+            }
+
+            Message::RecoveryCleared(result) => {
+                if let Err(error) = result {
+                    tracing::warn!(%error, "couldn't remove the recovery journal");
+                }
+
+                Task::none()
+            }
+
+            Message::CheckForUpdatesToggled(check_for_updates) => {
+                self.config.check_for_updates = check_for_updates;
+
+                self.update(Message::SaveConfig)
+            }
+
+            Message::ReleaseChannelSelected(channel) => {
+                self.config.release_channel = channel;
+
+                self.update(Message::SaveConfig)
+            }
+
+            Message::StorageBackendSelected(backend) => {
+                self.config.set_storage_backend(backend);
+
+                self.update(Message::SaveConfig)
+            }
+
+            Message::UpdateChecked(Ok(Some(release))) => {
+                self.toasts.push(Toast {
+                    title: tr("update.title").into(),
+                    body: tr_with(
+                        "update.available",
+                        format!("{} ({})", release.tag_name, release.html_url),
+                    ),
+                    status: Status::Primary,
+                });
+
+                Task::none()
+            }
+
+            Message::UpdateChecked(Ok(None)) => Task::none(),
+
+            Message::UpdateChecked(Err(error)) => {
+                tracing::warn!(%error, "couldn't check for updates");
+
+                Task::none()
+            }
+
+            Message::PagerPageSelected(page) => {
+                if let Some(pager) = &mut self.pager {
+                    pager.set_page(page);
+                }
+
+                Task::none()
+            }
+
+            // The editor may take a while with this much text, but it's what
+            // was asked for.
+            Message::PagerEditPressed => {
+                if let Some(pager) = self.pager.take() {
+                    self.content = text_editor::Content::with_text(&pager.text);
+                }
+
+                Task::none()
+            }
+
+            Message::AuditPasswordInput(password) => {
+                self.audit.password = password;
+                self.audit.error = None;
+
+                Task::none()
+            }
+
+            Message::AuditEnablePressed => {
+                self.audit.working = true;
+
+                Task::perform(
+                    audit::create(std::mem::take(&mut self.audit.password)),
+                    Message::AuditEnabled,
+                )
+            }
+
+            Message::AuditEnabled(result) => {
+                self.audit.working = false;
+
+                match result {
+                    Ok(()) => {
+                        tracing::info!("turned on audit log");
+                        self.audit.enabled = true;
+                        self.toasts.push(Toast {
+                            title: tr("toast.success").into(),
+                            body: tr("msg.audit_enabled").into(),
+                            status: Status::Success,
+                        });
+                    }
+                    Err(error) => self.report_error("error.audit", error),
+                }
+
+                Task::none()
+            }
+
+            Message::AuditShowPressed => {
+                self.audit.working = true;
+
+                Task::perform(
+                    audit::read(std::mem::take(&mut self.audit.password)),
+                    Message::AuditRead,
+                )
+            }
+
+            Message::AuditRead(result) => {
+                self.audit.working = false;
+
+                match result {
+                    Ok(entries) => self.audit.entries = Some(entries),
+                    Err(DocumentError::WrongPassword) => {
+                        self.audit.error = Some(tr("prompt.wrong_password").into());
+                    }
+                    Err(error) => self.audit.error = Some(error.to_string()),
+                }
+
+                Task::none()
+            }
+
+            Message::AuditHidden => {
+                self.audit.entries = None;
+
+                Task::none()
+            }
+
+            Message::Audited(result) => {
+                if let Err(error) = result {
+                    tracing::warn!(%error, "couldn't write to audit log");
+                }
+
+                Task::none()
+            }
+
+            Message::FailureRecorded(Ok(failures)) => {
+                tracing::info!(count = failures.count, "recorded failed unlock");
+
+                if let Some(prompt) = &mut self.prompt {
+                    prompt.locked_until = failures.locked_until();
+                }
+
+                Task::none()
+            }
+
+            Message::FailureRecorded(Err(error)) => {
+                tracing::warn!(%error, "couldn't record failed unlock");
+
+                Task::none()
+            }
+
+            Message::FailuresCleared(path, result) => {
+                if let Err(error) = result {
+                    tracing::warn!(%error, "couldn't clear failed unlocks");
+                }
+
+                // Clearing rewrote the file, which isn't a change to merge
+                // on the next save.
+                if self
+                    .disk_modified
+                    .as_ref()
+                    .is_some_and(|(modified_path, _)| *modified_path == path)
+                {
+                    self.disk_modified = modified_time(&path).map(|modified| (path, modified));
+                }
+
+                Task::none()
+            }
+
+            Message::Started => {
+                self.started = true;
+                let folder = PathBuf::from(&self.config.save_path);
+
+                let load = Task::perform(
+                    async move {
+                        tokio::task::spawn_blocking(move || {
+                            (VaultIndex::load(&folder), recovery::load())
+                        })
+                        .await
+                        .unwrap_or_default()
+                    },
+                    |(index, recovery)| Message::StartupLoaded(index, recovery),
+                );
+
+                Task::batch([load, font::load(ICON_FONT).map(Message::FontLoaded)])
+            }
+
+            Message::StartupLoaded(index, recovery) => {
+                self.index = index;
+                self.recovery = recovery;
+
+                Task::none()
+            }
+
+            Message::FontLoaded(Ok(())) => Task::none(),
+
+            Message::FontLoaded(Err(error)) => {
+                tracing::warn!(?error, "couldn't load the icon font");
+
+                Task::none()
+            }
+
+            Message::OperationCancelled => {
+                if let Some(operation) = &self.operation {
+                    operation.cancel();
+                }
+
+                Task::none()
+            }
+
+            Message::ReportSaved(Ok(_)) => {
+                self.operation = None;
+
+                self.toasts.push(Toast {
+                    title: tr("toast.success").into(),
+                    body: tr("msg.report_saved").into(),
+                    status: Status::Success,
+                });
+
+                Task::none()
+            }
+
+            Message::ReportSaved(Err(error)) => {
+                self.operation = None;
+                self.report_error("error.save_report", error);
+
+                Task::none()
+            }
+
+            Message::IndexSaved(Ok(_)) => Task::none(),
+
+            Message::IndexSaved(Err(error)) => {
+                self.report_error("error.save_index", error);
+
+                Task::none()
+            }
+
+            Message::ConfigSaved(Ok(_)) => Task::none(),
+
+            Message::ConfigSaved(Err(error)) => {
+                self.report_error("error.save_settings", error);
+
+                Task::none()
+            }
+
+            Message::SearchQueryInput(query) => {
+                self.search_query = query;
+
+                Task::none()
+            }
+
+            Message::SearchPasswordInput(password) => {
+                self.search_password = password;
+
+                Task::none()
+            }
+
+            Message::SearchSubmitted => {
+                if self.operation.is_some() {
+                    return Task::none();
+                }
+
+                if self.config.save_path.is_empty() {
+                    self.toasts.push(Toast {
+                        title: tr("toast.failed").into(),
+                        body: tr("msg.select_folder_first").into(),
+                        status: Status::Danger,
+                    });
+
+                    return Task::none();
+                }
+
+                self.is_searching = true;
+                self.search_results.clear();
+
+                let folder = PathBuf::from(&self.config.save_path);
+                let password = self.search_password.clone();
+                let query = self.search_query.clone();
+
+                let (operation, task) = progress::start(
+                    "progress.search",
+                    |reporter| search_documents(folder, password, query, reporter),
+                    Message::OperationProgress,
+                    Message::SearchCompleted,
+                );
+
+                self.operation = Some(operation);
+
+                task
+            }
+
+            Message::SearchCompleted(Ok(hits)) => {
+                tracing::info!(hits = hits.len(), "search finished");
+                self.is_searching = false;
+                self.operation = None;
+
+                if hits.is_empty() {
+                    self.toasts.push(Toast {
+                        title: tr("toast.search").into(),
+                        body: tr("msg.no_matches").into(),
+                        status: Status::Secondary,
+                    });
+                }
+
+                self.search_results = hits;
+
+                Task::none()
+            }
+
+            Message::SearchCompleted(Err(error)) => {
+                self.is_searching = false;
+                self.operation = None;
+                self.report_error("error.search_folder", error);
+
+                Task::none()
+            }
+
+            Message::SearchHitSelected(index) => match self.search_results.get(index) {
+                Some(hit) => Task::perform(load_file(hit.path.clone()), Message::SearchHitOpened),
+                None => Task::none(),
+            },
+
+            Message::SearchHitOpened(Ok((path, content))) => {
+                let locked_until =
+                    lockout::read(&content.tail()).and_then(|failures| failures.locked_until());
+
+                self.prompt = Some(PasswordPrompt {
+                    path,
+                    encrypted: content,
+                    password: self.search_password.clone(),
+                    error: None,
+                    attempts: 0,
+                    locked_until,
+                    keypad: self.config.on_screen_keypad.then(Keypad::new),
+                    decrypting: false,
+                    recovered: false,
+                });
+
+                self.update(Message::TryDecrypt)
+            }
+
+            Message::SearchHitOpened(Err(error)) => {
+                self.report_error("error.open_document", error);
+
+                Task::none()
+            }
+
+            Message::OpenDocumentPressed => Task::perform(pick_file(), Message::FileOpened),
+
+            Message::SaveDocumentPressed => {
+                if self.doc_name == String::new() {
+                    self.toasts.push(Toast {
+                        title: tr("toast.failed").into(),
+                        body: tr("msg.open_first").into(),
+                        status: Status::Danger,
+                    });
+
+                    Task::none()
+                } else if let Some(path) = self.conflicting_path() {
+                    self.overwrite = Some(OverwritePrompt {
+                        path,
+                        then_save: true,
+                    });
+
+                    Task::none()
+                } else if self.changed_on_disk() {
+                    let path = self.document_path();
+
+                    // Too large to merge line by line.
+                    if self.pager.is_some() {
+                        self.overwrite = Some(OverwritePrompt {
+                            path,
+                            then_save: true,
+                        });
+
+                        return Task::none();
+                    }
+
+                    tracing::info!(file = document_name(&path), "document changed on disk");
+                    let password = self.password.clone();
+
+                    Task::perform(
+                        async move {
+                            let modified = modified_time(&path);
+
+                            (Document::open(path, &password).await, modified)
+                        },
+                        |(result, modified)| Message::TheirsOpened(result, modified),
+                    )
+                } else {
+                    let path = self.document_path();
+                    let document = self.document();
+                    let password = self.password.clone();
+                    let keys = self.keys.clone();
+
+                    Task::perform(
+                        async move {
+                            let encrypted = document.encrypt_in_background(password, keys).await?;
+
+                            Ok((path, encrypted))
+                        },
+                        Message::Encrypted,
+                    )
+                }
+            }
+
+            Message::Encrypted(Ok((path, encrypted))) => Task::perform(
+                async move {
+                    backup_in_background(path.clone()).await?;
+
+                    save_file(Some(path), encrypted).await
+                },
+                Message::FileSaved,
+            ),
+
+            Message::Encrypted(Err(error)) => {
+                tracing::error!(%error, "couldn't encrypt document");
+                self.error = Some(ErrorReport::new(tr("error.save_document"), error));
+
+                Task::none()
+            }
+
+            Message::Edit(action) => {
+                let is_edit = action.is_edit();
+                self.is_dirty = self.is_dirty || is_edit;
+
+                let indent = match action {
+                    text_editor::Action::Edit(text_editor::Edit::Enter)
+                        if self.config.auto_indent =>
+                    {
+                        self.current_indent()
+                    }
+                    _ => String::new(),
+                };
+
+                self.content.perform(action);
+
+                if !indent.is_empty() {
+                    self.content
+                        .perform(text_editor::Action::Edit(text_editor::Edit::Paste(
+                            Arc::new(indent),
+                        )));
+                }
+
+                if is_edit {
+                    self.document_changed();
+                }
+
+                Task::none()
+            }
+
+            Message::ChecklistItemToggled(line) => {
+                self.toggle_task(line);
+
+                Task::none()
+            }
+
+            Message::NewTableSubmitted => {
+                self.set_text(SecretTable::default().to_text());
+                self.document_changed();
+
+                self.update(Message::NewDocumentSubmitted)
+            }
+
+            Message::TableCellEdited(index, field, value) => {
+                if let Some(row) = self
+                    .table
+                    .as_mut()
+                    .and_then(|table| table.rows.get_mut(index))
+                {
+                    *field.get_mut(row) = value;
+                    self.table_changed();
+                }
+
+                Task::none()
+            }
+
+            Message::TableCellCopied(index, field) => {
+                let Some(row) = self.table.as_ref().and_then(|table| table.rows.get(index)) else {
+                    return Task::none();
+                };
+
+                self.toasts.push(Toast {
+                    title: tr("toast.success").into(),
+                    body: tr("msg.copied_cell").into(),
+                    status: Status::Success,
+                });
+
+                clipboard::write(field.get(row).to_string())
+            }
+
+            Message::TableRowAdded => {
+                if let Some(table) = &mut self.table {
+                    table.rows.push(SecretRow::default());
+                    self.table_changed();
+                }
+
+                Task::none()
+            }
+
+            Message::TableRowRemoved(index) => {
+                if let Some(table) = self.table.as_mut().filter(|table| index < table.rows.len()) {
+                    table.rows.remove(index);
+                    // The rows below moved up, so what was revealed no longer lines up.
+                    self.revealed.clear();
+                    self.table_changed();
+                }
+
+                Task::none()
+            }
+
+            Message::SecretRevealToggled(index) => {
+                if !self.revealed.remove(&index) {
+                    self.revealed.insert(index);
+                }
+
+                Task::none()
+            }
+
+            // Only redraws, so the codes and countdowns move on.
+            Message::TotpTick => Task::none(),
+
+            Message::TotpCopied(code) => {
+                self.toasts.push(Toast {
+                    title: tr("toast.success").into(),
+                    body: tr("msg.copied_code").into(),
+                    status: Status::Success,
+                });
+
+                clipboard::write(code)
+            }
+
+            Message::ToggleTaskPressed => {
+                if self.editor_has_keyboard() {
+                    let (line, _) = self.content.cursor_position();
+                    self.toggle_task(line);
+                }
+
+                Task::none()
+            }
+
+            Message::StatsToggled => {
+                self.stats = match self.stats {
+                    Some(_) => None,
+                    None => Some(TextStats::new(&self.text())),
+                };
+
+                Task::none()
+            }
+
+            Message::InsertImagePressed => Task::perform(pick_image(), Message::ImagePicked),
+
+            Message::ImagePicked(Ok((name, bytes))) => {
+                let markdown = (bytes.len() <= MAX_IMAGE_SIZE)
+                    .then(|| images::markdown(&name, &bytes))
+                    .flatten();
+
+                let Some(markdown) = markdown else {
+                    self.toasts.push(Toast {
+                        title: tr("toast.failed").into(),
+                        body: tr_with("msg.image_not_embedded", format_size(MAX_IMAGE_SIZE as u64)),
+                        status: Status::Danger,
+                    });
+
+                    return Task::none();
+                };
+
+                self.update(Message::Edit(text_editor::Action::Edit(
+                    text_editor::Edit::Paste(Arc::new(markdown)),
+                )))
+            }
+
+            Message::ImagePicked(Err(error)) => {
+                self.report_error("error.insert_image", error);
+
+                Task::none()
+            }
+
+            Message::OutlineToggled => {
+                self.show_outline = !self.show_outline;
+
+                Task::none()
+            }
+
+            Message::HeadingSelected(line) => {
+                // Moving collapses any selection, so the position is read
+                // afterwards.
+                if self.content.selection().is_some() {
+                    self.content
+                        .perform(text_editor::Action::Move(text_editor::Motion::Left));
+                }
+
+                let cursor = self.cursor_offset();
+                let heading = self.char_offset(line, 0);
+                self.move_cursor(cursor, heading);
+
+                Task::none()
+            }
+
+            Message::LinkSelected(name) => {
+                if self.config.save_path.is_empty() {
+                    self.toasts.push(Toast {
+                        title: tr("toast.failed").into(),
+                        body: tr("msg.select_folder_first").into(),
+                        status: Status::Danger,
+                    });
+
+                    return Task::none();
+                }
+
+                let path = Vault::new(&self.config.save_path).path_for(&name);
+
+                // A name that isn't a valid file name, like one with a path in
+                // it, can't be a document in the save folder.
+                if validate_document_name(&name).is_err() || !path.exists() {
+                    self.toasts.push(Toast {
+                        title: tr("toast.failed").into(),
+                        body: tr_with("msg.link_missing", name).into(),
+                        status: Status::Danger,
+                    });
+
+                    return Task::none();
+                }
+
+                Task::perform(load_file(path), Message::LinkOpened)
+            }
+
+            Message::BacklinkSelected(path) => Task::perform(load_file(path), Message::LinkOpened),
+
+            Message::LinkOpened(Ok((path, content))) => {
+                let locked_until =
+                    lockout::read(&content.tail()).and_then(|failures| failures.locked_until());
+
+                // Linked documents usually share a password, so the open
+                // document's is tried first. If it's wrong the prompt stays
+                // up for the right one.
+                self.prompt = Some(PasswordPrompt {
+                    path,
+                    encrypted: content,
+                    password: self.password.clone(),
+                    error: None,
+                    attempts: 0,
+                    locked_until,
+                    keypad: self.config.on_screen_keypad.then(Keypad::new),
+                    decrypting: false,
+                    recovered: false,
+                });
+
+                self.update(Message::TryDecrypt)
+            }
+
+            Message::LinkOpened(Err(error)) => {
+                self.report_error("error.open_document", error);
+
+                Task::none()
+            }
+
+            Message::BacklinksFound(path, backlinks) => {
+                // The document may have been closed or swapped meanwhile.
+                if self.path.as_ref() == Some(&path) {
+                    self.backlinks = backlinks;
+                }
+
+                Task::none()
+            }
+
+            Message::RedactPressed => {
+                let Some(selection) = self.content.selection() else {
+                    return Task::none();
+                };
+
+                self.content
+                    .perform(text_editor::Action::Edit(text_editor::Edit::Paste(
+                        Arc::new(redact::mark(&selection)),
+                    )));
+
+                self.is_dirty = true;
+                self.document_changed();
+
+                Task::none()
+            }
+
+            Message::RedactedViewToggled => {
+                self.show_redacted = !self.show_redacted;
+
+                Task::none()
+            }
+
+            Message::ComparePressed => {
+                let Some(path) = self.path.clone() else {
+                    return Task::none();
+                };
+
+                Task::perform(find_revisions(path), Message::RevisionsFound)
+            }
+
+            Message::RevisionsFound(revisions) => {
+                if revisions.is_empty() {
+                    self.toasts.push(Toast {
+                        title: tr("viewer.compare").into(),
+                        body: tr("msg.no_revisions").into(),
+                        status: Status::Secondary,
+                    });
+
+                    return Task::none();
+                }
+
+                let compare = Compare::new(revisions);
+                let revision = compare.selected.clone();
+                self.compare = Some(compare);
+
+                self.update(Message::RevisionSelected(revision))
+            }
+
+            Message::RevisionSelected(revision) => {
+                let Some(compare) = &mut self.compare else {
+                    return Task::none();
+                };
+
+                compare.select(revision.clone());
+                let password = self.password.clone();
+
+                Task::perform(
+                    async move { Document::open(revision.path, &password).await },
+                    Message::RevisionOpened,
+                )
+            }
+
+            Message::RevisionOpened(Ok(document)) => {
+                let current = self.content.text();
+
+                if let Some(compare) = &mut self.compare {
+                    compare.set_old(document.text, &current);
+                }
+
+                Task::none()
+            }
+
+            Message::RevisionOpened(Err(error)) => {
+                tracing::warn!(%error, "couldn't open backup to compare");
+                self.compare = None;
+
+                self.toasts.push(Toast {
+                    title: tr("toast.failed").into(),
+                    body: match error {
+                        // The password has been changed since the backup.
+                        DocumentError::WrongPassword => tr("msg.revision_password").into(),
+                        error => error.to_string(),
+                    },
+                    status: Status::Danger,
+                });
+
+                Task::none()
+            }
+
+            Message::HunkRestored(index) => {
+                let current = self.content.text();
+
+                let Some(restored) = self
+                    .compare
+                    .as_mut()
+                    .and_then(|compare| compare.restore(index, &current))
+                else {
+                    return Task::none();
+                };
+
+                self.content = text_editor::Content::with_text(&restored);
+                self.is_dirty = true;
+                self.document_changed();
+
+                Task::none()
+            }
+
+            Message::CompareClosed => {
+                self.compare = None;
+
+                Task::none()
+            }
+
+            Message::TheirsOpened(Ok(theirs), modified) => {
+                let merge = Merge::new(&self.base, &self.content.text(), &theirs.text, modified);
+
+                if merge.text().is_none() {
+                    self.merge = Some(merge);
+
+                    return Task::none();
+                }
+
+                // Nothing conflicted, so the merge can be saved straight away.
+                self.toasts.push(Toast {
+                    title: tr("merge.toast").into(),
+                    body: tr("msg.merged").into(),
+                    status: Status::Secondary,
+                });
+
+                self.apply_merge(merge)
+            }
+
+            Message::TheirsOpened(Err(error), _) => {
+                tracing::warn!(%error, "couldn't open changed document to merge");
+
+                self.toasts.push(Toast {
+                    title: tr("toast.failed").into(),
+                    body: tr_with("msg.merge_failed", error),
+                    status: Status::Danger,
+                });
+
+                // Merging isn't possible, e.g. as the password was changed on
+                // the other machine, so all that's left is replacing it.
+                self.overwrite = Some(OverwritePrompt {
+                    path: self.document_path(),
+                    then_save: true,
+                });
+
+                Task::none()
+            }
+
+            Message::MergeSideChosen(index, side) => {
+                if let Some(merge) = &mut self.merge {
+                    merge.resolve(index, side);
+                }
+
+                Task::none()
+            }
+
+            Message::MergeCompleted => {
+                let Some(merge) = self.merge.take() else {
+                    return Task::none();
+                };
+
+                self.apply_merge(merge)
+            }
+
+            Message::MergeCancelled => {
+                self.merge = None;
+
+                Task::none()
+            }
+
+            Message::TabWidthSelected(tab_width) => {
+                self.config.tab_width = tab_width;
+
+                self.update(Message::SaveConfig)
+            }
+
+            Message::IndentWithSpacesToggled(indent_with_spaces) => {
+                self.config.indent_with_spaces = indent_with_spaces;
+
+                self.update(Message::SaveConfig)
+            }
+
+            Message::AutoIndentToggled(auto_indent) => {
+                self.config.auto_indent = auto_indent;
+
+                self.update(Message::SaveConfig)
+            }
+
+            Message::InsertDatePressed => {
+                if !self.editor_has_keyboard() {
+                    return Task::none();
+                }
+
+                match format_now(&self.config.date_format) {
+                    Some(date) => self.update(Message::Edit(text_editor::Action::Edit(
+                        text_editor::Edit::Paste(Arc::new(date)),
+                    ))),
+                    None => {
+                        self.toasts.push(Toast {
+                            title: tr("toast.failed").into(),
+                            body: tr("msg.invalid_date_format").into(),
+                            status: Status::Danger,
+                        });
+
+                        Task::none()
+                    }
+                }
+            }
+
+            Message::DateFormatInput(date_format) => {
+                self.config.date_format = date_format;
+
+                self.update(Message::SaveConfig)
+            }
+
+            Message::PostSaveHookToggled(enabled) => {
+                self.config.post_save_hook_enabled = enabled;
+
+                self.update(Message::SaveConfig)
+            }
+
+            Message::PostSaveHookInput(command) => {
+                self.config.post_save_hook = command;
+
+                self.update(Message::SaveConfig)
+            }
+
+            Message::PostSaveHookFinished(Ok(())) => Task::none(),
+
+            Message::ProfileDocumentsToggled(documents) => {
+                self.profile_documents = documents;
+
+                Task::none()
+            }
+
+            Message::ExportProfilePressed => {
+                let config = self.config.clone();
+                let index = self.index.clone();
+                let documents = self.profile_documents;
+
+                Task::perform(
+                    async move {
+                        let path = pick_profile_destination().await?;
+
+                        profile::export(path, config, index, documents).await
+                    },
+                    Message::ProfileExported,
+                )
+            }
+
+            Message::ProfileExported(Ok(path)) => {
+                tracing::info!(path = %path.display(), "exported profile");
+
+                self.toasts.push(Toast {
+                    title: tr("toast.success").into(),
+                    body: tr_with("msg.profile_exported", pathbuf_to_string(&path)),
+                    status: Status::Success,
+                });
+
+                Task::none()
+            }
+
+            Message::ProfileExported(Err(error)) => {
+                self.report_error("error.export_profile", error);
+
+                Task::none()
+            }
+
+            Message::ImportProfilePressed => Task::perform(
+                async {
+                    let path = pick_profile().await?;
+                    let folder = pick_profile_folder().await?;
+
+                    profile::import(path, folder).await
+                },
+                Message::ProfileImported,
+            ),
+
+            Message::ProfileImported(Ok(imported)) => {
+                tracing::info!(
+                    documents = imported.documents,
+                    skipped = imported.skipped,
+                    "imported profile"
+                );
+
+                self.config = imported.config;
+                self.index = imported.index;
+                i18n::set_language(self.config.language);
+
+                self.toasts.push(Toast {
+                    title: tr("toast.success").into(),
+                    body: tr_with("msg.profile_imported", imported.documents),
+                    status: Status::Success,
+                });
+
+                if imported.skipped > 0 {
+                    self.toasts.push(Toast {
+                        title: tr("toast.profile").into(),
+                        body: tr_with("msg.profile_skipped", imported.skipped),
+                        status: Status::Secondary,
+                    });
+                }
+
+                if imported.hooks_removed {
+                    self.toasts.push(Toast {
+                        title: tr("toast.profile").into(),
+                        body: tr("msg.profile_hooks_removed").into(),
+                        status: Status::Secondary,
+                    });
+                }
+
+                Task::perform(save_config(self.config.clone()), Message::ConfigSaved)
+            }
+
+            Message::ProfileImported(Err(error)) => {
+                self.report_error("error.import_profile", error);
+
+                Task::none()
+            }
+
+            Message::SelfTestPressed => {
+                self.is_self_testing = true;
+
+                Task::perform(
+                    async { tokio::task::spawn_blocking(selftest::run).await.ok() },
+                    Message::SelfTestFinished,
+                )
+            }
+
+            Message::SelfTestFinished(checks) => {
+                self.is_self_testing = false;
+
+                let Some(checks) = checks else {
+                    self.toasts.push(Toast {
+                        title: tr("toast.failed").into(),
+                        body: tr("msg.self_test_interrupted").into(),
+                        status: Status::Danger,
+                    });
+
+                    return Task::none();
+                };
+
+                for check in &checks {
+                    tracing::info!(check = check.name, passed = check.passed, "self-test");
+                }
+
+                self.toasts.push(if selftest::all_passed(&checks) {
+                    Toast {
+                        title: tr("toast.success").into(),
+                        body: tr_with("msg.self_test_passed", checks.len()),
+                        status: Status::Success,
+                    }
+                } else {
+                    let failed: Vec<&str> = checks
+                        .iter()
+                        .filter(|check| !check.passed)
+                        .map(|check| check.name)
+                        .collect();
+
+                    Toast {
+                        title: tr("toast.failed").into(),
+                        body: tr_with("msg.self_test_failed", failed.join(", ")),
+                        status: Status::Danger,
+                    }
+                });
+
+                Task::none()
+            }
+
+            Message::BenchPressed => {
+                self.is_benchmarking = true;
+
+                Task::perform(
+                    async { tokio::task::spawn_blocking(bench::run).await.ok() },
+                    Message::BenchFinished,
+                )
+            }
+
+            Message::BenchFinished(results) => {
+                self.is_benchmarking = false;
+
+                if let Some(results) = &results {
+                    tracing::info!(key_derivation_ms = results.key_derivation, "benchmark");
+
+                    for throughput in &results.throughput {
+                        tracing::info!(
+                            size = throughput.size,
+                            encrypt_mb_s = throughput.encrypt,
+                            decrypt_mb_s = throughput.decrypt,
+                            verified = throughput.verified,
+                            "benchmark"
+                        );
+                    }
+                }
+
+                if !results.as_ref().is_some_and(bench::Results::all_verified) {
+                    self.toasts.push(Toast {
+                        title: tr("toast.failed").into(),
+                        body: tr("msg.bench_failed").into(),
+                        status: Status::Danger,
+                    });
+                }
+
+                self.bench = results;
+
+                Task::none()
+            }
+
+            Message::PostSaveHookFinished(Err(error)) => {
+                tracing::warn!(%error, "post-save command failed");
+                self.toasts.push(Toast {
+                    title: tr("toast.failed").into(),
+                    body: tr_with("msg.hook_failed", error),
+                    status: Status::Danger,
+                });
+
+                Task::none()
+            }
+
+            Message::DocumentInput(content) => {
+                // An empty name is only worth flagging once the user submits.
+                self.name_error = validate_document_name(&content)
+                    .err()
+                    .filter(|error| *error != NameError::Empty);
+                self.doc_name = content;
+
+                Task::none()
+            }
+
+            Message::PasswordInput(content) => {
+                self.password = content;
+
+                Task::none()
+            }
+
+            Message::NewDocumentSubmitted => {
+                self.doc_name = match sanitize_document_name(&self.doc_name) {
+                    name if name.is_empty() => tr("new.untitled").to_string(),
+                    name => name,
+                };
+                self.name_error = None;
+
+                if let Some(path) = self.conflicting_path() {
+                    self.overwrite = Some(OverwritePrompt {
+                        path,
+                        then_save: false,
+                    });
+
+                    return Task::none();
+                }
+
+                self.current_page = Page::DocumentViewer;
+
+                Task::none()
+            }
+
+            Message::OverwriteConfirmed => {
+                let Some(overwrite) = self.overwrite.take() else {
+                    return Task::none();
+                };
+
+                // Adopting the path marks the existing file as ours, so later
+                // saves of this document don't ask again.
+                self.disk_modified = modified_time(&overwrite.path)
+                    .map(|modified| (overwrite.path.clone(), modified));
+                self.path = Some(overwrite.path);
+
+                if overwrite.then_save {
+                    self.update(Message::SaveDocumentPressed)
+                } else {
+                    self.current_page = Page::DocumentViewer;
+
+                    Task::none()
+                }
+            }
+
+            Message::OverwriteRenamed => {
+                self.overwrite = None;
+
+                // Unlike NewDocumentPressed, this keeps the content so it can be
+                // saved under the new name.
+                self.current_page = Page::NewDocumentPage;
+
+                self.focus_page()
+            }
+
+            Message::OverwriteCancelled => {
+                self.overwrite = None;
+
+                Task::none()
+            }
+
+            Message::FolderSelected(Ok(path)) => {
+                self.config.save_path = pathbuf_to_string(&path);
+                self.index = VaultIndex::load(&path);
+
+                Task::perform(
+                    save_config(self.config.clone()),
+                    Message::FolderPathFileSaved,
+                )
+            }
+            Message::FolderSelected(Err(error)) => {
+                self.report_error("error.select_folder", error);
+
+                Task::none()
+            }
+            Message::FileOpened(Ok((path, content))) => {
+                match inspect(content.as_bytes()) {
+                    Ok(info) => tracing::info!(
+                        file = document_name(&path),
+                        version = info.version,
+                        size = info.ciphertext_size,
+                        "opened document"
+                    ),
+                    Err(_) => tracing::warn!(
+                        file = document_name(&path),
+                        "opened file with an unrecognized format"
+                    ),
+                }
+
+                let locked_until =
+                    lockout::read(&content.tail()).and_then(|failures| failures.locked_until());
+
+                self.prompt = Some(PasswordPrompt {
+                    path,
+                    encrypted: content,
+                    password: String::new(),
+                    error: None,
+                    attempts: 0,
+                    locked_until,
+                    keypad: self.config.on_screen_keypad.then(Keypad::new),
+                    decrypting: false,
+                    recovered: false,
+                });
+
+                text_input::focus(password_prompt_id())
+            }
+
+            Message::FileOpened(Err(error)) => {
+                self.report_error("error.open_document", error);
+
+                Task::none()
+            }
+
+            Message::PromptPasswordInput(password) => {
+                // The password is fixed while it's being tried.
+                if let Some(prompt) = self.prompt.as_mut().filter(|prompt| !prompt.decrypting) {
+                    prompt.password = password;
+                }
+
+                Task::none()
+            }
+
+            Message::KeypadToggled => {
+                if let Some(prompt) = &mut self.prompt {
+                    prompt.keypad = match prompt.keypad {
+                        Some(_) => None,
+                        None => Some(Keypad::new()),
+                    };
+                }
+
+                Task::none()
+            }
+
+            Message::KeypadPressed(c) => {
+                if let Some(prompt) = self.prompt.as_mut().filter(|prompt| !prompt.decrypting) {
+                    prompt.password.push(c);
+
+                    if let Some(keypad) = &mut prompt.keypad {
+                        keypad.shuffle();
+                    }
+                }
+
+                Task::none()
+            }
+
+            Message::KeypadBackspace => {
+                if let Some(prompt) = self.prompt.as_mut().filter(|prompt| !prompt.decrypting) {
+                    prompt.password.pop();
+                }
+
+                Task::none()
+            }
+
+            Message::KeypadShift => {
+                if let Some(keypad) = self.prompt.as_mut().and_then(|p| p.keypad.as_mut()) {
+                    keypad.toggle_shift();
+                }
+
+                Task::none()
+            }
+
+            Message::PromptCancelled => {
+                self.prompt = None;
+
+                self.focus_page()
+            }
+
+            Message::CapsLockDetected(caps_lock) => {
+                self.caps_lock = caps_lock;
+
+                Task::none()
+            }
+            Message::CapsLockToggled => {
+                self.caps_lock = !self.caps_lock;
+
+                Task::none()
+            }
+            Message::EscapePressed => {
+                if self.toolbar_focus.is_some() {
+                    self.toolbar_focus = None;
+
+                    Task::none()
+                } else if self.capture.is_some() {
+                    self.update(Message::CaptureCancelled)
+                } else if self.overwrite.is_some() {
+                    self.update(Message::OverwriteCancelled)
+                } else if self.reauth.is_some() {
+                    self.update(Message::ReauthCancelled)
+                } else if self.vault_prompt.is_some() {
+                    self.update(Message::VaultPromptCancelled)
+                } else if self.vault_removal {
+                    self.update(Message::RemoveVaultCancelled)
+                } else {
+                    self.update(Message::PromptCancelled)
+                }
+            }
+
+            Message::TryDecrypt => {
+                let Some(prompt) = &mut self.prompt else {
+                    return Task::none();
+                };
+
+                if prompt.decrypting {
+                    return Task::none();
+                }
+
+                if prompt.locked_until.is_some_and(|until| until > Utc::now()) {
+                    return Task::none();
+                }
+
+                if self
+                    .retained
+                    .as_ref()
+                    .is_some_and(|retained| retained.matches(&prompt.path, &prompt.password))
+                {
+                    let document = self
+                        .retained
+                        .take()
+                        .expect("It was just checked")
+                        .into_document();
+
+                    tracing::info!(
+                        file = document_name(&prompt.path),
+                        "reopened retained document"
+                    );
+
+                    return self.update(Message::Decrypted(Ok(document)));
+                }
+
+                prompt.decrypting = true;
+
+                Task::perform(
+                    Document::decrypt_in_background(
+                        prompt.encrypted.clone(),
+                        prompt.password.clone(),
+                    ),
+                    Message::Decrypted,
+                )
+            }
+
+            Message::Decrypted(result) => {
+                // The prompt may have been cancelled while decrypting.
+                let Some(prompt) = &mut self.prompt else {
+                    return Task::none();
+                };
+
+                prompt.decrypting = false;
+
+                match &result {
+                    Ok(document) => tracing::info!(
+                        file = document_name(&prompt.path),
+                        encoding = %document.encoding,
+                        line_ending = %document.line_ending,
+                        "decrypted document"
+                    ),
+                    Err(error) => tracing::warn!(
+                        file = document_name(&prompt.path),
+                        %error,
+                        "couldn't decrypt document"
+                    ),
+                }
+
+                match result {
+                    Ok(document) => {
+                        if document.encoding != Encoding::Utf8 {
+                            self.toasts.push(Toast {
+                                title: tr("toast.encoding").into(),
+                                body: tr_with("msg.converted_encoding", document.encoding),
+                                status: Status::Secondary,
+                            });
+                        }
+
+                        if document.container == Container::CryptoDoc
+                            && format_version(prompt.encrypted.as_bytes())
+                                .is_ok_and(|version| version < FORMAT_VERSION)
+                        {
+                            self.toasts.push(Toast {
+                                title: tr("toast.old_format").into(),
+                                body: tr("msg.old_format").into(),
+                                status: Status::Secondary,
+                            });
+                        }
+
+                        let clear_failures = !prompt.recovered
+                            && (prompt.attempts > 0
+                                || lockout::read(&prompt.encrypted.tail()).is_some());
+
+                        // The encrypted text isn't needed anymore, so let it
+                        // go before the editor makes its own copy.
+                        prompt.encrypted = Arc::new(String::new().into());
+
+                        self.line_ending = document.line_ending;
+                        self.container = document.container;
+                        self.history = document.history;
+                        // Recovered edits still need saving to the real file.
+                        self.is_dirty = prompt.recovered;
+                        self.password = std::mem::take(&mut prompt.password);
+                        self.keys.clear();
+                        self.doc_name = pathbuf_to_string(&prompt.path);
+                        self.path = Some(prompt.path.clone());
+                        // A recovery journal can't tell what the file held
+                        // when it was written, so there's nothing to merge
+                        // against.
+                        (self.base, self.disk_modified) = if prompt.recovered {
+                            (String::new(), None)
+                        } else {
+                            (
+                                document.text.clone(),
+                                modified_time(&prompt.path)
+                                    .map(|modified| (prompt.path.clone(), modified)),
+                            )
+                        };
+                        self.set_text(document.text);
+                        self.current_page = Page::DocumentViewer;
+                        self.config.add_recent(prompt.path.clone());
+                        self.prompt = None;
+                        self.backlinks.clear();
+                        self.document_changed();
+
+                        let backlinks = if self.config.save_path.is_empty() {
+                            Task::none()
+                        } else {
+                            let path = prompt.path.clone();
+
+                            Task::perform(
+                                find_backlinks(
+                                    PathBuf::from(&self.config.save_path),
+                                    self.password.clone(),
+                                    path.clone(),
+                                ),
+                                move |backlinks| Message::BacklinksFound(path, backlinks),
+                            )
+                        };
+
+                        let clear_failures = match (clear_failures, self.path.clone()) {
+                            (true, Some(path)) => Task::perform(
+                                lockout::clear_in_background(path.clone()),
+                                move |result| Message::FailuresCleared(path, result),
+                            ),
+                            _ => Task::none(),
+                        };
+
+                        return Task::batch([
+                            audit::record(AuditEvent::Opened {
+                                document: self.doc_name.clone(),
+                            }),
+                            clear_failures,
+                            backlinks,
+                            self.update(Message::SaveConfig),
+                        ]);
+                    }
+                    Err(DocumentError::WrongPassword) => {
+                        prompt.error = Some(tr("prompt.wrong_password").into());
+                        prompt.attempts += 1;
+                        prompt.password.clear();
+
+                        // A recovery journal isn't the document, so there's
+                        // nothing to record the attempt in.
+                        let record = if prompt.recovered {
+                            Task::none()
+                        } else {
+                            Task::perform(
+                                lockout::record_failure_in_background(prompt.path.clone()),
+                                Message::FailureRecorded,
+                            )
+                        };
+
+                        return Task::batch([
+                            audit::record(AuditEvent::FailedUnlock {
+                                document: pathbuf_to_string(&prompt.path),
+                            }),
+                            record,
+                            text_input::focus(password_prompt_id()),
+                        ]);
+                    }
+                    // None of these can be fixed by trying another password.
+                    Err(DocumentError::BadFormat) => {
+                        prompt.error = Some(tr("prompt.invalid_document").into());
+                    }
+                    Err(DocumentError::Corrupt) => {
+                        prompt.error = Some(tr("prompt.corrupt_document").into());
+                    }
+                    Err(DocumentError::UnsupportedVersion(version)) => {
+                        prompt.error = Some(tr_with("prompt.unsupported_version", version));
+                    }
+                    Err(DocumentError::Locked(until)) => {
+                        prompt.locked_until = Some(until);
+                    }
+                    Err(DocumentError::Io(kind)) => {
+                        prompt.error = Some(tr_with("error.io", kind));
+                    }
+                }
+
+                text_input::focus(password_prompt_id())
+            }
+
+            Message::FileSaved(Ok(path)) => {
+                tracing::info!(file = document_name(&path), "saved document");
+                self.config.add_recent(path.clone());
+                self.disk_modified = modified_time(&path).map(|modified| (path.clone(), modified));
+                self.base = self.text();
+                self.path = Some(path.clone());
+                self.is_dirty = false;
+
+                self.toasts.push(Toast {
+                    title: tr("toast.success").into(),
+                    body: tr("msg.document_saved").into(),
+                    status: Status::Success,
+                });
+
+                let mut tasks = vec![
+                    self.update(Message::SaveConfig),
+                    Task::perform(recovery::discard(), Message::RecoveryCleared),
+                ];
+
+                if self.config.post_save_hook_enabled
+                    && !self.config.post_save_hook.trim().is_empty()
+                {
+                    tasks.push(Task::perform(
+                        run_post_save(self.config.post_save_hook.clone(), path),
+                        Message::PostSaveHookFinished,
+                    ));
+                }
+
+                Task::batch(tasks)
+            }
+
+            Message::FileSaved(Err(error)) => {
+                self.report_error("error.save_document", error);
+
+                Task::none()
+            }
+
+            Message::PlaintextRequested(action) => {
+                if !self.config.require_reauth {
+                    return self.run_plaintext_action(action);
+                }
+
+                self.reauth = Some(ReauthPrompt {
+                    action,
+                    password: String::new(),
+                    error: None,
+                });
+
+                text_input::focus(reauth_prompt_id())
+            }
+
+            Message::ReauthPasswordInput(password) => {
+                if let Some(reauth) = &mut self.reauth {
+                    reauth.password = password;
+                }
+
+                Task::none()
+            }
+
+            Message::ReauthSubmitted => {
+                let Some(reauth) = &mut self.reauth else {
+                    return Task::none();
+                };
+
+                if reauth.password != self.password {
+                    reauth.error = Some(tr("prompt.wrong_password").into());
+                    reauth.password.clear();
+
+                    return Task::batch([
+                        audit::record(AuditEvent::FailedUnlock {
+                            document: self.doc_name.clone(),
+                        }),
+                        text_input::focus(reauth_prompt_id()),
+                    ]);
+                }
+
+                let action = reauth.action;
+                self.reauth = None;
+
+                self.run_plaintext_action(action)
+            }
+
+            Message::ReauthCancelled => {
+                self.reauth = None;
+
+                Task::none()
+            }
+
+            Message::Exported(Ok(_)) => {
+                self.toasts.push(Toast {
+                    title: tr("toast.success").into(),
+                    body: tr("msg.exported").into(),
+                    status: Status::Success,
+                });
+
+                audit::record(AuditEvent::Exported {
+                    document: self.doc_name.clone(),
+                })
+            }
+
+            Message::Exported(Err(error)) => {
+                self.report_error("error.export", error);
+
+                Task::none()
+            }
+
+            Message::ArmorRequested(action) => {
+                let document = self.document();
+                let password = self.password.clone();
+
+                Task::perform(
+                    async move {
+                        tokio::task::spawn_blocking(move || document.encrypt_armored(&password))
+                            .await
+                            .map_err(|_| DocumentError::Io(io::ErrorKind::Other))
+                    },
+                    move |result| Message::Armored(action, result),
+                )
+            }
+
+            Message::Armored(ArmorAction::Copy, Ok(armored)) => {
+                self.toasts.push(Toast {
+                    title: tr("toast.success").into(),
+                    body: tr("msg.copied_armored").into(),
+                    status: Status::Success,
+                });
+
+                clipboard::write(armored)
+            }
+
+            Message::Armored(ArmorAction::Export, Ok(armored)) => Task::perform(
+                export_armored(self.doc_name.clone(), armored),
+                Message::ArmoredExported,
+            ),
+
+            Message::Armored(_, Err(error)) => {
+                tracing::error!(%error, "couldn't armor document");
+                self.error = Some(ErrorReport::new(tr("error.armor"), error));
+
+                Task::none()
+            }
+
+            Message::ArmoredExported(Ok(path)) => {
+                self.toasts.push(Toast {
+                    title: tr("toast.success").into(),
+                    body: tr_with("msg.exported_armored", pathbuf_to_string(&path)),
+                    status: Status::Success,
+                });
+
+                Task::none()
+            }
+
+            Message::ArmoredExported(Err(error)) => {
+                self.report_error("error.export_armored", error);
+
+                Task::none()
+            }
+
+            Message::ShareSealed(Ok((key, bundle))) => Task::perform(
+                export_armored(
+                    tr_with("share.file_name", document_name(&self.document_path())),
+                    bundle,
+                ),
+                move |result| Message::ShareExported(key.clone(), result),
+            ),
+
+            Message::ShareSealed(Err(error)) => {
+                tracing::error!(%error, "couldn't seal share bundle");
+                self.error = Some(ErrorReport::new(tr("error.share"), error));
+
+                Task::none()
+            }
+
+            Message::ShareExported(key, Ok(path)) => {
+                tracing::info!(file = document_name(&path), "exported share bundle");
+
+                self.share = Some(SharePrompt {
+                    qr: qr::render(&key),
+                    key,
+                    path,
+                });
+
+                Task::none()
+            }
+
+            Message::ShareExported(_, Err(error)) => {
+                self.report_error("error.share", error);
+
+                Task::none()
+            }
+
+            Message::ShareKeyCopied => {
+                let Some(share) = &self.share else {
+                    return Task::none();
+                };
+
+                self.toasts.push(Toast {
+                    title: tr("toast.success").into(),
+                    body: tr("msg.copied_share_key").into(),
+                    status: Status::Success,
+                });
+
+                clipboard::write(share.key.clone())
+            }
+
+            Message::ShareClosed => {
+                self.share = None;
+
+                Task::none()
+            }
+
+            Message::ClipboardPasswordInput(password) => {
+                if let Some(prompt) = &mut self.clipboard_prompt {
+                    prompt.password = password;
+                }
+
+                Task::none()
+            }
+
+            Message::ClipboardSubmitted => match &mut self.clipboard_prompt {
+                Some(prompt) if !prompt.working && !prompt.password.is_empty() => {
+                    prompt.working = true;
+                    prompt.error = None;
+
+                    clipboard::read(Message::ClipboardRead)
+                }
+                _ => Task::none(),
+            },
+
+            Message::ClipboardCancelled => {
+                if self
+                    .clipboard_prompt
+                    .as_ref()
+                    .is_some_and(|prompt| !prompt.working)
+                {
+                    self.clipboard_prompt = None;
+                }
+
+                Task::none()
+            }
+
+            Message::ClipboardRead(contents) => {
+                let Some(prompt) = &mut self.clipboard_prompt else {
+                    return Task::none();
+                };
+
+                let Some(contents) = contents.filter(|contents| !contents.trim().is_empty()) else {
+                    prompt.working = false;
+                    prompt.error = Some(tr("clipboard.empty").into());
+
+                    return Task::none();
+                };
+
+                // A document copied whole carries its failed attempts, which
+                // can't be added to without the file, but its cool-down is
+                // still kept.
+                if let Some(until) =
+                    lockout::read(contents.trim()).and_then(|failures| failures.locked_until())
+                {
+                    prompt.working = false;
+                    prompt.error = Some(locked_message(until));
+                    prompt.password.clear();
+
+                    return text_input::focus(clipboard_prompt_id());
+                }
+
+                let action = prompt.action;
+                let password = prompt.password.clone();
+
+                Task::perform(
+                    async move {
+                        match action {
+                            ClipboardAction::Encrypt => tokio::task::spawn_blocking(move || {
+                                Document::new(contents).encrypt_armored(&password)
+                            })
+                            .await
+                            .map_err(|_| DocumentError::Io(io::ErrorKind::Other)),
+                            ClipboardAction::Decrypt => {
+                                let document = Document::decrypt_in_background(
+                                    Arc::new(contents.trim().to_string().into()),
+                                    password,
+                                )
+                                .await?;
+
+                                Ok(document.line_ending.apply(&document.text))
+                            }
+                        }
+                    },
+                    Message::ClipboardProcessed,
+                )
+            }
+
+            Message::ClipboardProcessed(Ok(contents)) => {
+                let Some(prompt) = self.clipboard_prompt.take() else {
+                    return Task::none();
+                };
+
+                self.toasts.push(Toast {
+                    title: tr("toast.success").into(),
+                    body: match prompt.action {
+                        ClipboardAction::Encrypt => tr("msg.clipboard_encrypted"),
+                        ClipboardAction::Decrypt => tr("msg.clipboard_decrypted"),
+                    }
+                    .into(),
+                    status: Status::Success,
+                });
+
+                clipboard::write(contents)
+            }
+
+            Message::ClipboardProcessed(Err(error)) => {
+                let Some(prompt) = &mut self.clipboard_prompt else {
+                    return Task::none();
+                };
+
+                prompt.working = false;
+                prompt.error = Some(match error {
+                    DocumentError::WrongPassword => tr("prompt.wrong_password").into(),
+                    DocumentError::BadFormat => tr("clipboard.not_encrypted").into(),
+                    error => error.to_string(),
+                });
+                prompt.password.clear();
+
+                text_input::focus(clipboard_prompt_id())
+            }
+
+            Message::PasteArmoredPressed => clipboard::read(Message::ArmoredPasted),
+
+            Message::ArmoredPasted(contents) => {
+                let Some(encrypted) = contents
+                    .filter(|contents| armor::is_armored(contents))
+                    .and_then(|contents| armor::dearmor(&contents).ok())
+                else {
+                    self.toasts.push(Toast {
+                        title: tr("toast.failed").into(),
+                        body: tr("msg.no_armored_document").into(),
+                        status: Status::Danger,
+                    });
+
+                    return Task::none();
+                };
+
+                // The pasted document is stored exactly as it was sent, and
+                // then opened like any other file.
+                let path = Vault::new(&self.config.save_path)
+                    .unused_path_for(tr("armor.pasted_name"))
+                    .with_extension(Container::detect(&encrypted).extension());
+
+                Task::perform(
+                    async move {
+                        save_file(Some(path.clone()), encrypted).await?;
+
+                        load_file(path).await
+                    },
+                    Message::FileOpened,
+                )
+            }
+
+            Message::ArchiveImportRequested(importer) => {
+                Task::perform(pick_archive(importer), move |result| {
+                    Message::ArchivePicked(importer, result)
+                })
+            }
+
+            Message::ArchivePicked(importer, Ok(path)) => {
+                self.archive_import = Some(ArchivePrompt {
+                    importer,
+                    path,
+                    archive_password: String::new(),
+                    password: String::new(),
+                    error: None,
+                    importing: false,
+                });
+
+                text_input::focus(archive_prompt_id())
+            }
+
+            Message::ArchivePicked(_, Err(error)) => {
+                self.report_error("error.import", error);
+
+                Task::none()
+            }
+
+            Message::ArchivePasswordInput(password) => {
+                if let Some(prompt) = &mut self.archive_import {
+                    prompt.archive_password = password;
+                }
+
+                Task::none()
+            }
+
+            Message::ArchiveDocumentPasswordInput(password) => {
+                if let Some(prompt) = &mut self.archive_import {
+                    prompt.password = password;
+                }
+
+                Task::none()
+            }
+
+            Message::ArchiveImportSubmitted => {
+                let Some(prompt) = &mut self.archive_import else {
+                    return Task::none();
+                };
+
+                if !prompt.can_submit() {
+                    return Task::none();
+                }
+
+                prompt.importing = true;
+                prompt.error = None;
+
+                Task::perform(
+                    import_archive(
+                        prompt.importer,
+                        prompt.path.clone(),
+                        prompt.archive_password.clone(),
+                        Vault::new(&self.config.save_path),
+                        prompt.password.clone(),
+                    ),
+                    Message::ArchiveImported,
+                )
+            }
+
+            Message::ArchiveImportCancelled => {
+                // An import that's already running finishes regardless, so
+                // keep the prompt until it does.
+                if self
+                    .archive_import
+                    .as_ref()
+                    .is_some_and(|prompt| !prompt.importing)
+                {
+                    self.archive_import = None;
+                }
+
+                Task::none()
+            }
+
+            Message::ArchiveImported(Ok(results)) => {
+                self.archive_import = None;
+
+                let failed = results
+                    .iter()
+                    .filter(|imported| imported.result.is_err())
+                    .count();
+
+                let mut tagged = false;
+
+                for imported in &results {
+                    match &imported.result {
+                        Ok(path) if !imported.tags.is_empty() => {
+                            self.index.update(path, |meta| {
+                                for tag in &imported.tags {
+                                    meta.add_tag(tag);
+                                }
+                            });
+                            tagged = true;
+                        }
+                        Ok(_) => {}
+                        Err(error) => {
+                            tracing::error!(
+                                name = imported.name,
+                                %error,
+                                "couldn't import document"
+                            );
+                        }
+                    }
+                }
+
+                tracing::info!(
+                    imported = results.len() - failed,
+                    failed,
+                    "imported archive"
+                );
+
+                self.toasts.push(if failed == 0 {
+                    Toast {
+                        title: tr("toast.success").into(),
+                        body: tr_with("msg.archive_imported", results.len()),
+                        status: Status::Success,
+                    }
+                } else {
+                    Toast {
+                        title: tr("toast.failed").into(),
+                        body: tr_with("msg.archive_import_failed", failed),
+                        status: Status::Danger,
+                    }
+                });
+
+                let save_index = tagged.then(|| {
+                    Task::perform(
+                        save_index(PathBuf::from(&self.config.save_path), self.index.clone()),
+                        Message::IndexSaved,
+                    )
+                });
+
+                Task::batch(
+                    save_index
+                        .into_iter()
+                        .chain([self.update(Message::BrowsePressed)]),
+                )
+            }
+
+            Message::ArchiveImported(Err(error)) => {
+                if let Some(prompt) = &mut self.archive_import {
+                    prompt.importing = false;
+                    prompt.archive_password.clear();
+                    prompt.error = Some(error.to_string());
+                }
+
+                text_input::focus(archive_prompt_id())
+            }
+
+            Message::ArchiveExportRequested(exporter) => {
+                self.archive_export = Some(ArchiveExportPrompt {
+                    exporter,
+                    password: String::new(),
+                    error: None,
+                    decrypting: false,
+                });
+
+                text_input::focus(archive_export_prompt_id())
+            }
+
+            Message::ArchiveExportPasswordInput(password) => {
+                if let Some(prompt) = &mut self.archive_export {
+                    prompt.password = password;
+                }
+
+                Task::none()
+            }
+
+            Message::ArchiveExportSubmitted => {
+                let Some(prompt) = &mut self.archive_export else {
+                    return Task::none();
+                };
+
+                if prompt.decrypting || prompt.password.is_empty() {
+                    return Task::none();
+                }
+
+                prompt.decrypting = true;
+                prompt.error = None;
+
+                Task::perform(
+                    decrypt_vault(Vault::new(&self.config.save_path), prompt.password.clone()),
+                    Message::VaultDecrypted,
+                )
+            }
+
+            Message::ArchiveExportCancelled => {
+                if self
+                    .archive_export
+                    .as_ref()
+                    .is_some_and(|prompt| !prompt.decrypting)
+                {
+                    self.archive_export = None;
+                }
+
+                Task::none()
+            }
+
+            Message::VaultDecrypted(Ok((documents, failed))) => {
+                let Some(prompt) = self.archive_export.take() else {
+                    return Task::none();
+                };
+
+                Task::perform(export_archive(prompt.exporter, documents), move |result| {
+                    Message::ArchiveExported(result, failed)
+                })
+            }
+
+            Message::VaultDecrypted(Err(error)) => {
+                if let Some(prompt) = &mut self.archive_export {
+                    prompt.decrypting = false;
+                    prompt.password.clear();
+                    prompt.error = Some(match error {
+                        DocumentError::Locked(until) => locked_message(until),
+                        error => error.to_string(),
+                    });
+                }
+
+                text_input::focus(archive_export_prompt_id())
+            }
+
+            Message::ArchiveExported(Ok(path), failed) => {
+                tracing::info!(path = %path.display(), failed, "exported archive");
+
+                self.toasts.push(if failed == 0 {
+                    Toast {
+                        title: tr("toast.success").into(),
+                        body: tr_with("msg.archive_exported", pathbuf_to_string(&path)),
+                        status: Status::Success,
+                    }
+                } else {
+                    Toast {
+                        title: tr("toast.failed").into(),
+                        body: tr_with("msg.archive_export_incomplete", failed),
+                        status: Status::Danger,
+                    }
+                });
+
+                audit::record(AuditEvent::Exported {
+                    document: pathbuf_to_string(&path),
+                })
+            }
+
+            Message::ArchiveExported(Err(error), _) => {
+                self.report_error("error.export_archive", error);
+
+                Task::none()
+            }
+
+            Message::ImportRequested(importer) => {
+                Task::perform(import_file(importer), Message::Imported)
+            }
+
+            Message::Imported(Ok((path, document))) => {
+                if document.encoding != Encoding::Utf8 {
+                    self.toasts.push(Toast {
+                        title: tr("toast.encoding").into(),
+                        body: tr_with("msg.converted_encoding", document.encoding),
+                        status: Status::Secondary,
+                    });
+                }
+
+                if self.doc_name.is_empty() {
+                    self.doc_name = sanitize_document_name(&document_name(&path));
+                }
+
+                self.line_ending = document.line_ending;
+                self.set_text(document.text);
+                self.document_changed();
+
+                self.toasts.push(Toast {
+                    title: tr("toast.success").into(),
+                    body: tr("msg.imported").into(),
+                    status: Status::Success,
+                });
+
+                Task::none()
+            }
+
+            Message::Imported(Err(error)) => {
+                self.report_error("error.import", error);
+
+                Task::none()
+            }
+
+            Message::RequireReauthToggled(require_reauth) => {
+                self.config.require_reauth = require_reauth;
+
+                self.update(Message::SaveConfig)
+            }
+
+            Message::FolderPathFileSaved(Ok(_)) => {
+                self.toasts.push(Toast {
+                    title: tr("toast.success").into(),
+                    body: tr("msg.save_path_saved").into(),
+                    status: Status::Success,
+                });
+
+                Task::none()
+            }
+
+            Message::FolderPathFileSaved(Err(error)) => {
+                self.report_error("error.save_path", error);
+
+                Task::none()
+            }
+            Message::CloseToast(index) => {
+                self.toasts.remove(index);
+
+                Task::none()
+            }
+
+            Message::ErrorDismissed => {
+                self.error = None;
+
+                Task::none()
+            }
+        }
+    }
+
+    fn view(&self) -> Element<Message> {
+        if let (Some(capture), Some(inbox)) = (&self.capture, &self.config.inbox) {
+            return capture.view(&document_name(inbox));
+        }
+
+        let focused =
+            |element, action| toolbar::outline(element, self.toolbar_focus == Some(action));
+
+        let controls = row![
+            focused(
+                action(
+                    home_icon(),
+                    tr("toolbar.home"),
+                    Some(Message::HomePressed),
+                    true
+                ),
+                ToolbarAction::Home
+            ),
+            focused(
+                action(
+                    new_icon(),
+                    tr("toolbar.new"),
+                    Some(Message::NewDocumentPressed),
+                    false
+                ),
+                ToolbarAction::New
+            ),
+            focused(
+                action(
+                    open_icon(),
+                    tr("toolbar.open"),
+                    Some(Message::OpenDocumentPressed),
+                    false
+                ),
+                ToolbarAction::Open
+            ),
+            focused(
+                action(
+                    save_icon(),
+                    tr("toolbar.save"),
+                    self.is_dirty.then_some(Message::SaveDocumentPressed),
+                    false
+                ),
+                ToolbarAction::Save
+            ),
+        ]
+        .push_maybe(vaults::switcher(&self.config))
+        .push(horizontal_space())
+        .push(focused(
+            action(
+                settings_icon(),
+                tr("toolbar.settings"),
+                Some(Message::SettingsPressed),
+                false,
+            ),
+            ToolbarAction::Settings,
+        ))
+        .spacing(10)
+        .align_items(Alignment::Center);
+
+        let content: Element<Message> = match self.current_page {
+            Page::Settings => {
+                let save_title = text(tr("settings.save_dir"));
+
+                let save_button =
+                    button(tr("settings.select_path")).on_press(Message::SelectFolderPressed);
+
+                let current_path = text(format!(
+                    "{} {}",
+                    tr("settings.current_path"),
+                    &self.config.save_path
+                ));
+
+                let storage_backend = (!self.config.save_path.is_empty()).then(|| {
+                    row![
+                        text(tr("settings.storage_backend")),
+                        pick_list(
+                            Backend::ALL,
+                            Some(self.config.storage_backend()),
+                            Message::StorageBackendSelected,
+                        )
+                        .text_size(14)
+                        .padding([5, 10]),
+                    ]
+                    .spacing(10)
+                    .align_items(Alignment::Center)
+                });
+
+                let save_row = column![row![save_button, current_path].spacing(10)]
+                    .push_maybe(storage_backend)
+                    .spacing(10);
+
+                let theme_title = text(tr("settings.theme"));
+
+                let theme_list = pick_list(
+                    highlighter::Theme::ALL,
+                    Some(self.theme),
+                    Message::ThemeSelected,
+                )
+                .text_size(14)
+                .padding([5, 10]);
+
+                let language_title = text(tr("settings.language"));
+
+                let language_list = pick_list(
+                    Language::ALL,
+                    Some(self.config.language),
+                    Message::LanguageSelected,
+                )
+                .text_size(14)
+                .padding([5, 10]);
+
+                let scale_title = text(tr_with(
+                    "settings.ui_scale",
+                    format!("{:.0}%", self.config.ui_scale * 100.0),
+                ));
+
+                let scale_slider = slider(
+                    MIN_UI_SCALE..=MAX_UI_SCALE,
+                    self.config.ui_scale,
+                    Message::UiScaleChanged,
+                )
+                .step(0.05)
+                .on_release(Message::SaveConfig)
+                .width(300);
+
+                let large_text = checkbox(tr("settings.large_text"), self.config.large_text)
+                    .on_toggle(Message::LargeTextToggled);
+
+                let minimize_to_tray = checkbox(
+                    tr("settings.minimize_to_tray"),
+                    self.config.minimize_to_tray,
+                )
+                .on_toggle(Message::MinimizeToTrayToggled);
+
+                let quick_capture = text(match &self.config.inbox {
+                    Some(inbox) => tr_with(
+                        "settings.quick_capture",
+                        format!("{} → {}", capture::SHORTCUT, document_name(inbox)),
+                    ),
+                    None => tr("settings.no_inbox").into(),
+                })
+                .size(14);
+
+                let on_screen_keypad = checkbox(
+                    tr("settings.on_screen_keypad"),
+                    self.config.on_screen_keypad,
+                )
+                .on_toggle(Message::OnScreenKeypadToggled);
+
+                let security_title = text(tr("settings.security"));
+
+                let require_reauth =
+                    checkbox(tr("settings.require_reauth"), self.config.require_reauth)
+                        .on_toggle(Message::RequireReauthToggled);
+
+                let lock_on_session = checkbox(
+                    tr("settings.lock_on_session"),
+                    self.config.lock_on_session_events,
+                )
+                .on_toggle(Message::LockOnSessionToggled);
+
+                let plaintext_retention = row![
+                    text(tr("settings.plaintext_retention")),
+                    pick_list(
+                        Retention::ALL,
+                        Some(self.config.plaintext_retention),
+                        Message::PlaintextRetentionSelected,
+                    )
+                    .text_size(14)
+                    .padding([5, 10]),
+                ]
+                .spacing(10)
+                .align_items(Alignment::Center);
+
+                let self_test = button(if self.is_self_testing {
+                    tr("settings.self_testing")
+                } else {
+                    tr("settings.self_test")
+                })
+                .style(button::secondary)
+                .on_press_maybe((!self.is_self_testing).then_some(Message::SelfTestPressed));
+
+                let check_for_updates = checkbox(
+                    tr("settings.check_for_updates"),
+                    self.config.check_for_updates,
+                )
+                .on_toggle(Message::CheckForUpdatesToggled);
+
+                let release_channel = row![
+                    text(tr("settings.release_channel")),
+                    pick_list(
+                        ReleaseChannel::ALL,
+                        Some(self.config.release_channel),
+                        Message::ReleaseChannelSelected,
+                    )
+                    .text_size(14)
+                    .padding([5, 10]),
+                ]
+                .spacing(10)
+                .align_items(Alignment::Center);
+
+                let vaults_title = text(tr("settings.vaults"));
+
+                let add_vault = row![
+                    text_input(tr("vaults.name_placeholder"), &self.new_vault_name)
+                        .on_input(Message::NewVaultNameInput)
+                        .on_submit(Message::AddVaultPressed)
+                        .padding([5, 10]),
+                    button(tr("vaults.add"))
+                        .style(button::secondary)
+                        .on_press_maybe(
+                            (!self.new_vault_name.trim().is_empty())
+                                .then_some(Message::AddVaultPressed)
+                        ),
+                ]
+                .spacing(10)
+                .align_items(Alignment::Center);
+
+                let vault_password = row![
+                    text_input(
+                        tr(if self.config.vault_password_hash.is_some() {
+                            "vaults.change_password"
+                        } else {
+                            "vaults.new_password"
+                        }),
+                        &self.vault_password
+                    )
+                    .on_input(Message::VaultMasterPasswordInput)
+                    .on_submit(Message::SetVaultPasswordPressed)
+                    .secure(true)
+                    .padding([5, 10]),
+                    button(tr("vaults.set_password"))
+                        .style(button::secondary)
+                        .on_press_maybe(
+                            (!self.vault_password.is_empty()
+                                || self.config.vault_password_hash.is_some())
+                            .then_some(Message::SetVaultPasswordPressed)
+                        ),
+                ]
+                .spacing(10)
+                .align_items(Alignment::Center);
+
+                let vault_password = (!self.config.vaults.is_empty()).then(|| {
+                    column![
+                        text(tr_with("vaults.active", &self.config.vault_name)),
+                        vault_password,
+                        text(tr("vaults.password_hint")).size(14),
+                        button(tr("vaults.remove"))
+                            .style(button::danger)
+                            .on_press(Message::RemoveVaultPressed),
+                    ]
+                    .spacing(10)
+                });
+
+                let profile_title = text(tr("settings.profile"));
+
+                let profile_documents =
+                    checkbox(tr("settings.profile_documents"), self.profile_documents)
+                        .on_toggle(Message::ProfileDocumentsToggled);
+
+                let profile_buttons = row![
+                    button(tr("settings.export_profile"))
+                        .style(button::secondary)
+                        .on_press(Message::ExportProfilePressed),
+                    button(tr("settings.import_profile"))
+                        .style(button::secondary)
+                        .on_press(Message::ImportProfilePressed),
+                ]
+                .spacing(10);
+
+                let audit_title = text(tr("settings.audit"));
+
+                let debug_title = text(tr("settings.debug"));
+
+                let log_btn = button(if self.log.is_some() {
+                    tr("settings.hide_log")
+                } else {
+                    tr("settings.show_log")
+                })
+                .style(button::secondary)
+                .on_press(Message::LogToggled);
+
+                let log_buttons = row![log_btn]
+                    .push_maybe(self.log.as_ref().map(|_| {
+                        button(tr("settings.refresh_log"))
+                            .style(button::secondary)
+                            .on_press(Message::LogRefreshed)
+                    }))
+                    .spacing(10);
+
+                let bench_btn = button(if self.is_benchmarking {
+                    tr("settings.benchmarking")
+                } else {
+                    tr("settings.bench")
+                })
+                .style(button::secondary)
+                .on_press_maybe((!self.is_benchmarking).then_some(Message::BenchPressed));
+
+                let bench_view = self.bench.as_ref().map(|results| {
+                    let mut table = format!(
+                        "{} {:.3} ms\n\n{:<8} {:>14} {:>14}",
+                        tr("settings.bench_key_derivation"),
+                        results.key_derivation,
+                        tr("settings.bench_size"),
+                        tr("settings.bench_encrypt"),
+                        tr("settings.bench_decrypt"),
+                    );
+
+                    for throughput in &results.throughput {
+                        table.push_str(&format!(
+                            "\n{:<8} {:>9.1} MB/s {:>9.1} MB/s",
+                            format_size(throughput.size as u64),
+                            throughput.encrypt,
+                            throughput.decrypt,
+                        ));
+                    }
+
+                    container(text(table).font(Font::MONOSPACE).size(12))
+                        .padding(10)
+                        .width(Length::Fill)
+                        .style(container::rounded_box)
+                });
+
+                let log_view = self.log.as_ref().map(|log| {
+                    container(
+                        scrollable(
+                            text(if log.is_empty() {
+                                tr("settings.log_empty")
+                            } else {
+                                log.as_str()
+                            })
+                            .font(Font::MONOSPACE)
+                            .size(12),
+                        )
+                        .height(300),
+                    )
+                    .padding(10)
+                    .width(Length::Fill)
+                    .style(container::rounded_box)
+                });
+
+                let editor_title = text(tr("settings.editor"));
+
+                let tab_width = row![
+                    text(tr("settings.tab_width")),
+                    pick_list(
+                        TAB_WIDTHS,
+                        Some(self.config.tab_width),
+                        Message::TabWidthSelected,
+                    )
+                    .text_size(14)
+                    .padding([5, 10]),
+                ]
+                .spacing(10)
+                .align_items(Alignment::Center);
+
+                let indent_with_spaces = checkbox(
+                    tr("settings.indent_with_spaces"),
+                    self.config.indent_with_spaces,
+                )
+                .on_toggle(Message::IndentWithSpacesToggled);
+
+                let auto_indent = checkbox(tr("settings.auto_indent"), self.config.auto_indent)
+                    .on_toggle(Message::AutoIndentToggled);
+
+                let date_preview = format_now(&self.config.date_format)
+                    .unwrap_or_else(|| tr("settings.invalid_date_format").to_string());
+
+                let date_format = row![
+                    text(tr("settings.date_format")),
+                    text_input("%Y-%m-%d %H:%M", &self.config.date_format)
+                        .on_input(Message::DateFormatInput)
+                        .padding([5, 10])
+                        .width(200),
+                    text(date_preview).size(14),
+                ]
+                .spacing(10)
+                .align_items(Alignment::Center);
+
+                let post_save_hook = checkbox(
+                    tr("settings.post_save_hook"),
+                    self.config.post_save_hook_enabled,
+                )
+                .on_toggle(Message::PostSaveHookToggled);
+
+                let post_save_command = text_input(
+                    tr("settings.post_save_command"),
+                    &self.config.post_save_hook,
+                )
+                .on_input(Message::PostSaveHookInput)
+                .padding([5, 10]);
+
+                let content = container(scrollable(
+                    column![
+                        controls,
+                        vaults_title,
+                        add_vault,
+                        column![].push_maybe(vault_password),
+                        save_title,
+                        save_row,
+                        post_save_hook,
+                        post_save_command,
+                        theme_title,
+                        theme_list,
+                        language_title,
+                        language_list,
+                        scale_title,
+                        scale_slider,
+                        large_text,
+                        minimize_to_tray,
+                        quick_capture,
+                        check_for_updates,
+                        release_channel,
+                        editor_title,
+                        tab_width,
+                        indent_with_spaces,
+                        auto_indent,
+                        date_format,
+                        security_title,
+                        on_screen_keypad,
+                        require_reauth,
+                        lock_on_session,
+                        plaintext_retention,
+                        self_test,
+                        profile_title,
+                        profile_documents,
+                        profile_buttons,
+                        audit_title,
+                        self.audit.view(),
+                        debug_title,
+                        bench_btn,
+                    ]
+                    .push_maybe(bench_view)
+                    .push(log_buttons)
+                    .push_maybe(log_view)
+                    .spacing(10),
+                ))
+                .padding(10);
+
+                content.into()
+            }
+
+            Page::StartPage => {
+                let placeholder_text = text(tr("start.placeholder"));
+
+                let search_btn = button(tr("start.search")).on_press(Message::SearchPressed);
+
+                let journal_btn = button(tr("start.journal")).on_press(Message::JournalPressed);
+
+                let browse_btn = button(tr("start.browse")).on_press(Message::BrowsePressed);
+
+                let paste_btn = button(tr("start.paste_armored"))
+                    .style(button::secondary)
+                    .on_press_maybe(
+                        (!self.config.save_path.is_empty()).then_some(Message::PasteArmoredPressed),
+                    );
+
+                let content = container(
+                    column![
+                        controls,
+                        placeholder_text,
+                        row![search_btn, browse_btn, journal_btn, paste_btn].spacing(10)
+                    ]
+                    .push_maybe(self.document_section("start.pinned", &self.config.pinned))
+                    .push_maybe(self.document_section("start.recent", &self.config.recent))
+                    .spacing(10),
+                )
+                .padding(10)
+                .center_x(Length::Fill)
+                .center_y(Length::Fill);
+
+                content.into()
+            }
+
+            Page::NewDocumentPage => {
+                let name_title = text(tr("new.name_title"));
+
+                let name_input = text_input(tr("new.name_placeholder"), &self.doc_name)
+                    .id(new_document_name_id())
+                    .padding(10)
+                    .on_input(Message::DocumentInput)
+                    .on_submit(Message::FocusNext);
+
+                let invalid_name = self.name_error.as_ref().map(|error| {
+                    text(name_error(error))
+                        .size(14)
+                        .color(Color::from_rgb(0.9, 0.3, 0.3))
+                });
+
+                let pass_title = text(tr("new.password_title"));
+
+                let pass_input = text_input(tr("common.password"), &self.password)
+                    .padding(10)
+                    .on_input(Message::PasswordInput)
+                    .on_submit(Message::NewDocumentSubmitted)
+                    .secure(true);
+
+                let submit_btn = button(tr("new.create")).on_press(Message::NewDocumentSubmitted);
+
+                let table_btn = button(tr("new.create_table"))
+                    .style(button::secondary)
+                    .on_press(Message::NewTableSubmitted);
+
+                let import_list = pick_list(IMPORTERS, None, Message::ImportRequested)
+                    .placeholder(tr("new.import"));
+
+                let content = container(
+                    column![controls, name_title, name_input]
+                        .push_maybe(invalid_name)
+                        .push(pass_title)
+                        .push(pass_input)
+                        .push_maybe(caps_lock_warning(self.caps_lock))
+                        .push(row![submit_btn, table_btn, import_list].spacing(10))
+                        .spacing(10),
+                )
+                .padding(10)
+                .center_x(Length::Fill)
+                .center_y(Length::Fill);
+
+                content.into()
+            }
+            Page::Properties => {
+                let title = text(tr("properties.title"));
+
+                let properties = match &self.properties {
+                    Some(properties) => {
+                        let mut rows = vec![
+                            (tr("properties.path"), pathbuf_to_string(&properties.path)),
+                            (tr("properties.size"), format_size(properties.size)),
+                            (
+                                tr("properties.created"),
+                                format_timestamp(properties.created),
+                            ),
+                            (
+                                tr("properties.modified"),
+                                format_timestamp(properties.modified),
+                            ),
+                        ];
+
+                        match &properties.format {
+                            Some(format) => rows.extend([
+                                (tr("properties.version"), format.version.to_string()),
+                                (tr("properties.cipher"), format.cipher.to_string()),
+                                (tr("properties.kdf"), format.kdf.to_string()),
+                                (
+                                    tr("properties.iv_size"),
+                                    tr_with("properties.bytes", format.iv_size),
+                                ),
+                                (
+                                    tr("properties.mac_size"),
+                                    tr_with("properties.bytes", format.mac_size),
+                                ),
+                                (
+                                    tr("properties.ciphertext_size"),
+                                    format_size(format.ciphertext_size as u64),
+                                ),
+                            ]),
+                            None => rows.push((
+                                tr("properties.format"),
+                                tr("properties.unrecognized").to_string(),
+                            )),
+                        }
+
+                        rows.push((
+                            tr("properties.signature"),
+                            tr("properties.not_signed").to_string(),
+                        ));
+
+                        rows.into_iter()
+                            .fold(column![].spacing(5), |column, (label, value)| {
+                                column.push(row![text(label).width(150), text(value)].spacing(10))
+                            })
+                    }
+                    None => column![],
+                };
+
+                let label = self.properties.as_ref().map(|properties| {
+                    let meta = self
+                        .index
+                        .meta(&properties.path)
+                        .cloned()
+                        .unwrap_or_default();
+
+                    row![
+                        text(tr("properties.label")).width(150),
+                        pick_list(Label::ALL, meta.color.map(Label), |label| {
+                            Message::LabelColorSelected(label.0)
+                        })
+                        .placeholder(tr("label.none"))
+                        .text_size(14)
+                        .padding([5, 10]),
+                        text_input(tr("label.emoji"), meta.emoji.as_deref().unwrap_or_default())
+                            .on_input(Message::LabelEmojiInput)
+                            .padding([5, 10])
+                            .width(80),
+                        button(tr("label.clear"))
+                            .style(button::secondary)
+                            .on_press(Message::LabelCleared),
+                    ]
+                    .spacing(10)
+                    .align_items(Alignment::Center)
+                });
+
+                let tags = self.properties.as_ref().map(|properties| {
+                    let meta = self
+                        .index
+                        .meta(&properties.path)
+                        .cloned()
+                        .unwrap_or_default();
+
+                    meta.tags
+                        .into_iter()
+                        .fold(row![text(tr("properties.tags")).width(150)], |row, tag| {
+                            row.push(
+                                button(text(format!("{} ×", tag)).size(14))
+                                    .style(button::secondary)
+                                    .padding([2, 8])
+                                    .on_press(Message::TagRemoved(tag)),
+                            )
+                        })
+                        .push(
+                            text_input(tr("tags.add"), &self.tag_input)
+                                .on_input(Message::TagInput)
+                                .on_submit(Message::TagSubmitted)
+                                .padding([5, 10])
+                                .width(150),
+                        )
+                        .spacing(10)
+                        .align_items(Alignment::Center)
+                });
+
+                let inbox = self.properties.as_ref().map(|properties| {
+                    checkbox(
+                        tr("properties.inbox"),
+                        self.config.inbox.as_ref() == Some(&properties.path),
+                    )
+                    .on_toggle(Message::InboxToggled)
+                });
+
+                let back_btn = button(tr("properties.back")).on_press(Message::PropertiesClosed);
+
+                let content = container(
+                    column![controls, title, properties]
+                        .push_maybe(label)
+                        .push_maybe(tags)
+                        .push_maybe(inbox)
+                        .push(back_btn)
+                        .spacing(10),
+                )
+                .padding(10);
+
+                content.into()
+            }
+            Page::DocumentViewer => {
+                let title = row![text(tr("viewer.current"))]
+                    .push_maybe(self.path.as_deref().map(|path| self.document_badge(path)))
+                    .push(text(self.doc_name.as_str()))
+                    .spacing(5)
+                    .align_items(Alignment::Center);
+                let properties_btn =
+                    button(tr("viewer.properties")).on_press(Message::PropertiesPressed);
+                let export_list = pick_list(EXPORTERS, None, |exporter| {
+                    Message::PlaintextRequested(PlaintextAction::Export(exporter))
+                })
+                .placeholder(tr("viewer.export"))
+                .text_size(14)
+                .padding([5, 10]);
+                let armor_list = pick_list(ArmorAction::ALL, None, Message::ArmorRequested)
+                    .placeholder(tr("viewer.armored"))
+                    .text_size(14)
+                    .padding([5, 10]);
+                let copy_btn = button(tr("viewer.copy_all"))
+                    .style(button::secondary)
+                    .on_press(Message::PlaintextRequested(PlaintextAction::CopyAll));
+                let share_btn = button(tr("viewer.share"))
+                    .style(button::secondary)
+                    .on_press(Message::PlaintextRequested(PlaintextAction::Share));
+                let image_btn = button(tr("viewer.insert_image"))
+                    .style(button::secondary)
+                    .on_press_maybe(
+                        (self.table.is_none() && self.pager.is_none())
+                            .then_some(Message::InsertImagePressed),
+                    );
+                let date_btn = button(tr("viewer.insert_date"))
+                    .style(button::secondary)
+                    .on_press(Message::InsertDatePressed);
+                let tasks_left = (!self.checklist.is_empty()).then(|| {
+                    text(tr_with(
+                        "checklist.remaining",
+                        format!("{}/{}", remaining(&self.checklist), self.checklist.len()),
+                    ))
+                });
+                let line_ending_list = pick_list(
+                    LineEnding::ALL,
+                    Some(self.line_ending),
+                    Message::LineEndingSelected,
+                )
+                .text_size(14)
+                .padding([5, 10]);
+                let container_list = (Container::ALL.len() > 1).then(|| {
+                    pick_list(
+                        Container::ALL,
+                        Some(self.container),
+                        Message::ContainerSelected,
+                    )
+                    .text_size(14)
+                    .padding([5, 10])
+                });
+                let stats_btn = button(tr("viewer.stats"))
+                    .style(if self.stats.is_some() {
+                        button::primary
+                    } else {
+                        button::secondary
+                    })
+                    .on_press(Message::StatsToggled);
+                let outline_btn = (!self.outline.is_empty()).then(|| {
+                    button(tr("viewer.outline"))
+                        .style(if self.show_outline {
+                            button::primary
+                        } else {
+                            button::secondary
+                        })
+                        .on_press(Message::OutlineToggled)
+                });
+                let redact_btn = button(tr("viewer.redact"))
+                    .style(button::secondary)
+                    .on_press_maybe(
+                        (!self.show_redacted && self.content.selection().is_some())
+                            .then_some(Message::RedactPressed),
+                    );
+                let compare_btn = button(tr("viewer.compare"))
+                    .style(if self.compare.is_some() {
+                        button::primary
+                    } else {
+                        button::secondary
+                    })
+                    .on_press_maybe(
+                        (self.path.is_some() && self.pager.is_none() && self.table.is_none())
+                            .then_some(if self.compare.is_some() {
+                                Message::CompareClosed
+                            } else {
+                                Message::ComparePressed
+                            }),
+                    );
+                let redacted_btn = button(tr("viewer.show_redacted"))
+                    .style(if self.show_redacted {
+                        button::primary
+                    } else {
+                        button::secondary
+                    })
+                    .on_press(Message::RedactedViewToggled);
+                let editor = text_editor(&self.content)
+                    .on_action(Message::Edit)
+                    .size(self.config.editor_text_size())
+                    .height(Length::Fill);
+
+                let journal_nav = self.journal_date().map(|date| {
+                    row![
+                        button(text("‹"))
+                            .style(button::secondary)
+                            .on_press_maybe(date.pred_opt().map(Message::JournalDaySelected)),
+                        button(text(date.format("%a %Y-%m-%d").to_string()).size(14))
+                            .style(button::secondary)
+                            .on_press(Message::CalendarPressed),
+                        button(text("›"))
+                            .style(button::secondary)
+                            .on_press_maybe(date.succ_opt().map(Message::JournalDaySelected)),
+                    ]
+                    .spacing(5)
+                    .align_items(Alignment::Center)
+                });
+
+                let header = row![title, horizontal_space()]
+                    .push_maybe(journal_nav)
+                    .push_maybe(tasks_left)
+                    .push(line_ending_list)
+                    .push_maybe(container_list)
+                    .push(stats_btn)
+                    .push_maybe(outline_btn)
+                    .push(redact_btn)
+                    .push(redacted_btn)
+                    .push(compare_btn)
+                    .push(date_btn)
+                    .push(image_btn)
+                    .push(copy_btn)
+                    .push(share_btn)
+                    .push(export_list)
+                    .push(armor_list)
+                    .push(properties_btn)
+                    .spacing(10)
+                    .align_items(Alignment::Center);
+
+                let checklist = (!self.checklist.is_empty()).then(|| {
+                    let items = self
+                        .checklist
+                        .iter()
+                        .fold(column![].spacing(5), |items, item| {
+                            let line = item.line;
+
+                            items.push(
+                                checkbox(item.text.as_str(), item.done)
+                                    .on_toggle(move |_| Message::ChecklistItemToggled(line)),
+                            )
+                        });
+
+                    container(scrollable(items)).width(250).padding(5)
+                });
+
+                let outline = (self.show_outline && !self.outline.is_empty()).then(|| {
+                    let headings =
+                        self.outline
+                            .iter()
+                            .fold(column![].spacing(2), |headings, heading| {
+                                headings.push(
+                                    // Indented by level, like a table of contents.
+                                    button(
+                                        text(format!(
+                                            "{}{}",
+                                            "    ".repeat(heading.level - 1),
+                                            heading.text
+                                        ))
+                                        .size(14),
+                                    )
+                                    .style(button::text)
+                                    .padding([2, 5])
+                                    .on_press(Message::HeadingSelected(heading.line)),
+                                )
+                            });
+
+                    container(scrollable(headings)).width(220).padding(5)
+                });
+
+                let codes = (!self.totp_seeds.is_empty()).then(|| {
+                    let codes = self.totp_seeds.iter().fold(
+                        column![text(tr("totp.title"))].spacing(5),
+                        |codes, (label, totp)| {
+                            codes
+                                .push_maybe(
+                                    (!label.is_empty()).then(|| text(label.as_str()).size(14)),
+                                )
+                                .push(secrets::code(totp))
+                        },
+                    );
+
+                    container(scrollable(codes)).width(250).padding(5)
+                });
+
+                let editor: Element<Message> = if let Some(merge) = &self.merge {
+                    merge.view(self.config.editor_text_size())
+                } else {
+                    match (&self.table, &self.pager, &self.compare) {
+                        (Some(table), _, _) => secrets::view(table, &self.revealed),
+                        (None, Some(pager), _) => pager.view(self.config.editor_text_size()),
+                        (None, None, Some(compare)) => {
+                            compare.view(&self.content.text(), self.config.editor_text_size())
+                        }
+                        (None, None, None) if self.show_redacted => scrollable(
+                            text(redact::black_out(&self.content.text()).into_owned())
+                                .size(self.config.editor_text_size())
+                                .width(Length::Fill),
+                        )
+                        .height(Length::Fill)
+                        .into(),
+                        (None, None, None) => row![]
+                            .push_maybe(outline)
+                            .push(editor)
+                            .push_maybe(checklist)
+                            .push_maybe(codes)
+                            .push_maybe(
+                                (!self.images.is_empty()).then(|| images::view(&self.images)),
+                            )
+                            .push_maybe(
+                                (!self.links.is_empty() || !self.backlinks.is_empty())
+                                    .then(|| links::view(&self.links, &self.backlinks)),
+                            )
+                            .spacing(10)
+                            .into(),
+                    }
+                };
+
+                let stats = self.stats.map(|stats| {
+                    row![
+                        text(tr_with("stats.reading_time", stats.reading_minutes)),
+                        text(tr_with("stats.words", stats.words)),
+                        text(tr_with("stats.sentences", stats.sentences)),
+                        text(tr_with("stats.paragraphs", stats.paragraphs)),
+                        text(tr_with(
+                            "stats.average_word_length",
+                            format!("{:.1}", stats.average_word_length)
+                        )),
+                    ]
+                    .spacing(20)
+                });
+
+                let content = container(
+                    column![controls, header, editor]
+                        .push_maybe(stats)
+                        .spacing(10),
+                )
+                .padding(10)
+                .center_x(Length::Fill)
+                .center_y(Length::Fill);
+
+                content.into()
+            }
+            Page::Browser => {
+                let title = text(format!(
+                    "{} {}",
+                    tr("browser.title"),
+                    &self.config.save_path
+                ));
+
+                let filter_input = text_input(tr("browser.filter"), &self.browser_filter)
+                    .id(browser_filter_id())
+                    .padding(10)
+                    .on_input(Message::BrowserFilterInput);
+
+                let sort_list = pick_list(
+                    SortOrder::ALL,
+                    Some(self.browser_sort),
+                    Message::BrowserSortSelected,
+                )
+                .padding(10);
+
+                let report_list = pick_list(
+                    ReportFormat::ALL,
+                    None::<ReportFormat>,
+                    Message::ReportRequested,
+                )
+                .placeholder(tr("browser.export_report"))
+                .padding(10);
+
+                let import_list = (!self.config.save_path.is_empty()
+                    && !ARCHIVE_IMPORTERS.is_empty())
+                .then(|| {
+                    pick_list(ARCHIVE_IMPORTERS, None, Message::ArchiveImportRequested)
+                        .placeholder(tr("browser.import_archive"))
+                        .padding(10)
+                });
+
+                let export_list = (!self.config.save_path.is_empty()
+                    && !ARCHIVE_EXPORTERS.is_empty())
+                .then(|| {
+                    pick_list(ARCHIVE_EXPORTERS, None, Message::ArchiveExportRequested)
+                        .placeholder(tr("browser.export_archive"))
+                        .padding(10)
+                });
+
+                let documents = sorted_documents(
+                    &self.documents,
+                    &self.config,
+                    self.browser_sort,
+                    &self.browser_filter,
+                )
+                .into_iter()
+                .fold(column![].spacing(5), |documents, document| {
+                    let details = format!(
+                        "{}, {}",
+                        format_size(document.size),
+                        format_age(document.modified)
+                    );
+
+                    documents.push(self.document_row(&document.path, Some(details)))
+                });
+
+                let content = container(
+                    column![
+                        controls,
+                        title,
+                        row![filter_input, sort_list, report_list]
+                            .push_maybe(import_list)
+                            .push_maybe(export_list)
+                            .spacing(10),
+                        scrollable(documents).height(Length::Fill)
+                    ]
+                    .spacing(10),
+                )
+                .padding(10);
+
+                content.into()
+            }
+            Page::Search => {
+                let title = text(tr("search.title"));
+
+                let query_input = text_input(tr("search.placeholder"), &self.search_query)
+                    .id(search_query_id())
+                    .padding(10)
+                    .on_input(Message::SearchQueryInput)
+                    .on_submit(Message::SearchSubmitted);
+
+                let pass_input = text_input(tr("common.password"), &self.search_password)
+                    .padding(10)
+                    .on_input(Message::SearchPasswordInput)
+                    .on_submit(Message::SearchSubmitted)
+                    .secure(true);
+
+                let search_btn = button(if self.is_searching {
+                    tr("search.searching")
+                } else {
+                    tr("search.search")
+                })
+                .on_press_maybe((!self.is_searching).then_some(Message::SearchSubmitted));
+
+                let results = self.search_results.iter().enumerate().fold(
+                    column![].spacing(5),
+                    |results, (index, hit)| {
+                        let location = text(format!(
+                            "{} {}",
+                            pathbuf_to_string(&hit.path),
+                            tr_with("search.line", hit.line)
+                        ))
+                        .size(14);
+
+                        results.push(
+                            button(column![location, text(hit.snippet.as_str())].spacing(2))
+                                .width(Length::Fill)
+                                .style(button::secondary)
+                                .on_press(Message::SearchHitSelected(index)),
+                        )
+                    },
+                );
+
+                let content = container(
+                    column![controls, title, query_input, pass_input]
+                        .push_maybe(caps_lock_warning(self.caps_lock))
+                        .push(search_btn)
+                        .push(scrollable(results).height(Length::Fill))
+                        .spacing(10),
+                )
+                .padding(10);
+
+                content.into()
+            }
+        };
+
+        let content = match &self.error {
+            Some(report) => column![error::banner(report, Message::ErrorDismissed), content]
+                .spacing(10)
+                .into(),
+            None => content,
+        };
+
+        let content = match &self.prompt {
+            Some(prompt) => modal(
+                content,
+                password_prompt(prompt, self.caps_lock),
+                Message::PromptCancelled,
+            ),
+            None => content,
+        };
+
+        let content = match &self.reauth {
+            Some(reauth) => modal(content, reauth_prompt(reauth), Message::ReauthCancelled),
+            None => content,
+        };
+
+        let content = if self.vault_removal {
+            modal(
+                content,
+                vaults::remove_view(&self.config.vault_name),
+                Message::RemoveVaultCancelled,
+            )
+        } else {
+            content
+        };
+
+        let content = match &self.vault_prompt {
+            Some(prompt) => modal(
+                content,
+                vaults::prompt_view(prompt),
+                Message::VaultPromptCancelled,
+            ),
+            None => content,
+        };
+
+        let content = match &self.external_edit {
+            Some(prompt) => modal(
+                content,
+                external_edit_prompt(prompt),
+                Message::ExternalEditDenied,
+            ),
+            None => content,
+        };
+
+        let content = match &self.archive_import {
+            Some(prompt) => modal(
+                content,
+                archive_prompt(prompt),
+                Message::ArchiveImportCancelled,
+            ),
+            None => content,
+        };
+
+        let content = match &self.share {
+            Some(share) => modal(content, share_prompt(share), Message::ShareClosed),
+            None => content,
+        };
+
+        let content = match &self.clipboard_prompt {
+            Some(prompt) => modal(
+                content,
+                clipboard_prompt(prompt),
+                Message::ClipboardCancelled,
+            ),
+            None => content,
+        };
+
+        let content = match &self.journal_prompt {
+            Some(prompt) => modal(content, journal_prompt(prompt), Message::JournalCancelled),
+            None => content,
+        };
+
+        let content = match &self.calendar {
+            Some(calendar) => modal(content, calendar.view(), Message::CalendarClosed),
+            None => content,
+        };
+
+        let content = match &self.archive_export {
+            Some(prompt) => modal(
+                content,
+                archive_export_prompt(prompt),
+                Message::ArchiveExportCancelled,
+            ),
+            None => content,
+        };
+
+        let content = match &self.overwrite {
+            Some(overwrite) => modal(
+                content,
+                overwrite_prompt(overwrite),
+                Message::OverwriteCancelled,
+            ),
+            None => content,
+        };
+
+        let content = match &self.recovery {
+            Some(recovery) => modal(
+                content,
+                recovery_prompt(recovery),
+                Message::RecoveryPostponed,
+            ),
+            None => content,
+        };
+
+        let content = match &self.operation {
+            Some(operation) => modal(
+                content,
+                progress_overlay(operation),
+                Message::OperationCancelled,
+            ),
+            None => content,
+        };
+
+        toast::Manager::new(content, &self.toasts, Message::CloseToast).into()
+    }
+
+    /// The day of the journal entry being viewed, if one is.
+    fn journal_date(&self) -> Option<NaiveDate> {
+        if !matches!(self.current_page, Page::DocumentViewer) {
+            return None;
+        }
+
+        journal::entry_date(&self.config.save_path, &self.document_path())
+    }
+
+    fn document_path(&self) -> PathBuf {
+        Vault::new(&self.config.save_path)
+            .path_for(&self.doc_name)
+            .with_extension(self.container.extension())
+    }
+
+    /// Returns the save path if a file already exists there that isn't the
+    /// document currently open, i.e. saving would clobber someone else's file.
+    fn conflicting_path(&self) -> Option<PathBuf> {
+        let path = self.document_path();
+
+        (self.path.as_ref() != Some(&path) && path.exists()).then_some(path)
+    }
+
+    /// Whether the open document's file has been written by something else
+    /// since the app last read or wrote it, like a sync client bringing in
+    /// edits from another machine.
+    fn changed_on_disk(&self) -> bool {
+        let Some((path, modified)) = &self.disk_modified else {
+            return false;
+        };
+
+        self.path.as_ref() == Some(path)
+            && *path == self.document_path()
+            && modified_time(path).is_some_and(|current| current != *modified)
+    }
+
+    /// Puts the merged text in the editor and saves it over the other
+    /// version.
+    fn apply_merge(&mut self, merge: Merge) -> Task<Message> {
+        let Some(text) = merge.text() else {
+            return Task::none();
+        };
+
+        // The other version is part of the merge now, so saving over it
+        // shouldn't count as a conflict again.
+        if let (Some(path), Some(modified)) = (self.path.clone(), merge.modified) {
+            self.disk_modified = Some((path, modified));
+        }
+        self.base = merge.theirs;
+
+        self.set_text(text);
+        self.is_dirty = true;
+        self.document_changed();
+
+        self.update(Message::SaveDocumentPressed)
+    }
+
+    /// Drops the decrypted document from memory and asks for the password
+    /// again. Unsaved changes are encrypted and written out first.
+    fn lock(&mut self) -> Task<Message> {
+        if let Some(prompt) = self.external_edit.take() {
+            instance::deny_edit(prompt.id);
+        }
+
+        self.inbox_password.clear();
+        self.audit.password.clear();
+        self.audit.entries = None;
+        self.merge = None;
+
+        if !self.document_loaded() {
+            self.base.zeroize();
+            return Task::none();
+        }
+
+        let retain = self.retain_document();
+
+        let path = self.document_path();
+        let unsaved = self
+            .is_dirty
+            .then(|| (self.document(), self.password.clone(), self.keys.clone()));
+        let reopen = self.path.is_some() || unsaved.is_some();
+
+        self.set_text(String::new());
+        self.password.zeroize();
+        // Replaced rather than cleared, as saving the unsaved changes below
+        // still uses it. The key is wiped once that's done.
+        self.keys = KeyCache::default();
+        self.reauth = None;
+        self.backlinks.clear();
+        self.document_changed();
+        self.doc_name.clear();
+        self.path = None;
+        self.is_dirty = false;
+        self.current_page = Page::StartPage;
+
+        self.toasts.push(Toast {
+            title: tr("tray.lock").into(),
+            body: tr("msg.locked").into(),
+            status: Status::Secondary,
+        });
+
+        if !reopen {
+            return retain;
+        }
+
+        let reopen = Task::perform(
+            async move {
+                if let Some((document, password, keys)) = unsaved {
+                    let encrypted = document
+                        .encrypt_in_background(password, keys)
+                        .await
+                        .map_err(|_| FileError::IOFailed(io::ErrorKind::Other))?;
+
+                    backup_in_background(path.clone()).await?;
+                    save_file(Some(path.clone()), encrypted).await?;
+                    recovery::discard().await?;
+                }
+
+                load_file(path).await
+            },
+            Message::FileOpened,
+        );
+
+        Task::batch([retain, reopen])
+    }
+
+    /// Closes the open document and makes the vault profile called `name`
+    /// the active one. Its password, if it has one, is filled in for
+    /// searching, as it's usually the one its documents share.
+    fn switch_vault(&mut self, name: &str, password: String) -> Task<Message> {
+        if self.is_dirty {
+            self.toasts.push(Toast {
+                title: tr("toast.failed").into(),
+                body: tr("msg.save_before_switching").into(),
+                status: Status::Danger,
+            });
+
+            return Task::none();
+        }
+
+        let close = self.close_for_vault();
+
+        if !self.config.switch_vault(name) {
+            return close;
+        }
+
+        tracing::info!("switched vault profile");
+
+        self.toasts.push(Toast {
+            title: tr("toast.success").into(),
+            body: tr_with("msg.vault_switched", name),
+            status: Status::Success,
+        });
+
+        Task::batch([close, self.vault_changed(password)])
+    }
+
+    /// Closes the open document, which belongs to the vault profile being
+    /// left.
+    fn close_for_vault(&mut self) -> Task<Message> {
+        if !self.document_loaded() {
+            return Task::none();
+        }
+
+        let in_settings = matches!(self.current_page, Page::Settings);
+        let close = self.update(Message::HomePressed);
+
+        if in_settings {
+            self.current_page = Page::Settings;
+        }
+
+        close
+    }
+
+    /// Removes the active vault profile and switches to the next one, whose
+    /// password is `password`.
+    fn remove_vault(&mut self, password: String) -> Task<Message> {
+        if self.is_dirty {
+            self.toasts.push(Toast {
+                title: tr("toast.failed").into(),
+                body: tr("msg.save_before_switching").into(),
+                status: Status::Danger,
+            });
+
+            return Task::none();
+        }
+
+        let removed = self.config.vault_name.clone();
+        let close = self.close_for_vault();
+
+        if !self.config.remove_vault() {
+            return close;
+        }
+
+        self.toasts.push(Toast {
+            title: tr("toast.success").into(),
+            body: tr_with("msg.vault_removed", removed),
+            status: Status::Success,
+        });
+
+        Task::batch([close, self.vault_changed(password)])
+    }
+
+    /// Forgets what belonged to the previous vault profile.
+    fn vault_changed(&mut self, password: String) -> Task<Message> {
+        self.index = VaultIndex::load(Path::new(&self.config.save_path));
+        self.search_password = password;
+        self.search_results.clear();
+        self.documents.clear();
+        self.vault_password.zeroize();
+
+        self.update(Message::SaveConfig)
+    }
+
+    /// Keeps the open document for a quick re-open if
+    /// [`Config::plaintext_retention`] allows it, and wipes the app's own
+    /// copy of its text. The editor's buffers are freed when it's emptied
+    /// but can't be overwritten first.
+    fn retain_document(&mut self) -> Task<Message> {
+        let mut text = std::mem::take(&mut self.base);
+
+        let modified = match (&self.path, &self.disk_modified) {
+            (Some(path), Some((modified_path, modified))) if path == modified_path => {
+                Some((path.clone(), *modified))
+            }
+            _ => None,
+        };
+
+        let (Some(duration), Some((path, modified)), false, Page::DocumentViewer) = (
+            self.config.plaintext_retention.duration(),
+            modified,
+            self.is_dirty,
+            &self.current_page,
+        ) else {
+            text.zeroize();
+            return Task::none();
+        };
+
+        self.retention_generation += 1;
+        let generation = self.retention_generation;
+
+        // The saved text, not the editor's, as a document with unsaved
+        // changes isn't kept anyway.
+        self.retained = Some(Retained::new(
+            path,
+            modified,
+            self.password.clone(),
+            Document {
+                text,
+                encoding: Encoding::Utf8,
+                line_ending: self.line_ending,
+                container: self.container,
+                history: self.history.clone(),
+            },
+            generation,
+        ));
+
+        Task::perform(tokio::time::sleep(duration), move |_| {
+            Message::RetentionExpired(generation)
+        })
+    }
+
+    fn run_plaintext_action(&mut self, action: PlaintextAction) -> Task<Message> {
+        match action {
+            PlaintextAction::Export(exporter) => Task::perform(
+                export_file(self.doc_name.clone(), exporter, self.document()),
+                Message::Exported,
+            ),
+            PlaintextAction::CopyAll => {
+                self.toasts.push(Toast {
+                    title: tr("toast.success").into(),
+                    body: tr("msg.copied").into(),
+                    status: Status::Success,
+                });
+
+                clipboard::write(self.document_text())
+            }
+            PlaintextAction::Share => {
+                let document = self.document();
+
+                Task::perform(
+                    async move {
+                        tokio::task::spawn_blocking(move || share::seal(&document))
+                            .await
+                            .map_err(|_| DocumentError::Io(io::ErrorKind::Other))
+                    },
+                    Message::ShareSealed,
+                )
+            }
+        }
+    }
+
+    fn report_error(&mut self, context: &'static str, error: FileError) {
+        // Closing a dialog is the user's choice, not something to report.
+        if let FileError::DialogClosed | FileError::Cancelled = error {
+            return;
+        }
+
+        tracing::warn!(%error, "{}", context);
+
+        self.error = Some(ErrorReport::new(tr(context), i18n::file_error(&error)));
+    }
+
+    fn document_section<'a>(
+        &'a self,
+        title: &'static str,
+        paths: &'a [PathBuf],
+    ) -> Option<Element<'a, Message>> {
+        if paths.is_empty() {
+            return None;
+        }
+
+        let section = paths
+            .iter()
+            .fold(column![text(tr(title))].spacing(5), |section, path| {
+                section.push(self.document_row(path, None))
+            });
+
+        Some(section.into())
+    }
+
+    fn document_row<'a>(&'a self, path: &PathBuf, details: Option<String>) -> Element<'a, Message> {
+        let pin_label = if self.config.is_pinned(path) {
+            tr("browser.unpin")
+        } else {
+            tr("browser.pin")
+        };
+
+        let name = row![
+            self.document_badge(path),
+            text(document_name(path)),
+            self.document_tags(path)
+        ]
+        .spacing(5)
+        .align_items(Alignment::Center);
+
+        let label = match details {
+            Some(details) => row![name, horizontal_space(), text(details).size(14)],
+            None => row![name],
+        };
+
+        row![
+            button(label)
+                .width(Length::Fill)
+                .style(button::secondary)
+                .on_press(Message::DocumentSelected(path.clone())),
+            button(pin_label).on_press(Message::PinToggled(path.clone())),
+        ]
+        .spacing(5)
+        .into()
+    }
+
+    /// The color dot and emoji assigned to a document, if any.
+    fn document_badge<'a>(&self, path: &Path) -> Element<'a, Message> {
+        let Some(meta) = self.index.meta(path) else {
+            return row![].into();
+        };
+
+        row![]
+            .push_maybe(
+                meta.color
+                    .map(|color| text("●").color(Label(color).color())),
+            )
+            .push_maybe(meta.emoji.clone().map(text))
+            .spacing(5)
+            .into()
+    }
+
+    /// The tags of a document, for after its name in a list.
+    fn document_tags<'a>(&self, path: &Path) -> Element<'a, Message> {
+        let tags = self
+            .index
+            .meta(path)
+            .map(|meta| meta.tags.as_slice())
+            .unwrap_or_default();
+
+        tags.iter()
+            .fold(row![].spacing(5), |row, tag| {
+                row.push(text(format!("#{}", tag)).size(12))
+            })
+            .into()
+    }
+
+    fn update_meta(&mut self, f: impl FnOnce(&mut DocumentMeta)) -> Task<Message> {
+        let Some(properties) = &self.properties else {
+            return Task::none();
+        };
+
+        if self.config.save_path.is_empty() {
+            self.toasts.push(Toast {
+                title: tr("toast.failed").into(),
+                body: tr("msg.select_folder_first").into(),
+                status: Status::Danger,
+            });
+
+            return Task::none();
+        }
+
+        self.index.update(&properties.path, f);
+
+        Task::perform(
+            save_index(PathBuf::from(&self.config.save_path), self.index.clone()),
+            Message::IndexSaved,
+        )
+    }
+
+    /// Whether Tab should indent the document rather than move focus. The
+    /// editor is the only text field on the viewer, so it owns Tab unless a
+    /// dialog is open on top of it.
+    /// Whether a document is open, even if another page is showing, like
+    /// Properties or Settings.
+    fn document_loaded(&self) -> bool {
+        self.path.is_some()
+            || self.is_dirty
+            || !self.doc_name.is_empty()
+            || !self.password.is_empty()
+    }
+
+    /// Whether the toolbar action can be pressed, so it takes part in the
+    /// Tab order.
+    fn toolbar_enabled(&self, action: ToolbarAction) -> bool {
+        action != ToolbarAction::Save || self.is_dirty
+    }
+
+    fn editor_has_keyboard(&self) -> bool {
+        matches!(self.current_page, Page::DocumentViewer)
+            && self.pager.is_none()
+            && self.prompt.is_none()
+            && self.overwrite.is_none()
+            && self.reauth.is_none()
+    }
+
+    /// The editor text with the document's own line endings restored, which
+    /// the editor normalizes away.
+    fn document_text(&self) -> String {
+        self.line_ending.apply(&self.text())
+    }
+
+    /// The text being edited, or shown in the pager.
+    fn text(&self) -> String {
+        match &self.pager {
+            Some(pager) => pager.text.clone(),
+            None => self.content.text(),
+        }
+    }
+
+    /// Replaces the document text, in the pager if it's too large to edit.
+    fn set_text(&mut self, text: String) {
+        self.compare = None;
+
+        if text.len() >= LARGE_DOCUMENT_SIZE {
+            self.content = text_editor::Content::new();
+            self.pager = Some(Pager::new(text));
+        } else {
+            self.content = text_editor::Content::with_text(&text);
+            self.pager = None;
+        }
+    }
+
+    fn document(&self) -> Document {
+        Document {
+            text: self.text(),
+            encoding: Encoding::Utf8,
+            line_ending: self.line_ending,
+            container: self.container,
+            history: self.history.clone(),
+        }
+    }
+
+    /// Asks for the password to encrypt or decrypt the clipboard with. The
+    /// open document's password is filled in, since it's usually the one
+    /// shared with whoever is on the other end.
+    fn open_clipboard_prompt(&mut self, action: ClipboardAction) -> Task<Message> {
+        self.clipboard_prompt = Some(ClipboardPrompt {
+            action,
+            password: self.password.clone(),
+            error: None,
+            working: false,
+        });
+
+        Task::batch([show_window(), text_input::focus(clipboard_prompt_id())])
+    }
+
+    /// Puts the window back how it was before capturing.
+    fn close_capture(&mut self) -> Task<Message> {
+        let Some(capture) = self.capture.take() else {
+            return Task::none();
+        };
+
+        Task::batch([
+            window::change_level(capture.window, window::Level::Normal),
+            window::resize(capture.window, capture.size),
+            window::change_mode(capture.window, capture.mode),
+        ])
+    }
+
+    /// Recomputes everything derived from the document text after it changes.
+    fn document_changed(&mut self) {
+        let text = self.text();
+
+        self.checklist = checklist(&text);
+        self.outline = outline(&text);
+        self.table = SecretTable::parse(&text);
+        self.totp_seeds = find_seeds(&text);
+        self.images = find_images(&text, &mut std::mem::take(&mut self.images));
+        self.links = links(&text);
+        self.revealed.clear();
+
+        if self.stats.is_some() {
+            self.stats = Some(TextStats::new(&text));
+        }
+    }
+
+    /// Writes an edited table back into the document text.
+    fn table_changed(&mut self) {
+        if let Some(table) = &self.table {
+            self.set_text(table.to_text());
+            self.is_dirty = true;
+        }
+    }
+
+    /// Flips the checkbox on `line` in place, leaving the cursor and undo
+    /// history of the editor intact.
+    fn toggle_task(&mut self, line: usize) {
+        let Some(item) = self
+            .content
+            .line(line)
+            .and_then(|text| parse_line(line, &text))
+        else {
+            return;
+        };
+
+        // Moving collapses any selection, so the position is read afterwards.
+        if self.content.selection().is_some() {
+            self.content
+                .perform(text_editor::Action::Move(text_editor::Motion::Left));
+        }
+
+        let cursor = self.cursor_offset();
+        let mark = self.char_offset(line, item.mark);
+
+        self.move_cursor(cursor, mark);
+        self.content
+            .perform(text_editor::Action::Select(text_editor::Motion::Right));
+        self.content
+            .perform(text_editor::Action::Edit(text_editor::Edit::Paste(
+                Arc::new(if item.done { " " } else { "x" }.to_string()),
+            )));
+        self.move_cursor(mark + 1, cursor);
+
+        self.is_dirty = true;
+        self.document_changed();
+    }
+
+    fn cursor_offset(&self) -> usize {
+        let (line, index) = self.content.cursor_position();
+
+        // The cursor index is in bytes; offsets are counted in characters.
+        let column = self.content.line(line).map_or(0, |text| {
+            text.get(..index).unwrap_or(&*text).chars().count()
+        });
+
+        self.char_offset(line, column)
+    }
+
+    /// The number of characters before `line`/`column`, counting each line
+    /// break as one.
+    fn char_offset(&self, line: usize, column: usize) -> usize {
+        (0..line)
+            .map(|line| {
+                self.content
+                    .line(line)
+                    .map_or(0, |text| text.chars().count())
+                    + 1
+            })
+            .sum::<usize>()
+            + column
+    }
+
+    /// Steps the cursor one character at a time. Vertical motions follow
+    /// wrapped lines on screen, so they can't reach a logical line reliably.
+    fn move_cursor(&mut self, from: usize, to: usize) {
+        let motion = if to > from {
+            text_editor::Motion::Right
+        } else {
+            text_editor::Motion::Left
+        };
+
+        for _ in 0..from.abs_diff(to) {
+            self.content.perform(text_editor::Action::Move(motion));
+        }
+    }
+
+    /// The leading whitespace of the cursor's line, up to the cursor.
+    fn current_indent(&self) -> String {
+        let (line, column) = self.content.cursor_position();
+
+        self.content
+            .line(line)
+            .map(|text| {
+                text.chars()
+                    .take(column)
+                    .take_while(|c| *c == ' ' || *c == '\t')
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Moves keyboard focus to the main input of the current page, so that
+    /// switching pages or closing a dialog never leaves focus nowhere.
+    fn focus_page(&self) -> Task<Message> {
+        match self.current_page {
+            Page::NewDocumentPage => text_input::focus(new_document_name_id()),
+            Page::Search => text_input::focus(search_query_id()),
+            Page::Browser => text_input::focus(browser_filter_id()),
+            _ => Task::none(),
+        }
+    }
+
+    fn subscription(&self) -> Subscription<Message> {
+        let shortcuts = keyboard::on_key_press(|key, modifiers| match key.as_ref() {
+            keyboard::Key::Character("s") if modifiers.command() => {
+                Some(Message::SaveDocumentPressed)
+            }
+            keyboard::Key::Character("f" | "F") if modifiers.command() && modifiers.shift() => {
+                Some(Message::SearchPressed)
+            }
+            keyboard::Key::Character("n") if modifiers.command() => {
+                Some(Message::NewDocumentPressed)
+            }
+            keyboard::Key::Character("o") if modifiers.command() => {
+                Some(Message::OpenDocumentPressed)
+            }
+            keyboard::Key::Character("b") if modifiers.command() => Some(Message::BrowsePressed),
+            keyboard::Key::Character("i") if modifiers.command() => {
+                Some(Message::PropertiesPressed)
+            }
+            keyboard::Key::Character("l") if modifiers.command() => Some(Message::LockPressed),
+            keyboard::Key::Character("d" | "D") if modifiers.command() && modifiers.shift() => {
+                Some(Message::InsertDatePressed)
+            }
+            keyboard::Key::Character("t") if modifiers.command() => {
+                Some(Message::ToggleTaskPressed)
+            }
+            keyboard::Key::Character(",") if modifiers.command() => Some(Message::SettingsPressed),
+            keyboard::Key::Named(keyboard::key::Named::Home) if modifiers.alt() => {
+                Some(Message::HomePressed)
+            }
+            keyboard::Key::Named(keyboard::key::Named::Tab) if modifiers.shift() => {
+                Some(Message::FocusPrevious)
+            }
+            keyboard::Key::Named(keyboard::key::Named::Tab) => Some(Message::FocusNext),
+            keyboard::Key::Named(keyboard::key::Named::F6) => Some(Message::FocusToolbar),
+            keyboard::Key::Named(keyboard::key::Named::Enter | keyboard::key::Named::Space)
+                if modifiers.is_empty() =>
+            {
+                Some(Message::ToolbarActivated)
+            }
+            _ => None,
+        });
+
+        // Focused text inputs capture Escape to unfocus themselves, so it's
+        // listened for regardless of event status to still close dialogs.
+        let escape = event::listen_with(|event, _status, _window| match event {
+            Event::Keyboard(keyboard::Event::KeyPressed {
+                key: keyboard::Key::Named(keyboard::key::Named::Escape),
+                ..
+            }) => Some(Message::EscapePressed),
+            _ => None,
+        });
+
+        // iced doesn't expose the Caps Lock state, so it's inferred from the
+        // case of typed letters compared to whether Shift was held.
+        let caps_lock = event::listen_with(|event, _status, _window| match event {
+            Event::Keyboard(keyboard::Event::KeyPressed {
+                key: keyboard::Key::Named(keyboard::key::Named::CapsLock),
+                ..
+            }) => Some(Message::CapsLockToggled),
+            Event::Keyboard(keyboard::Event::KeyPressed {
+                text: Some(text),
+                modifiers,
+                ..
+            }) => {
+                let mut chars = text.chars();
+
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) if c.is_uppercase() || c.is_lowercase() => Some(
+                        Message::CapsLockDetected(c.is_uppercase() != modifiers.shift()),
+                    ),
+                    _ => None,
+                }
+            }
+            _ => None,
+        });
+
+        let subscriptions = Subscription::batch([
+            shortcuts,
+            escape,
+            caps_lock,
+            tray::events().map(Message::Tray),
+            capture::events().map(|()| Message::CaptureHotkey),
+            instance::requests().map(Message::Instance),
+            window::close_requests().map(Message::CloseRequested),
+        ]);
+
+        // Clicking anywhere goes back to the mouse, so the toolbar outline
+        // shouldn't linger.
+        let toolbar = if self.toolbar_focus.is_some() {
+            event::listen_with(|event, _status, _window| match event {
+                Event::Mouse(mouse::Event::ButtonPressed(_)) => Some(Message::ToolbarUnfocused),
+                _ => None,
+            })
+        } else {
+            Subscription::none()
+        };
+
+        let started = if self.started {
+            Subscription::none()
+        } else {
+            started()
+        };
+
+        // Only poll the session while there is an unlocked document to protect.
+        let session = if self.config.lock_on_session_events && self.document_loaded() {
+            session::events().map(Message::Session)
+        } else {
+            Subscription::none()
+        };
+
+        let has_codes = !self.totp_seeds.is_empty()
+            || self.table.as_ref().is_some_and(|table| {
+                table
+                    .rows
+                    .iter()
+                    .any(|row| Totp::parse(&row.totp).is_some())
+            });
+
+        let totp = if has_codes && matches!(self.current_page, Page::DocumentViewer) {
+            iced::time::every(Duration::from_secs(1)).map(|_| Message::TotpTick)
+        } else {
+            Subscription::none()
+        };
+
+        let recovery = if self.is_dirty && matches!(self.current_page, Page::DocumentViewer) {
+            iced::time::every(recovery::INTERVAL).map(|_| Message::RecoveryTick)
+        } else {
+            Subscription::none()
+        };
+
+        let updates = if self.config.check_for_updates {
+            update::check(self.config.release_channel).map(Message::UpdateChecked)
+        } else {
+            Subscription::none()
+        };
+
+        Subscription::batch([
+            subscriptions,
+            toolbar,
+            started,
+            session,
+            totp,
+            recovery,
+            updates,
+        ])
+    }
+
+    fn scale_factor(&self) -> f64 {
+        self.config.ui_scale
+    }
+
+    fn theme(&self) -> Theme {
+        if self.theme.is_dark() {
+            Theme::Dark
+        } else {
+            Theme::Light
+        }
+    }
+}
+
+struct PasswordPrompt {
+    path: PathBuf,
+    /// Shared with the file that was read, rather than copied, as it can be
+    /// large.
+    encrypted: Arc<Contents>,
+    password: String,
+    error: Option<String>,
+    attempts: u32,
+    /// Set once the document has failed to unlock too many times, even in
+    /// an earlier session. See [`lockout`].
+    locked_until: Option<DateTime<Utc>>,
+    keypad: Option<Keypad>,
+    decrypting: bool,
+    /// Whether this is a crash-recovery journal rather than the saved file.
+    recovered: bool,
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+fn show_window() -> Task<Message> {
+    window::get_oldest().and_then(|id| {
+        Task::batch([
+            window::change_mode(id, window::Mode::Windowed),
+            window::gain_focus(id),
+        ])
+    })
+}
+
+fn progress_overlay(operation: &Operation) -> Element<Message> {
+    let title = text(tr(operation.title));
+
+    let progress = operation.progress.unwrap_or(Progress { done: 0, total: 0 });
+
+    let bar = progress_bar(0.0..=1.0, progress.fraction()).height(8);
+
+    let count = text(tr_with(
+        "progress.count",
+        format!("{}/{}", progress.done, progress.total),
+    ))
+    .size(14);
+
+    let cancel_btn = if operation.is_cancelled() {
+        button(tr("progress.cancelling")).style(button::secondary)
+    } else {
+        button(tr("progress.cancel"))
+            .style(button::secondary)
+            .on_press(Message::OperationCancelled)
+    };
+
+    container(
+        column![
+            title,
+            bar,
+            row![count, horizontal_space(), cancel_btn].align_items(Alignment::Center)
+        ]
+        .spacing(10),
+    )
+    .width(400)
+    .padding(20)
+    .style(container::rounded_box)
+    .into()
+}
+
+struct OverwritePrompt {
+    path: PathBuf,
+    then_save: bool,
+}
+
+fn overwrite_prompt(overwrite: &OverwritePrompt) -> Element<Message> {
+    let title = text(tr("overwrite.title"));
+
+    let body = text(tr_with(
+        "overwrite.body",
+        pathbuf_to_string(&overwrite.path),
+    ));
+
+    let buttons = row![
+        horizontal_space(),
+        button(tr("prompt.cancel"))
+            .style(button::secondary)
+            .on_press(Message::OverwriteCancelled),
+        button(tr("overwrite.rename")).on_press(Message::OverwriteRenamed),
+        button(tr("overwrite.overwrite"))
+            .style(button::danger)
+            .on_press(Message::OverwriteConfirmed),
+    ]
+    .spacing(10);
+
+    container(column![title, body, buttons].spacing(10))
+        .width(400)
+        .padding(20)
+        .style(container::rounded_box)
+        .into()
+}
+
+fn recovery_prompt(recovery: &Recovery) -> Element<Message> {
+    let title = text(tr("recovery.title"));
+
+    let body = text(tr_with("recovery.body", pathbuf_to_string(&recovery.path)));
+
+    let buttons = row![
+        horizontal_space(),
+        button(tr("recovery.discard"))
+            .style(button::danger)
+            .on_press(Message::RecoveryDiscarded),
+        button(tr("recovery.restore")).on_press(Message::RecoveryRestored),
+    ]
+    .spacing(10);
+
+    container(column![title, body, buttons].spacing(10))
+        .width(400)
+        .padding(20)
+        .style(container::rounded_box)
+        .into()
+}
+
+/// Actions that put decrypted text somewhere outside the app.
+#[derive(Debug, Clone, Copy)]
+enum PlaintextAction {
+    Export(&'static dyn Exporter),
+    CopyAll,
+    /// Encrypts a copy with a one-time key, which opens it as well as the
+    /// password would.
+    Share,
+}
+
+/// Ways to share the encrypted document as text. Unlike a
+/// [`PlaintextAction`], nothing readable leaves the app, so the password
+/// isn't asked for again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ArmorAction {
+    Copy,
+    Export,
+}
+
+impl ArmorAction {
+    const ALL: &'static [Self] = &[Self::Copy, Self::Export];
+}
+
+impl fmt::Display for ArmorAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArmorAction::Copy => tr("armor.copy"),
+            ArmorAction::Export => tr("armor.export"),
+        }
+        .fmt(f)
+    }
+}
+
+/// What to do to the clipboard from the tray. Encrypted text is armored, so
+/// it can be pasted anywhere plain text goes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ClipboardAction {
+    Encrypt,
+    Decrypt,
+}
+
+struct ClipboardPrompt {
+    action: ClipboardAction,
+    password: String,
+    error: Option<String>,
+    working: bool,
+}
+
+fn clipboard_prompt(prompt: &ClipboardPrompt) -> Element<Message> {
+    let (title, body) = match prompt.action {
+        ClipboardAction::Encrypt => ("clipboard.encrypt_title", "clipboard.encrypt_body"),
+        ClipboardAction::Decrypt => ("clipboard.decrypt_title", "clipboard.decrypt_body"),
+    };
+
+    let password = text_input(tr("common.password"), &prompt.password)
+        .id(clipboard_prompt_id())
+        .padding(10)
+        .on_input(Message::ClipboardPasswordInput)
+        .on_submit(Message::ClipboardSubmitted)
+        .secure(true);
+
+    let error = prompt
+        .error
+        .as_ref()
+        .map(|error| text(error.as_str()).color(Color::from_rgb(0.9, 0.3, 0.3)));
+
+    let buttons = row![
+        horizontal_space(),
+        button(tr("prompt.cancel"))
+            .style(button::secondary)
+            .on_press_maybe((!prompt.working).then_some(Message::ClipboardCancelled)),
+        button(tr(match prompt.action {
+            ClipboardAction::Encrypt => "clipboard.encrypt",
+            ClipboardAction::Decrypt => "clipboard.decrypt",
+        }))
+        .on_press_maybe(
+            (!prompt.working && !prompt.password.is_empty()).then_some(Message::ClipboardSubmitted)
+        ),
+    ]
+    .spacing(10);
+
+    container(
+        column![text(tr(title)), text(tr(body)), password]
+            .push_maybe(error)
+            .push(buttons)
+            .spacing(10),
+    )
+    .width(400)
+    .padding(20)
+    .style(container::rounded_box)
+    .into()
+}
+
+fn clipboard_prompt_id() -> text_input::Id {
+    text_input::Id::new("clipboard-prompt")
+}
+
+struct ReauthPrompt {
+    action: PlaintextAction,
+    password: String,
+    error: Option<String>,
+}
+
+fn reauth_prompt(reauth: &ReauthPrompt) -> Element<Message> {
+    let title = text(tr("reauth.title"));
+
+    let pass_input = text_input(tr("common.password"), &reauth.password)
+        .id(reauth_prompt_id())
+        .padding(10)
+        .on_input(Message::ReauthPasswordInput)
+        .on_submit(Message::ReauthSubmitted)
+        .secure(true);
+
+    let error = reauth
+        .error
+        .as_ref()
+        .map(|error| text(error.as_str()).color(Color::from_rgb(0.9, 0.3, 0.3)));
+
+    let buttons = row![
+        horizontal_space(),
+        button(tr("prompt.cancel"))
+            .style(button::secondary)
+            .on_press(Message::ReauthCancelled),
+        button(tr("reauth.confirm")).on_press(Message::ReauthSubmitted),
+    ]
+    .spacing(10);
+
+    container(
+        column![title, pass_input]
+            .push_maybe(error)
+            .push(buttons)
+            .spacing(10),
+    )
+    .width(400)
+    .padding(20)
+    .style(container::rounded_box)
+    .into()
+}
+
+/// A share bundle that has been written, with the key to hand over.
+struct SharePrompt {
+    key: String,
+    path: PathBuf,
+    qr: Option<iced::widget::image::Handle>,
+}
+
+fn share_prompt(share: &SharePrompt) -> Element<Message> {
+    let title = text(tr("share.title"));
+
+    let body = text(tr_with("share.body", pathbuf_to_string(&share.path)));
+
+    let key = text(share.key.as_str()).font(Font::MONOSPACE).size(22);
+
+    let qr = share
+        .qr
+        .clone()
+        .map(|handle| iced::widget::image(handle).width(200).height(200));
+
+    let buttons = row![
+        horizontal_space(),
+        button(tr("share.copy_key"))
+            .style(button::secondary)
+            .on_press(Message::ShareKeyCopied),
+        button(tr("share.done")).on_press(Message::ShareClosed),
+    ]
+    .spacing(10);
+
+    container(
+        column![title, body, key]
+            .push_maybe(qr)
+            .push(text(tr("share.warning")).size(14))
+            .push(buttons)
+            .spacing(10)
+            .align_items(Alignment::Center),
+    )
+    .width(450)
+    .padding(20)
+    .style(container::rounded_box)
+    .into()
+}
+
+struct JournalPrompt {
+    password: String,
+    error: Option<String>,
+    opening: bool,
+}
+
+fn journal_prompt(prompt: &JournalPrompt) -> Element<Message> {
+    let title = text(tr("journal.title"));
+
+    let body = text(tr("journal.body"));
+
+    let password = text_input(tr("common.password"), &prompt.password)
+        .id(journal_prompt_id())
+        .padding(10)
+        .on_input(Message::JournalPasswordInput)
+        .on_submit(Message::JournalSubmitted)
+        .secure(true);
+
+    let error = prompt
+        .error
+        .as_ref()
+        .map(|error| text(error.as_str()).color(Color::from_rgb(0.9, 0.3, 0.3)));
+
+    let buttons = row![
+        horizontal_space(),
+        button(tr("prompt.cancel"))
+            .style(button::secondary)
+            .on_press_maybe((!prompt.opening).then_some(Message::JournalCancelled)),
+        button(tr("journal.open")).on_press_maybe(
+            (!prompt.opening && !prompt.password.is_empty()).then_some(Message::JournalSubmitted)
+        ),
+    ]
+    .spacing(10);
+
+    container(
+        column![title, body, password]
+            .push_maybe(error)
+            .push(buttons)
+            .spacing(10),
+    )
+    .width(400)
+    .padding(20)
+    .style(container::rounded_box)
+    .into()
+}
+
+fn journal_prompt_id() -> text_input::Id {
+    text_input::Id::new("journal-prompt")
+}
+
+struct ExternalEditPrompt {
+    id: u64,
+    path: PathBuf,
+    password: String,
+    error: Option<String>,
+    decrypting: bool,
+}
+
+struct ExternalEdit {
+    id: u64,
+    path: PathBuf,
+    password: String,
+    document: Document,
+}
+
+/// Tells when a document locked by failed attempts can be tried again.
+fn locked_message(until: DateTime<Utc>) -> String {
+    tr_with(
+        "prompt.locked",
+        until.with_timezone(&Local).format("%H:%M:%S"),
+    )
+}
+
+fn external_edit_prompt(prompt: &ExternalEditPrompt) -> Element<Message> {
+    let title = text(tr("external_edit.title"));
+
+    let body = text(tr_with(
+        "external_edit.body",
+        pathbuf_to_string(&prompt.path),
+    ));
+
+    let pass_input = text_input(tr("common.password"), &prompt.password)
+        .id(external_edit_prompt_id())
+        .padding(10)
+        .on_input(Message::ExternalEditPasswordInput)
+        .on_submit(Message::ExternalEditAllowed)
+        .secure(true);
+
+    let error = prompt
+        .error
+        .as_ref()
+        .map(|error| text(error.as_str()).color(Color::from_rgb(0.9, 0.3, 0.3)));
+
+    let buttons = row![
+        horizontal_space(),
+        button(tr("external_edit.deny"))
+            .style(button::secondary)
+            .on_press(Message::ExternalEditDenied),
+        button(tr("external_edit.allow")).on_press_maybe(
+            (!prompt.decrypting && !prompt.password.is_empty())
+                .then_some(Message::ExternalEditAllowed)
+        ),
+    ]
+    .spacing(10);
+
+    container(
+        column![title, body, pass_input]
+            .push_maybe(error)
+            .push(buttons)
+            .spacing(10),
+    )
+    .width(400)
+    .padding(20)
+    .style(container::rounded_box)
+    .into()
+}
+
+struct ArchivePrompt {
+    importer: &'static dyn ArchiveImporter,
+    path: PathBuf,
+    archive_password: String,
+    /// The password for the new documents.
+    password: String,
+    error: Option<String>,
+    importing: bool,
+}
+
+impl ArchivePrompt {
+    fn can_submit(&self) -> bool {
+        !self.importing
+            && !self.password.is_empty()
+            && (!self.importer.needs_password() || !self.archive_password.is_empty())
+    }
+}
+
+fn archive_prompt(prompt: &ArchivePrompt) -> Element<Message> {
+    let title = text(tr_with("archive.title", prompt.importer));
+
+    let body = text(tr_with("archive.body", pathbuf_to_string(&prompt.path)));
+
+    let archive_password = prompt.importer.needs_password().then(|| {
+        text_input(tr("archive.archive_password"), &prompt.archive_password)
+            .id(archive_prompt_id())
+            .padding(10)
+            .on_input(Message::ArchivePasswordInput)
+            .on_submit(Message::ArchiveImportSubmitted)
+            .secure(true)
+    });
+
+    let password = text_input(tr("archive.password"), &prompt.password)
+        .padding(10)
+        .on_input(Message::ArchiveDocumentPasswordInput)
+        .on_submit(Message::ArchiveImportSubmitted)
+        .secure(true);
+
+    let error = prompt
+        .error
+        .as_ref()
+        .map(|error| text(error.as_str()).color(Color::from_rgb(0.9, 0.3, 0.3)));
+
+    let buttons = row![
+        horizontal_space(),
+        button(tr("prompt.cancel"))
+            .style(button::secondary)
+            .on_press_maybe((!prompt.importing).then_some(Message::ArchiveImportCancelled)),
+        button(if prompt.importing {
+            tr("archive.importing")
+        } else {
+            tr("archive.import")
+        })
+        .on_press_maybe(
+            prompt
+                .can_submit()
+                .then_some(Message::ArchiveImportSubmitted)
+        ),
+    ]
+    .spacing(10);
+
+    container(
+        column![title, body]
+            .push_maybe(archive_password)
+            .push(password)
+            .push_maybe(error)
+            .push(buttons)
+            .spacing(10),
+    )
+    .width(400)
+    .padding(20)
+    .style(container::rounded_box)
+    .into()
+}
+
+fn archive_prompt_id() -> text_input::Id {
+    text_input::Id::new("archive-prompt")
+}
+
+/// One entry of an imported archive, and where it was saved.
+#[derive(Debug, Clone)]
+struct ImportedDocument {
+    name: String,
+    tags: Vec<String>,
+    result: Result<PathBuf, DocumentError>,
+}
+
+/// Reads an archive and saves each of its entries as a new document in
+/// `vault`, never replacing one that's already there.
+async fn import_archive(
+    importer: &'static dyn ArchiveImporter,
+    path: PathBuf,
+    archive_password: String,
+    vault: Vault,
+    password: String,
+) -> Result<Vec<ImportedDocument>, FormatError> {
+    let bytes = tokio::fs::read(&path)
+        .await
+        .map_err(|error| FormatError(error.to_string()))?;
+
+    // Opening a password manager database runs its own key derivation, which
+    // is slow on purpose.
+    let documents = tokio::task::spawn_blocking(move || {
+        let archive_password = importer.needs_password().then_some(archive_password);
+
+        importer.import(&bytes, archive_password.as_deref())
+    })
+    .await
+    .map_err(|error| FormatError(error.to_string()))??;
+
+    let mut results = vec![];
+
+    for imported in documents {
+        let path = vault.unused_path_for(&imported.name);
+        let result = imported.document.save(path, &password).await;
+
+        results.push(ImportedDocument {
+            name: imported.name,
+            tags: imported.tags,
+            result,
+        });
+    }
+
+    Ok(results)
+}
+
+struct ArchiveExportPrompt {
+    exporter: &'static dyn ArchiveExporter,
+    password: String,
+    error: Option<String>,
+    decrypting: bool,
+}
+
+fn archive_export_prompt(prompt: &ArchiveExportPrompt) -> Element<Message> {
+    let title = text(tr_with("archive_export.title", prompt.exporter));
+
+    let body = text(tr("archive_export.body"));
+
+    let password = text_input(tr("archive_export.password"), &prompt.password)
+        .id(archive_export_prompt_id())
+        .padding(10)
+        .on_input(Message::ArchiveExportPasswordInput)
+        .on_submit(Message::ArchiveExportSubmitted)
+        .secure(true);
+
+    let error = prompt
+        .error
+        .as_ref()
+        .map(|error| text(error.as_str()).color(Color::from_rgb(0.9, 0.3, 0.3)));
+
+    let buttons = row![
+        horizontal_space(),
+        button(tr("prompt.cancel"))
+            .style(button::secondary)
+            .on_press_maybe((!prompt.decrypting).then_some(Message::ArchiveExportCancelled)),
+        button(if prompt.decrypting {
+            tr("archive_export.decrypting")
+        } else {
+            tr("archive_export.export")
+        })
+        .on_press_maybe(
+            (!prompt.decrypting && !prompt.password.is_empty())
+                .then_some(Message::ArchiveExportSubmitted)
+        ),
+    ]
+    .spacing(10);
+
+    container(
+        column![title, body, password]
+            .push_maybe(error)
+            .push(buttons)
+            .spacing(10),
+    )
+    .width(400)
+    .padding(20)
+    .style(container::rounded_box)
+    .into()
+}
+
+fn archive_export_prompt_id() -> text_input::Id {
+    text_input::Id::new("archive-export-prompt")
+}
+
+/// Decrypts every document in `vault` for an export. Documents that don't
+/// open are counted and left out, unless none do, which most likely means
+/// the password is wrong. Each wrong password counts towards that
+/// document's lockout, and locked documents aren't tried.
+async fn decrypt_vault(
+    vault: Vault,
+    password: String,
+) -> Result<(Vec<NamedDocument>, usize), DocumentError> {
+    let mut documents = vec![];
+    let mut first_error = None;
+    let mut failed = 0;
+
+    for info in vault.documents().await? {
+        match lockout::open(info.path.clone(), &password).await {
+            Ok(document) => documents.push(NamedDocument {
+                name: document_name(&info.path),
+                document,
+                modified: info.modified,
+                tags: vec![],
+            }),
+            Err(error) => {
+                tracing::error!(path = %info.path.display(), %error, "couldn't export document");
+
+                failed += 1;
+                first_error.get_or_insert(error);
+            }
+        }
+    }
+
+    match first_error {
+        Some(error) if documents.is_empty() => Err(error),
+        _ => Ok((documents, failed)),
+    }
+}
+
+fn external_edit_prompt_id() -> text_input::Id {
+    text_input::Id::new("external-edit-prompt")
+}
+
+fn reauth_prompt_id() -> text_input::Id {
+    text_input::Id::new("reauth-prompt")
+}
+
+fn password_prompt_id() -> text_input::Id {
+    text_input::Id::new("password-prompt")
+}
+
+fn new_document_name_id() -> text_input::Id {
+    text_input::Id::new("new-document-name")
+}
+
+fn search_query_id() -> text_input::Id {
+    text_input::Id::new("search-query")
+}
+
+fn browser_filter_id() -> text_input::Id {
+    text_input::Id::new("browser-filter")
+}
+
+fn caps_lock_warning<'a>(caps_lock: bool) -> Option<Element<'a, Message>> {
+    caps_lock.then(|| {
+        text(tr("common.caps_lock"))
+            .size(14)
+            .color(Color::from_rgb(0.9, 0.6, 0.2))
+            .into()
+    })
+}
+
+fn password_prompt(prompt: &PasswordPrompt, caps_lock: bool) -> Element<Message> {
+    let title = text(format!(
+        "{} {}",
+        tr("prompt.title"),
+        pathbuf_to_string(&prompt.path)
+    ));
+
+    let pass_input = text_input(tr("common.password"), &prompt.password)
+        .id(password_prompt_id())
+        .padding(10)
+        .on_input(Message::PromptPasswordInput)
+        .on_submit(Message::TryDecrypt)
+        .secure(true);
+
+    let error = prompt.error.as_ref().map(|error| {
+        column![
+            text(error.as_str()).color(Color::from_rgb(0.9, 0.3, 0.3)),
+            text(tr_with("prompt.attempts", prompt.attempts)).size(14)
+        ]
+        .spacing(2)
+    });
+
+    let locked = prompt
+        .locked_until
+        .filter(|until| *until > Utc::now())
+        .map(|until| text(locked_message(until)).color(Color::from_rgb(0.9, 0.3, 0.3)));
+
+    let keypad = prompt.keypad.as_ref().map(|keypad| {
+        keypad::view(
+            keypad,
+            Message::KeypadPressed,
+            Message::KeypadBackspace,
+            Message::KeypadShift,
+        )
+    });
+
+    let buttons = row![
+        button(tr("prompt.keypad"))
+            .style(button::secondary)
+            .on_press(Message::KeypadToggled),
+        horizontal_space(),
+        button(tr("prompt.cancel"))
+            .style(button::secondary)
+            .on_press(Message::PromptCancelled),
+        button(if prompt.decrypting {
+            tr("prompt.decrypting")
+        } else {
+            tr("prompt.submit")
+        })
+        .on_press_maybe((!prompt.decrypting).then_some(Message::TryDecrypt)),
+    ]
+    .spacing(10);
+
+    container(
+        column![title, pass_input]
+            .push_maybe(caps_lock_warning(caps_lock))
+            .push_maybe(error)
+            .push_maybe(locked)
+            .push_maybe(keypad)
+            .push(buttons)
+            .spacing(10),
+    )
+    .width(400)
+    .padding(20)
+    .style(container::rounded_box)
+    .into()
+}
+
+impl Default for CryptoDoc {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,152 @@
+use std::io;
+
+use chrono::Local;
+use cryptodoc::audit::{AuditLog, Entry, Event, DEFAULT_PATH};
+use cryptodoc::file::FileError;
+use cryptodoc::DocumentError;
+use iced::widget::{button, column, row, text, text_input};
+use iced::{Color, Element, Font, Task};
+
+use crate::gui::i18n::{tr, tr_with};
+use crate::gui::Message;
+
+fn log() -> AuditLog {
+    AuditLog::new(DEFAULT_PATH)
+}
+
+pub fn is_enabled() -> bool {
+    log().exists()
+}
+
+/// Adds `event` to the log, if it's turned on.
+pub async fn append(event: Event) -> Result<(), FileError> {
+    tokio::task::spawn_blocking(move || log().append(event))
+        .await
+        .map_err(|_| FileError::IOFailed(io::ErrorKind::Other))?
+        .map_err(|error| FileError::IOFailed(error.kind()))
+}
+
+pub async fn create(password: String) -> Result<(), FileError> {
+    tokio::task::spawn_blocking(move || log().create(&password))
+        .await
+        .map_err(|_| FileError::IOFailed(io::ErrorKind::Other))?
+        .map_err(|error| FileError::IOFailed(error.kind()))
+}
+
+pub async fn read(password: String) -> Result<Vec<Entry>, DocumentError> {
+    tokio::task::spawn_blocking(move || log().read(&password))
+        .await
+        .map_err(|_| DocumentError::Io(io::ErrorKind::Other))?
+        .map_err(DocumentError::from)
+}
+
+/// The audit log section of Settings. The entries are only kept while
+/// they're shown, and forgotten on lock.
+#[derive(Debug, Default)]
+pub struct AuditView {
+    pub enabled: bool,
+    pub password: String,
+    pub entries: Option<Vec<Entry>>,
+    pub error: Option<String>,
+    pub working: bool,
+}
+
+impl AuditView {
+    pub fn view(&self) -> Element<Message> {
+        let description = text(tr("audit.description")).size(14);
+
+        let error = self
+            .error
+            .as_ref()
+            .map(|error| text(error.as_str()).color(Color::from_rgb(0.9, 0.3, 0.3)));
+
+        if let Some(entries) = &self.entries {
+            let entries = entries
+                .iter()
+                .rev()
+                .fold(column![].spacing(5), |list, entry| {
+                    list.push(
+                        row![
+                            text(
+                                entry
+                                    .time
+                                    .with_timezone(&Local)
+                                    .format("%Y-%m-%d %H:%M:%S")
+                                    .to_string()
+                            )
+                            .font(Font::MONOSPACE)
+                            .size(14),
+                            text(describe(&entry.event)).size(14),
+                        ]
+                        .spacing(20),
+                    )
+                });
+
+            let empty = self
+                .entries
+                .as_ref()
+                .is_some_and(Vec::is_empty)
+                .then(|| text(tr("audit.empty")).size(14));
+
+            return column![
+                description,
+                button(tr("audit.hide"))
+                    .style(button::secondary)
+                    .on_press(Message::AuditHidden)
+            ]
+            .push_maybe(empty)
+            .push(entries)
+            .spacing(10)
+            .into();
+        }
+
+        let (placeholder, label, submit) = if self.enabled {
+            (
+                tr("common.password"),
+                tr("audit.show"),
+                Message::AuditShowPressed,
+            )
+        } else {
+            (
+                tr("audit.new_password"),
+                tr("audit.enable"),
+                Message::AuditEnablePressed,
+            )
+        };
+
+        let can_submit = !self.working && !self.password.is_empty();
+
+        let password = text_input(placeholder, &self.password)
+            .on_input(Message::AuditPasswordInput)
+            .on_submit_maybe(can_submit.then_some(submit.clone()))
+            .secure(true)
+            .padding([5, 10])
+            .width(300);
+
+        column![
+            description,
+            row![
+                password,
+                button(label).on_press_maybe(can_submit.then_some(submit))
+            ]
+            .spacing(10)
+        ]
+        .push_maybe(error)
+        .spacing(10)
+        .into()
+    }
+}
+
+fn describe(event: &Event) -> String {
+    match event {
+        Event::Opened { document } => tr_with("audit.opened", document),
+        Event::FailedUnlock { document } => tr_with("audit.failed_unlock", document),
+        Event::PasswordChanged { document } => tr_with("audit.password_changed", document),
+        Event::Exported { document } => tr_with("audit.exported", document),
+    }
+}
+
+/// Adds `event` to the log in the background.
+pub fn record(event: Event) -> Task<Message> {
+    Task::perform(append(event), Message::Audited)
+}
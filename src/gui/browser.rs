@@ -0,0 +1,80 @@
+use std::cmp::Reverse;
+use std::fmt;
+use std::time::SystemTime;
+
+pub use cryptodoc::file::format_size;
+use cryptodoc::file::{document_name, DocumentInfo};
+
+use crate::gui::config::Config;
+use crate::gui::i18n::{tr, tr_with};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SortOrder {
+    #[default]
+    Name,
+    Modified,
+    Size,
+}
+
+impl SortOrder {
+    pub const ALL: &'static [Self] = &[Self::Name, Self::Modified, Self::Size];
+}
+
+impl fmt::Display for SortOrder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SortOrder::Name => tr("sort.name"),
+            SortOrder::Modified => tr("sort.modified"),
+            SortOrder::Size => tr("sort.size"),
+        }
+        .fmt(f)
+    }
+}
+
+/// Returns the documents whose name contains `filter`, pinned ones first and
+/// each group ordered by `sort`.
+pub fn sorted_documents<'a>(
+    documents: &'a [DocumentInfo],
+    config: &Config,
+    sort: SortOrder,
+    filter: &str,
+) -> Vec<&'a DocumentInfo> {
+    let filter = filter.to_lowercase();
+
+    let mut documents: Vec<&DocumentInfo> = documents
+        .iter()
+        .filter(|document| {
+            document_name(&document.path)
+                .to_lowercase()
+                .contains(&filter)
+        })
+        .collect();
+
+    match sort {
+        SortOrder::Name => {
+            documents.sort_by_key(|document| document_name(&document.path).to_lowercase())
+        }
+        SortOrder::Modified => documents.sort_by_key(|document| Reverse(document.modified)),
+        SortOrder::Size => documents.sort_by_key(|document| Reverse(document.size)),
+    }
+
+    // Stable sort, so the order chosen above is kept within each group.
+    documents.sort_by_key(|document| !config.is_pinned(&document.path));
+
+    documents
+}
+
+pub fn format_age(modified: Option<SystemTime>) -> String {
+    let Some(elapsed) = modified.and_then(|modified| modified.elapsed().ok()) else {
+        return tr("age.unknown").to_string();
+    };
+
+    let minutes = elapsed.as_secs() / 60;
+
+    match minutes {
+        0 => tr("age.just_now").to_string(),
+        1..=59 => tr_with("age.minutes", minutes),
+        60..=1439 => tr_with("age.hours", minutes / 60),
+        _ => tr_with("age.days", minutes / 1440),
+    }
+}
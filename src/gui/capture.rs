@@ -0,0 +1,153 @@
+use std::path::PathBuf;
+
+use cryptodoc::{lockout, Document, DocumentError};
+use global_hotkey::hotkey::{Code, HotKey, Modifiers};
+use global_hotkey::{GlobalHotKeyEvent, GlobalHotKeyManager, HotKeyState};
+use iced::futures::SinkExt;
+use iced::widget::{button, column, container, horizontal_space, row, text, text_input};
+use iced::{window, Color, Element, Length, Size, Subscription};
+
+use crate::gui::i18n::{tr, tr_with};
+use crate::gui::Message;
+
+/// Shown in Settings; keep in step with [`hotkey`].
+pub const SHORTCUT: &str = "Ctrl+Shift+Space";
+
+/// The size of the window while capturing.
+pub const WINDOW_SIZE: Size = Size::new(480.0, 220.0);
+
+fn hotkey() -> HotKey {
+    HotKey::new(Some(Modifiers::CONTROL | Modifiers::SHIFT), Code::Space)
+}
+
+/// Registers the capture shortcut with the system. The returned manager must
+/// be kept alive while the app runs. Returns `None` where global shortcuts
+/// aren't available, like on Wayland, or when another app already has it.
+pub fn register() -> Option<GlobalHotKeyManager> {
+    let manager = GlobalHotKeyManager::new()
+        .inspect_err(|error| tracing::warn!(%error, "global shortcuts aren't available"))
+        .ok()?;
+
+    manager
+        .register(hotkey())
+        .inspect_err(|error| tracing::warn!(%error, "couldn't register the capture shortcut"))
+        .ok()?;
+
+    Some(manager)
+}
+
+pub fn events() -> Subscription<()> {
+    struct Events;
+
+    iced::subscription::channel(
+        std::any::TypeId::of::<Events>(),
+        16,
+        |mut output| async move {
+            loop {
+                let event =
+                    tokio::task::spawn_blocking(|| GlobalHotKeyEvent::receiver().recv()).await;
+
+                match event {
+                    Ok(Ok(event)) => {
+                        if event.state == HotKeyState::Pressed {
+                            let _ = output.send(()).await;
+                        }
+                    }
+                    // Nothing registered the shortcut, so it can never fire.
+                    _ => std::future::pending::<()>().await,
+                }
+            }
+        },
+    )
+}
+
+/// A note being typed into the capture window. The main window shrinks into
+/// it and floats above other apps, then goes back to how it was.
+pub struct Capture {
+    pub window: window::Id,
+    /// How the window was before capturing, to restore afterwards.
+    pub mode: window::Mode,
+    pub size: Size,
+    pub text: String,
+    pub password: String,
+    /// Whether the password is already known, so isn't asked for.
+    pub unlocked: bool,
+    pub error: Option<String>,
+    pub saving: bool,
+}
+
+impl Capture {
+    pub fn view(&self, inbox: &str) -> Element<Message> {
+        let title = text(tr_with("capture.title", inbox)).size(14);
+
+        let note = text_input(tr("capture.placeholder"), &self.text)
+            .id(input_id())
+            .padding(10)
+            .on_input(Message::CaptureInput)
+            .on_submit(Message::CaptureSubmitted);
+
+        let password = (!self.unlocked).then(|| {
+            text_input(tr("common.password"), &self.password)
+                .padding(10)
+                .on_input(Message::CapturePasswordInput)
+                .on_submit(Message::CaptureSubmitted)
+                .secure(true)
+        });
+
+        let error = self
+            .error
+            .as_ref()
+            .map(|error| text(error.as_str()).color(Color::from_rgb(0.9, 0.3, 0.3)));
+
+        let can_save = !self.saving
+            && !self.text.trim().is_empty()
+            && (self.unlocked || !self.password.is_empty());
+
+        let buttons = row![
+            horizontal_space(),
+            button(tr("prompt.cancel"))
+                .style(button::secondary)
+                .on_press_maybe((!self.saving).then_some(Message::CaptureCancelled)),
+            button(tr("capture.save"))
+                .on_press_maybe(can_save.then_some(Message::CaptureSubmitted)),
+        ]
+        .spacing(10);
+
+        container(
+            column![title, note]
+                .push_maybe(password)
+                .push_maybe(error)
+                .push(buttons)
+                .spacing(10),
+        )
+        .padding(15)
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .into()
+    }
+}
+
+pub fn input_id() -> text_input::Id {
+    text_input::Id::new("capture")
+}
+
+/// Adds `entry` to the end of the inbox, creating it if it doesn't exist yet.
+/// A wrong password counts towards the inbox's lockout.
+pub async fn append(path: PathBuf, password: String, entry: String) -> Result<(), DocumentError> {
+    let mut document = if path.exists() {
+        lockout::open(path.clone(), &password).await?
+    } else {
+        Document::new("")
+    };
+
+    let text = document.text.trim_end();
+    document.text = if text.is_empty() {
+        format!("{}\n", entry)
+    } else {
+        format!("{}\n\n{}\n", text, entry)
+    };
+
+    document.save(path, &password).await?;
+
+    Ok(())
+}
@@ -0,0 +1,45 @@
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChecklistItem {
+    pub line: usize,
+    /// Column of the character between the brackets.
+    pub mark: usize,
+    pub text: String,
+    pub done: bool,
+}
+
+/// Parses a Markdown task line such as `- [ ] buy milk` or `  * [x] done`.
+pub fn parse_line(line: usize, text: &str) -> Option<ChecklistItem> {
+    let indent = text.chars().take_while(|c| c.is_whitespace()).count();
+    let rest: String = text.chars().skip(indent).collect();
+
+    let rest = rest
+        .strip_prefix("- [")
+        .or_else(|| rest.strip_prefix("* ["))?;
+
+    let mut chars = rest.chars();
+    let done = match chars.next()? {
+        ' ' => false,
+        'x' | 'X' => true,
+        _ => return None,
+    };
+
+    let label = chars.as_str().strip_prefix(']')?;
+
+    Some(ChecklistItem {
+        line,
+        mark: indent + 3,
+        text: label.trim().to_string(),
+        done,
+    })
+}
+
+pub fn checklist(text: &str) -> Vec<ChecklistItem> {
+    text.lines()
+        .enumerate()
+        .filter_map(|(line, text)| parse_line(line, text))
+        .collect()
+}
+
+pub fn remaining(items: &[ChecklistItem]) -> usize {
+    items.iter().filter(|item| !item.done).count()
+}
@@ -0,0 +1,177 @@
+//! Compares the open document with one of its backups side by side, and
+//! puts changes back from the backup one at a time.
+
+use std::fmt;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use cryptodoc::diff::{diff, restore, Hunk};
+use cryptodoc::migrate::backups;
+use iced::widget::{button, column, horizontal_space, pick_list, row, scrollable, text};
+use iced::{Alignment, Color, Element, Font, Length};
+
+use crate::gui::i18n::{tr, tr_with};
+use crate::gui::Message;
+
+/// An earlier copy of the document to compare with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Revision {
+    pub path: PathBuf,
+    /// The format version the backup was made from.
+    pub version: u32,
+}
+
+impl fmt::Display for Revision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        tr_with("compare.backup", self.version).fmt(f)
+    }
+}
+
+/// The earlier copies of the document at `path`, newest first.
+pub async fn find_revisions(path: PathBuf) -> Vec<Revision> {
+    tokio::task::spawn_blocking(move || revisions(&path))
+        .await
+        .unwrap_or_default()
+}
+
+fn revisions(path: &Path) -> Vec<Revision> {
+    backups(path)
+        .into_iter()
+        .rev()
+        .map(|(version, path)| Revision { path, version })
+        .collect()
+}
+
+pub struct Compare {
+    pub revisions: Vec<Revision>,
+    pub selected: Revision,
+    /// The text of the selected revision, once it's been decrypted.
+    old: Option<String>,
+    hunks: Vec<Hunk>,
+}
+
+impl Compare {
+    /// Starts comparing with the newest of `revisions`, which can't be
+    /// empty.
+    pub fn new(revisions: Vec<Revision>) -> Self {
+        Self {
+            selected: revisions[0].clone(),
+            revisions,
+            old: None,
+            hunks: vec![],
+        }
+    }
+
+    pub fn select(&mut self, revision: Revision) {
+        self.selected = revision;
+        self.old = None;
+        self.hunks.clear();
+    }
+
+    pub fn set_old(&mut self, old: String, current: &str) {
+        self.hunks = diff(&old, current);
+        self.old = Some(old);
+    }
+
+    /// `current` with the hunk at `index` taken from the revision instead,
+    /// or `None` if it's gone.
+    pub fn restore(&mut self, index: usize, current: &str) -> Option<String> {
+        let old = self.old.as_deref()?;
+        let restored = restore(old, current, self.hunks.get(index)?);
+
+        self.hunks = diff(old, &restored);
+
+        Some(restored)
+    }
+
+    pub fn view(&self, current: &str, text_size: f32) -> Element<Message> {
+        let controls = row![
+            text(tr("compare.title")).size(14),
+            pick_list(
+                self.revisions.as_slice(),
+                Some(&self.selected),
+                Message::RevisionSelected
+            )
+            .text_size(14)
+            .padding([5, 10]),
+            horizontal_space(),
+            button(tr("compare.close"))
+                .style(button::secondary)
+                .on_press(Message::CompareClosed),
+        ]
+        .spacing(10)
+        .align_items(Alignment::Center);
+
+        let Some(old) = &self.old else {
+            return column![controls, text(tr("compare.loading")).size(14)]
+                .spacing(10)
+                .into();
+        };
+
+        if self.hunks.is_empty() {
+            return column![controls, text(tr("compare.identical")).size(14)]
+                .spacing(10)
+                .into();
+        }
+
+        let old_lines: Vec<&str> = old.lines().collect();
+        let new_lines: Vec<&str> = current.lines().collect();
+
+        let hunks =
+            self.hunks
+                .iter()
+                .enumerate()
+                .fold(column![].spacing(15), |hunks, (index, hunk)| {
+                    hunks.push(
+                        row![
+                            lines(&old_lines, &hunk.old, text_size, REMOVED),
+                            lines(&new_lines, &hunk.new, text_size, ADDED),
+                            button(tr("compare.restore"))
+                                .style(button::secondary)
+                                .on_press(Message::HunkRestored(index)),
+                        ]
+                        .spacing(10),
+                    )
+                });
+
+        column![
+            controls,
+            row![
+                text(tr("compare.backup_side"))
+                    .size(14)
+                    .width(Length::FillPortion(1)),
+                text(tr("compare.current_side"))
+                    .size(14)
+                    .width(Length::FillPortion(1)),
+            ],
+            scrollable(hunks).height(Length::Fill),
+        ]
+        .spacing(10)
+        .into()
+    }
+}
+
+const REMOVED: Color = Color::from_rgb(0.9, 0.3, 0.3);
+const ADDED: Color = Color::from_rgb(0.3, 0.7, 0.3);
+
+/// One side of a hunk, with line numbers.
+fn lines<'a>(
+    all: &[&str],
+    range: &Range<usize>,
+    text_size: f32,
+    color: Color,
+) -> Element<'a, Message> {
+    let lines = all[range.clone()]
+        .iter()
+        .enumerate()
+        .fold(column![], |lines, (offset, line)| {
+            lines.push(
+                text(format!("{:>5}  {}", range.start + offset + 1, line))
+                    .font(Font::MONOSPACE)
+                    .size(text_size)
+                    .color(color),
+            )
+        });
+
+    column![lines].width(Length::FillPortion(1)).into()
+}
@@ -0,0 +1,429 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use cryptodoc::file::FileError;
+use cryptodoc::migrate::backup_path;
+use cryptodoc::storage::Backend;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::gui::dialogs::save_file;
+use crate::gui::i18n::Language;
+use crate::gui::retention::Retention;
+use crate::gui::update::ReleaseChannel;
+
+pub const MIN_UI_SCALE: f64 = 0.75;
+pub const MAX_UI_SCALE: f64 = 2.0;
+pub const TAB_WIDTHS: &[u8] = &[2, 4, 8];
+
+const MAX_RECENT: usize = 10;
+
+/// The current settings schema. A new field with a sensible default doesn't
+/// need a new version; anything that moves, renames or reinterprets existing
+/// settings does, along with a step in [`MIGRATIONS`].
+pub const CONFIG_VERSION: u32 = 1;
+
+/// `MIGRATIONS[n]` upgrades settings from version `n` to `n + 1`.
+const MIGRATIONS: [fn(&mut Map<String, Value>) -> io::Result<()>; CONFIG_VERSION as usize] =
+    [move_save_path];
+
+/// Where the document folder was kept before version 1 of the settings.
+const LEGACY_SAVE_PATH_FILE: &str = "./save_path.dat";
+
+/// Set when settings that couldn't be read couldn't be backed up either, so
+/// they aren't saved over with the defaults.
+static KEEP_UNREADABLE: AtomicBool = AtomicBool::new(false);
+
+/// Settings that couldn't be read, replaced by the defaults.
+#[derive(Debug, Clone)]
+pub struct Unreadable {
+    /// Where a copy of them was kept, or `None` if that failed too and the
+    /// file is left alone instead.
+    pub backup: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub version: u32,
+    /// The folder documents are saved in and browsed from, or empty if none
+    /// has been chosen yet.
+    pub save_path: String,
+    /// The storage backend of each vault that doesn't use the default, by
+    /// folder.
+    pub storage_backends: HashMap<String, Backend>,
+    pub pinned: Vec<PathBuf>,
+    /// The document quick capture adds notes to.
+    pub inbox: Option<PathBuf>,
+    pub language: Language,
+    pub ui_scale: f64,
+    pub large_text: bool,
+    pub recent: Vec<PathBuf>,
+    pub minimize_to_tray: bool,
+    pub on_screen_keypad: bool,
+    pub require_reauth: bool,
+    pub lock_on_session_events: bool,
+    /// How long a document's plaintext is kept after it's closed or locked.
+    pub plaintext_retention: Retention,
+    pub tab_width: u8,
+    pub indent_with_spaces: bool,
+    pub auto_indent: bool,
+    pub date_format: String,
+    pub post_save_hook_enabled: bool,
+    /// A shell command run after each successful save.
+    pub post_save_hook: String,
+    pub check_for_updates: bool,
+    pub release_channel: ReleaseChannel,
+    /// The name of the active vault profile, empty until there's a second
+    /// one.
+    pub vault_name: String,
+    /// See [`VaultProfile::password_hash`].
+    pub vault_password_hash: Option<String>,
+    /// The vault profiles that aren't active.
+    pub vaults: Vec<VaultProfile>,
+}
+
+/// A save location with settings of its own, like "Work" and "Personal".
+/// The active one's are the fields of [`Config`] itself, the others wait in
+/// [`Config::vaults`] until they're switched to.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VaultProfile {
+    pub name: String,
+    pub save_path: String,
+    pub pinned: Vec<PathBuf>,
+    pub recent: Vec<PathBuf>,
+    pub inbox: Option<PathBuf>,
+    pub require_reauth: bool,
+    pub post_save_hook_enabled: bool,
+    pub post_save_hook: String,
+    /// A PBKDF2 hash of the password asked for before switching to the
+    /// vault, if it has one.
+    pub password_hash: Option<String>,
+}
+
+impl Default for VaultProfile {
+    fn default() -> Self {
+        let config = Config::default();
+
+        Self {
+            name: String::new(),
+            save_path: config.save_path,
+            pinned: config.pinned,
+            recent: config.recent,
+            inbox: config.inbox,
+            require_reauth: config.require_reauth,
+            post_save_hook_enabled: config.post_save_hook_enabled,
+            post_save_hook: config.post_save_hook,
+            password_hash: None,
+        }
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            version: CONFIG_VERSION,
+            save_path: String::new(),
+            storage_backends: HashMap::new(),
+            pinned: vec![],
+            inbox: None,
+            language: Language::default(),
+            ui_scale: 1.0,
+            large_text: false,
+            recent: vec![],
+            minimize_to_tray: false,
+            on_screen_keypad: false,
+            require_reauth: true,
+            lock_on_session_events: true,
+            plaintext_retention: Retention::default(),
+            tab_width: 4,
+            indent_with_spaces: true,
+            auto_indent: true,
+            date_format: "%Y-%m-%d %H:%M".to_string(),
+            post_save_hook_enabled: false,
+            post_save_hook: String::new(),
+            check_for_updates: false,
+            release_channel: ReleaseChannel::default(),
+            vault_name: String::new(),
+            vault_password_hash: None,
+            vaults: vec![],
+        }
+    }
+}
+
+impl Config {
+    /// The saved settings, or the defaults if there are none. Settings that
+    /// can't be read are kept rather than silently saved over, see
+    /// [`Unreadable`].
+    pub fn load() -> (Self, Option<Unreadable>) {
+        let path = get_config_file_path();
+
+        // Version 0 had no config file at all, only the save path file.
+        let settings = match fs::read_to_string(&path) {
+            Ok(contents) => match serde_json::from_str(&contents) {
+                Ok(settings) => settings,
+                Err(error) => return (Config::default(), Some(unreadable(&path, error))),
+            },
+            Err(_) if Path::new(LEGACY_SAVE_PATH_FILE).exists() => Map::new(),
+            Err(_) => return (Config::default(), None),
+        };
+
+        let settings = migrate(&path, settings);
+
+        match serde_json::from_value::<Config>(Value::Object(settings)) {
+            Ok(config) => (config.normalized(), None),
+            Err(error) => (Config::default(), Some(unreadable(&path, error))),
+        }
+    }
+
+    /// Brings values the settings page can't produce, say from editing the
+    /// file by hand, back into range.
+    pub fn normalized(mut self) -> Self {
+        self.ui_scale = self.ui_scale.clamp(MIN_UI_SCALE, MAX_UI_SCALE);
+        if !TAB_WIDTHS.contains(&self.tab_width) {
+            self.tab_width = Config::default().tab_width;
+        }
+        self
+    }
+
+    pub fn editor_text_size(&self) -> f32 {
+        if self.large_text {
+            22.0
+        } else {
+            16.0
+        }
+    }
+
+    /// The text a Tab press inserts at `column`. Spaces pad up to the next tab
+    /// stop rather than always inserting a full tab width.
+    pub fn indent_at(&self, column: usize) -> String {
+        if self.indent_with_spaces {
+            let width = usize::from(self.tab_width.max(1));
+            " ".repeat(width - column % width)
+        } else {
+            "\t".to_string()
+        }
+    }
+
+    /// The storage backend of the current vault.
+    pub fn storage_backend(&self) -> Backend {
+        self.storage_backends
+            .get(&self.save_path)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    pub fn set_storage_backend(&mut self, backend: Backend) {
+        if backend == Backend::default() {
+            self.storage_backends.remove(&self.save_path);
+        } else {
+            self.storage_backends
+                .insert(self.save_path.clone(), backend);
+        }
+    }
+
+    pub fn is_pinned(&self, path: &PathBuf) -> bool {
+        self.pinned.contains(path)
+    }
+
+    pub fn add_recent(&mut self, path: PathBuf) {
+        self.recent.retain(|recent| recent != &path);
+        self.recent.insert(0, path);
+        self.recent.truncate(MAX_RECENT);
+    }
+
+    /// The names of the vault profiles, the active one first.
+    pub fn vault_names(&self) -> Vec<String> {
+        std::iter::once(self.vault_name.clone())
+            .chain(self.vaults.iter().map(|vault| vault.name.clone()))
+            .collect()
+    }
+
+    pub fn vault(&self, name: &str) -> Option<&VaultProfile> {
+        self.vaults.iter().find(|vault| vault.name == name)
+    }
+
+    /// Adds an empty vault profile called `name`, unless there's one by
+    /// that name already. The active one is named `default_name` if it has
+    /// no name yet.
+    pub fn add_vault(&mut self, name: &str, default_name: &str) -> bool {
+        let name = name.trim();
+
+        if self.vault_name.is_empty() {
+            self.vault_name = default_name.to_string();
+        }
+
+        if name.is_empty()
+            || self
+                .vault_names()
+                .iter()
+                .any(|existing| existing.eq_ignore_ascii_case(name))
+        {
+            return false;
+        }
+
+        self.vaults.push(VaultProfile {
+            name: name.to_string(),
+            ..VaultProfile::default()
+        });
+
+        true
+    }
+
+    /// Makes the vault profile called `name` the active one.
+    pub fn switch_vault(&mut self, name: &str) -> bool {
+        let Some(index) = self.vaults.iter().position(|vault| vault.name == name) else {
+            return false;
+        };
+
+        let active = self.take_active_vault();
+        let vault = std::mem::replace(&mut self.vaults[index], active);
+        self.set_active_vault(vault);
+
+        true
+    }
+
+    /// Forgets the active vault profile and switches to the next one. The
+    /// documents in its folder are left alone.
+    pub fn remove_vault(&mut self) -> bool {
+        if self.vaults.is_empty() {
+            return false;
+        }
+
+        let vault = self.vaults.remove(0);
+        self.storage_backends.remove(&self.save_path);
+        self.set_active_vault(vault);
+
+        true
+    }
+
+    fn take_active_vault(&mut self) -> VaultProfile {
+        VaultProfile {
+            name: std::mem::take(&mut self.vault_name),
+            save_path: std::mem::take(&mut self.save_path),
+            pinned: std::mem::take(&mut self.pinned),
+            recent: std::mem::take(&mut self.recent),
+            inbox: self.inbox.take(),
+            require_reauth: self.require_reauth,
+            post_save_hook_enabled: self.post_save_hook_enabled,
+            post_save_hook: std::mem::take(&mut self.post_save_hook),
+            password_hash: self.vault_password_hash.take(),
+        }
+    }
+
+    fn set_active_vault(&mut self, vault: VaultProfile) {
+        self.vault_name = vault.name;
+        self.save_path = vault.save_path;
+        self.pinned = vault.pinned;
+        self.recent = vault.recent;
+        self.inbox = vault.inbox;
+        self.require_reauth = vault.require_reauth;
+        self.post_save_hook_enabled = vault.post_save_hook_enabled;
+        self.post_save_hook = vault.post_save_hook;
+        self.vault_password_hash = vault.password_hash;
+    }
+
+    pub fn toggle_pin(&mut self, path: PathBuf) {
+        if let Some(index) = self.pinned.iter().position(|pinned| pinned == &path) {
+            self.pinned.remove(index);
+        } else {
+            self.pinned.push(path);
+        }
+    }
+}
+
+pub fn get_config_file_path() -> PathBuf {
+    PathBuf::from("./config.json")
+}
+
+pub async fn save_config(config: Config) -> Result<PathBuf, FileError> {
+    if KEEP_UNREADABLE.load(Ordering::Relaxed) {
+        return Err(FileError::IOFailed(io::ErrorKind::PermissionDenied));
+    }
+
+    let contents = serde_json::to_string_pretty(&config).expect("Failed to serialize config");
+
+    save_file(Some(get_config_file_path()), contents).await
+}
+
+/// Copies settings that can't be read next to them, as the defaults used
+/// instead are saved over them with the next change.
+fn unreadable(path: &Path, error: serde_json::Error) -> Unreadable {
+    tracing::warn!(%error, "couldn't read settings; using the defaults");
+
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".unreadable");
+    let backup = path.with_file_name(name);
+
+    match fs::copy(path, &backup) {
+        Ok(_) => Unreadable {
+            backup: Some(backup),
+        },
+        Err(error) => {
+            tracing::warn!(%error, "couldn't back up settings; not saving over them");
+            KEEP_UNREADABLE.store(true, Ordering::Relaxed);
+
+            Unreadable { backup: None }
+        }
+    }
+}
+
+/// Brings settings from an older version up to [`CONFIG_VERSION`], backing
+/// up the old file first. Settings from a newer version are left alone;
+/// fields this version doesn't know are dropped when they're next saved.
+fn migrate(path: &Path, mut settings: Map<String, Value>) -> Map<String, Value> {
+    let version = settings.get("version").and_then(Value::as_u64).unwrap_or(0) as u32;
+
+    let Some(steps) = MIGRATIONS
+        .get(version as usize..)
+        .filter(|steps| !steps.is_empty())
+    else {
+        return settings;
+    };
+
+    if path.exists() {
+        if let Err(error) = fs::copy(path, backup_path(path, version)) {
+            tracing::warn!(%error, "couldn't back up settings; not migrating them");
+            return settings;
+        }
+    }
+
+    for (from, migration) in (version..).zip(steps) {
+        if let Err(error) = migration(&mut settings) {
+            tracing::warn!(%error, from, "couldn't migrate settings");
+            return settings;
+        }
+
+        settings.insert("version".into(), (from + 1).into());
+    }
+
+    tracing::info!(from = version, to = CONFIG_VERSION, "migrated settings");
+
+    let contents = serde_json::to_string_pretty(&settings).expect("Failed to serialize config");
+
+    if let Err(error) = fs::write(path, contents) {
+        tracing::warn!(%error, "couldn't save migrated settings");
+    }
+
+    settings
+}
+
+/// Version 0 to 1: the save path moves from its own file into the settings.
+/// The old file is renamed rather than deleted, so downgrading only means
+/// renaming it back.
+fn move_save_path(settings: &mut Map<String, Value>) -> io::Result<()> {
+    let legacy = Path::new(LEGACY_SAVE_PATH_FILE);
+
+    match fs::read_to_string(legacy) {
+        Ok(save_path) => {
+            settings.insert("save_path".into(), save_path.trim_end().into());
+            fs::rename(legacy, backup_path(legacy, 0))
+        }
+        Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(()),
+        Err(error) => Err(error),
+    }
+}
@@ -0,0 +1,198 @@
+use std::io;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use cryptodoc::armor;
+use cryptodoc::file::{load_file, write_file, Contents, FileError};
+use cryptodoc::formats::{
+    self, ArchiveExporter, ArchiveImporter, Exporter, Importer, NamedDocument,
+};
+use cryptodoc::Document;
+
+use crate::gui::i18n::tr;
+use crate::gui::profile;
+
+pub async fn pick_file() -> Result<(PathBuf, Arc<Contents>), FileError> {
+    let handle = rfd::AsyncFileDialog::new()
+        .set_title(tr("dialog.select_file"))
+        .pick_file()
+        .await
+        .ok_or(FileError::DialogClosed)?;
+
+    load_file(handle.path().to_owned()).await
+}
+
+/// Reads an image to embed in the document, with its file name.
+pub async fn pick_image() -> Result<(String, Vec<u8>), FileError> {
+    let handle = rfd::AsyncFileDialog::new()
+        .set_title(tr("dialog.select_image"))
+        .add_filter(
+            tr("dialog.images"),
+            &["png", "jpg", "jpeg", "gif", "webp", "bmp"],
+        )
+        .pick_file()
+        .await
+        .ok_or(FileError::DialogClosed)?;
+
+    let bytes = tokio::fs::read(handle.path())
+        .await
+        .map_err(|error| FileError::IOFailed(error.kind()))?;
+
+    Ok((handle.file_name(), bytes))
+}
+
+pub async fn pick_folder() -> Result<PathBuf, FileError> {
+    let handle = rfd::AsyncFileDialog::new()
+        .set_title(tr("dialog.select_folder"))
+        .pick_folder()
+        .await
+        .ok_or(FileError::DialogClosed)?;
+
+    Ok(handle.path().to_owned())
+}
+
+pub async fn save_file(path: Option<PathBuf>, text: String) -> Result<PathBuf, FileError> {
+    let path = if let Some(path) = path {
+        path
+    } else {
+        rfd::AsyncFileDialog::new()
+            .set_title(tr("dialog.choose_file"))
+            .save_file()
+            .await
+            .ok_or(FileError::DialogClosed)
+            .map(|handle| handle.path().to_owned())?
+    };
+
+    write_file(path, text).await
+}
+
+/// Writes the decrypted document to a file of the user's choosing. This is
+/// the only place plaintext ever reaches the disk.
+pub async fn export_file(
+    name: String,
+    exporter: &'static dyn Exporter,
+    document: Document,
+) -> Result<PathBuf, FileError> {
+    let path = rfd::AsyncFileDialog::new()
+        .set_title(tr("dialog.export_file"))
+        .set_file_name(format!("{}.{}", name, exporter.extension()))
+        .add_filter(exporter.name(), &[exporter.extension()])
+        .save_file()
+        .await
+        .ok_or(FileError::DialogClosed)
+        .map(|handle| handle.path().to_owned())?;
+
+    let contents = formats::export(exporter, &document)
+        .map_err(|_| FileError::IOFailed(io::ErrorKind::InvalidData))?;
+
+    tokio::fs::write(&path, contents)
+        .await
+        .map_err(|error| FileError::IOFailed(error.kind()))?;
+
+    Ok(path)
+}
+
+/// Writes decrypted documents into one archive of the user's choosing. Like
+/// [`export_file`], the plaintext only reaches the disk here.
+pub async fn export_archive(
+    exporter: &'static dyn ArchiveExporter,
+    documents: Vec<NamedDocument>,
+) -> Result<PathBuf, FileError> {
+    let path = rfd::AsyncFileDialog::new()
+        .set_title(tr("dialog.export_archive"))
+        .set_file_name(format!("{}.{}", exporter.id(), exporter.extension()))
+        .add_filter(exporter.name(), &[exporter.extension()])
+        .save_file()
+        .await
+        .ok_or(FileError::DialogClosed)
+        .map(|handle| handle.path().to_owned())?;
+
+    let contents = formats::export_archive(exporter, &documents)
+        .map_err(|_| FileError::IOFailed(io::ErrorKind::InvalidData))?;
+
+    tokio::fs::write(&path, contents)
+        .await
+        .map_err(|error| FileError::IOFailed(error.kind()))?;
+
+    Ok(path)
+}
+
+/// Saves an armored document, which is still encrypted, as a `.asc` file.
+pub async fn export_armored(name: String, armored: String) -> Result<PathBuf, FileError> {
+    let path = rfd::AsyncFileDialog::new()
+        .set_title(tr("dialog.export_armored"))
+        .set_file_name(format!("{}.{}", name, armor::EXTENSION))
+        .add_filter(tr("dialog.armored_filter"), &[armor::EXTENSION])
+        .save_file()
+        .await
+        .ok_or(FileError::DialogClosed)
+        .map(|handle| handle.path().to_owned())?;
+
+    write_file(path, armored).await
+}
+
+pub async fn pick_archive(importer: &'static dyn ArchiveImporter) -> Result<PathBuf, FileError> {
+    let handle = rfd::AsyncFileDialog::new()
+        .set_title(tr("dialog.import_archive"))
+        .add_filter(importer.name(), importer.extensions())
+        .pick_file()
+        .await
+        .ok_or(FileError::DialogClosed)?;
+
+    Ok(handle.path().to_owned())
+}
+
+pub async fn pick_profile_destination() -> Result<PathBuf, FileError> {
+    rfd::AsyncFileDialog::new()
+        .set_title(tr("dialog.export_profile"))
+        .set_file_name(format!("cryptodoc.{}", profile::EXTENSION))
+        .add_filter(tr("dialog.profile_filter"), &[profile::EXTENSION])
+        .save_file()
+        .await
+        .ok_or(FileError::DialogClosed)
+        .map(|handle| handle.path().to_owned())
+}
+
+pub async fn pick_profile() -> Result<PathBuf, FileError> {
+    let handle = rfd::AsyncFileDialog::new()
+        .set_title(tr("dialog.import_profile"))
+        .add_filter(tr("dialog.profile_filter"), &[profile::EXTENSION])
+        .pick_file()
+        .await
+        .ok_or(FileError::DialogClosed)?;
+
+    Ok(handle.path().to_owned())
+}
+
+/// The folder to restore a profile's vault into.
+pub async fn pick_profile_folder() -> Result<PathBuf, FileError> {
+    let handle = rfd::AsyncFileDialog::new()
+        .set_title(tr("dialog.profile_folder"))
+        .pick_folder()
+        .await
+        .ok_or(FileError::DialogClosed)?;
+
+    Ok(handle.path().to_owned())
+}
+
+pub async fn import_file(
+    importer: &'static dyn Importer,
+) -> Result<(PathBuf, Document), FileError> {
+    let path = rfd::AsyncFileDialog::new()
+        .set_title(tr("dialog.import_file"))
+        .add_filter(importer.name(), importer.extensions())
+        .pick_file()
+        .await
+        .ok_or(FileError::DialogClosed)
+        .map(|handle| handle.path().to_owned())?;
+
+    let bytes = tokio::fs::read(&path)
+        .await
+        .map_err(|error| FileError::IOFailed(error.kind()))?;
+
+    let document = importer
+        .import(bytes)
+        .map_err(|_| FileError::IOFailed(io::ErrorKind::InvalidData))?;
+
+    Ok((path, document))
+}
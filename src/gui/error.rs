@@ -0,0 +1,55 @@
+use iced::widget::{button, column, container, horizontal_space, row, text};
+use iced::{Alignment, Element, Length, Theme};
+
+use crate::gui::i18n::tr;
+
+#[derive(Debug, Clone)]
+pub struct ErrorReport {
+    pub context: String,
+    pub details: String,
+}
+
+impl ErrorReport {
+    pub fn new(context: impl Into<String>, details: impl ToString) -> Self {
+        Self {
+            context: context.into(),
+            details: details.to_string(),
+        }
+    }
+}
+
+/// A persistent banner that stays visible until dismissed, unlike toasts.
+pub fn banner<'a, Message>(report: &'a ErrorReport, on_dismiss: Message) -> Element<'a, Message>
+where
+    Message: Clone + 'a,
+{
+    container(
+        row![
+            column![
+                text(report.context.as_str()).size(16),
+                text(report.details.as_str()).size(14)
+            ]
+            .spacing(2),
+            horizontal_space(),
+            button(tr("error.dismiss"))
+                .style(button::secondary)
+                .on_press(on_dismiss),
+        ]
+        .spacing(10)
+        .align_items(Alignment::Center),
+    )
+    .width(Length::Fill)
+    .padding(10)
+    .style(danger)
+    .into()
+}
+
+fn danger(theme: &Theme) -> container::Style {
+    let palette = theme.extended_palette();
+
+    container::Style {
+        background: Some(palette.danger.weak.color.into()),
+        text_color: palette.danger.weak.text.into(),
+        ..Default::default()
+    }
+}
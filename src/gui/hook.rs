@@ -0,0 +1,63 @@
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+
+use tokio::process::Command;
+
+const PATH_VARIABLE: &str = "CRYPTODOC_FILE";
+
+#[derive(Debug, Clone)]
+pub enum HookError {
+    Spawn(io::ErrorKind),
+    Failed(Option<i32>),
+}
+
+impl fmt::Display for HookError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HookError::Spawn(kind) => kind.fmt(f),
+            HookError::Failed(Some(code)) => write!(f, "exit code {}", code),
+            HookError::Failed(None) => write!(f, "terminated"),
+        }
+    }
+}
+
+/// Runs `command` through the shell after a document was saved to `path`.
+/// The path is passed as `$1` on Unix and in `CRYPTODOC_FILE` everywhere,
+/// and only ever points at the encrypted file.
+pub async fn run_post_save(command: String, path: PathBuf) -> Result<(), HookError> {
+    let status = shell(&command, &path)
+        .env(PATH_VARIABLE, &path)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .await
+        .map_err(|error| HookError::Spawn(error.kind()))?;
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(HookError::Failed(status.code()))
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+fn shell(command: &str, path: &Path) -> Command {
+    let mut shell = Command::new("sh");
+    shell.arg("-c").arg(command).arg("cryptodoc").arg(path);
+    shell
+}
+
+#[cfg(target_os = "windows")]
+fn shell(command: &str, _path: &Path) -> Command {
+    const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+    let mut shell = Command::new("cmd");
+    shell
+        .arg("/C")
+        .arg(command)
+        .creation_flags(CREATE_NO_WINDOW);
+    shell
+}
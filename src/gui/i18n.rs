@@ -0,0 +1,1024 @@
+use std::fmt;
+use std::io;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use cryptodoc::file::{FileError, NameError};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Language {
+    #[default]
+    English,
+    German,
+}
+
+impl Language {
+    pub const ALL: &'static [Self] = &[Self::English, Self::German];
+
+    fn strings(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Language::English => ENGLISH,
+            Language::German => GERMAN,
+        }
+    }
+}
+
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Language::English => "English",
+            Language::German => "Deutsch",
+        }
+        .fmt(f)
+    }
+}
+
+static CURRENT: AtomicU8 = AtomicU8::new(Language::English as u8);
+
+pub fn set_language(language: Language) {
+    CURRENT.store(language as u8, Ordering::Relaxed);
+}
+
+pub fn language() -> Language {
+    match CURRENT.load(Ordering::Relaxed) {
+        1 => Language::German,
+        _ => Language::English,
+    }
+}
+
+/// Looks up `key` in the current language, falling back to English and then
+/// to the key itself so a missing translation never blanks out the UI.
+pub fn tr(key: &'static str) -> &'static str {
+    lookup(language().strings(), key)
+        .or_else(|| lookup(ENGLISH, key))
+        .unwrap_or(key)
+}
+
+/// Like [`tr`], with the first `{}` in the translation replaced by `value`.
+pub fn tr_with(key: &'static str, value: impl fmt::Display) -> String {
+    tr(key).replacen("{}", &value.to_string(), 1)
+}
+
+pub fn file_error(error: &FileError) -> String {
+    match error {
+        FileError::DialogClosed => tr("error.dialog_closed").to_string(),
+        FileError::Cancelled => tr("error.cancelled").to_string(),
+        FileError::IOFailed(io::ErrorKind::NotFound) => tr("error.not_found").to_string(),
+        FileError::IOFailed(io::ErrorKind::PermissionDenied) => {
+            tr("error.permission_denied").to_string()
+        }
+        FileError::IOFailed(io::ErrorKind::InvalidData) => tr("error.invalid_data").to_string(),
+        FileError::IOFailed(kind) => tr_with("error.io", kind),
+    }
+}
+
+pub fn name_error(error: &NameError) -> String {
+    match error {
+        NameError::Empty => tr("name.empty").to_string(),
+        NameError::InvalidChar(c) => tr_with("name.invalid_char", c),
+        NameError::Reserved => tr("name.reserved").to_string(),
+        NameError::TrailingDotOrSpace => tr("name.trailing").to_string(),
+    }
+}
+
+fn lookup(strings: &'static [(&'static str, &'static str)], key: &str) -> Option<&'static str> {
+    strings
+        .iter()
+        .find(|(candidate, _)| *candidate == key)
+        .map(|(_, value)| *value)
+}
+
+const ENGLISH: &[(&str, &str)] = &[
+    ("toolbar.home", "Home (Alt+Home)"),
+    ("toolbar.new", "New File (Ctrl+N)"),
+    ("toolbar.open", "Open File (Ctrl+O)"),
+    ("toolbar.save", "Save File (Ctrl+S)"),
+    ("toolbar.settings", "Settings (Ctrl+,)"),
+    ("toast.failed", "Failed"),
+    ("toast.success", "Success"),
+    ("toast.search", "Search"),
+    ("toast.properties", "Properties"),
+    ("toast.encoding", "Encoding"),
+    (
+        "msg.converted_encoding",
+        "This document was stored as {} and will be saved as UTF-8.",
+    ),
+    ("toast.old_format", "Older format"),
+    ("msg.old_format", "This document uses an older file format. It'll be upgraded when you save it, and the old file will be kept as a backup."),
+    ("msg.save_first", "Save the document first."),
+    ("msg.config_unreadable", "Your settings couldn't be read, so the defaults are used. The old settings were kept in {}."),
+    ("msg.config_unreadable_kept", "Your settings couldn't be read or backed up, so the defaults are used and changes aren't saved until you fix or remove config.json."),
+    (
+        "msg.select_folder_first",
+        "Select a save folder in Settings first.",
+    ),
+    ("msg.no_matches", "No matches found."),
+    ("msg.open_first", "Open a document first."),
+    ("msg.document_saved", "Document has been saved."),
+    ("msg.save_path_saved", "Document save path has been saved."),
+    ("error.read_folder", "Couldn't read the save folder"),
+    (
+        "error.read_properties",
+        "Couldn't read the document properties",
+    ),
+    ("error.save_settings", "Couldn't save settings"),
+    ("error.search_folder", "Couldn't search the save folder"),
+    ("error.open_document", "Couldn't open the document"),
+    ("error.open_journal", "Couldn't open the journal"),
+    ("error.select_folder", "Couldn't select the folder"),
+    ("error.save_document", "Couldn't save the document"),
+    ("error.save_path", "Couldn't save the document path"),
+    ("error.dismiss", "Dismiss"),
+    ("error.dialog_closed", "No file was selected."),
+    ("error.cancelled", "The operation was cancelled."),
+    (
+        "error.not_found",
+        "The file or folder doesn't exist. It may have been moved or deleted.",
+    ),
+    (
+        "error.permission_denied",
+        "Permission denied. Check that you have access to this location.",
+    ),
+    (
+        "error.invalid_data",
+        "The file isn't readable text. It may be corrupted or not a CryptoDoc document.",
+    ),
+    ("error.io", "The operation failed: {}."),
+    ("prompt.title", "Enter the password for:"),
+    ("prompt.wrong_password", "Password is incorrect."),
+    (
+        "prompt.invalid_document",
+        "This file isn't a CryptoDoc document.",
+    ),
+    ("prompt.corrupt_document", "This document is damaged and can't be decrypted."),
+    ("prompt.unsupported_version", "This document uses format version {}, which needs a newer version of CryptoDoc."),
+    ("prompt.attempts", "Failed attempts: {}"),
+    ("prompt.locked", "Too many failed attempts. Try again after {}."),
+    ("prompt.cancel", "Cancel"),
+    ("prompt.submit", "Submit"),
+    ("prompt.decrypting", "Decrypting…"),
+    ("recovery.title", "Unsaved changes were recovered"),
+    ("recovery.body", "CryptoDoc closed unexpectedly while {} had unsaved changes. Restore them? You'll need the document's password."),
+    ("recovery.restore", "Restore"),
+    ("recovery.discard", "Discard"),
+    ("progress.search", "Searching documents…"),
+    ("progress.report", "Building the report…"),
+    ("progress.count", "{} documents"),
+    ("progress.cancel", "Cancel"),
+    ("progress.cancelling", "Cancelling…"),
+    ("common.password", "Password"),
+    ("common.caps_lock", "Caps Lock is on"),
+    ("settings.save_dir", "Directory to save documents into:"),
+    ("settings.select_path", "Select Path"),
+    ("settings.current_path", "Current Path:"),
+    ("settings.storage_backend", "Stored in:"),
+    ("settings.theme", "Theme:"),
+    ("settings.language", "Language:"),
+    ("settings.ui_scale", "Interface scale: {}"),
+    ("settings.large_text", "Large editor text"),
+    ("start.placeholder", "Click to get started."),
+    ("start.search", "Search documents (Ctrl+Shift+F)"),
+    ("start.journal", "Journal"),
+    ("journal.title", "Open the journal"),
+    ("journal.body", "Every day gets its own document in the journal folder, all encrypted with this password."),
+    ("journal.open", "Open"),
+    ("journal.today", "Today"),
+    ("calendar.weekdays", "Mo Tu We Th Fr Sa Su"),
+    ("start.browse", "Browse documents (Ctrl+B)"),
+    ("start.paste_armored", "Paste armored document"),
+    ("new.name_title", "Enter the new document name:"),
+    ("new.name_placeholder", "Document Name"),
+    ("new.password_title", "Enter a document password:"),
+    ("new.create", "Create"),
+    ("new.create_table", "Create secrets table"),
+    ("table.label", "Label"),
+    ("table.username", "Username"),
+    ("table.secret", "Secret"),
+    ("table.totp", "2FA seed"),
+    ("table.notes", "Notes"),
+    ("table.add_row", "Add row"),
+    ("table.copy", "Copy"),
+    ("table.reveal", "Show"),
+    ("table.hide", "Hide"),
+    ("totp.title", "Two-factor codes"),
+    ("totp.remaining", "{}s"),
+    ("new.untitled", "Untitled"),
+    ("name.empty", "The name can't be empty."),
+    ("name.invalid_char", "The name can't contain \"{}\"."),
+    (
+        "name.reserved",
+        "This name is reserved by the operating system.",
+    ),
+    ("name.trailing", "The name can't end with a dot or a space."),
+    ("properties.title", "Document Properties"),
+    ("properties.path", "Path"),
+    ("properties.size", "Size on disk"),
+    ("properties.created", "Created"),
+    ("properties.modified", "Modified"),
+    ("properties.version", "Format version"),
+    ("properties.cipher", "Cipher"),
+    ("properties.kdf", "Key derivation"),
+    ("properties.iv_size", "IV size"),
+    ("properties.mac_size", "MAC size"),
+    ("properties.ciphertext_size", "Ciphertext size"),
+    ("properties.format", "Format"),
+    ("properties.unrecognized", "Unrecognized"),
+    ("properties.signature", "Signature"),
+    ("properties.not_signed", "Not signed"),
+    ("properties.bytes", "{} bytes"),
+    ("properties.unknown", "Unknown"),
+    ("properties.back", "Back to document"),
+    ("properties.label", "Label"),
+    ("label.none", "No color"),
+    ("label.emoji", "Emoji"),
+    ("label.clear", "Clear"),
+    ("properties.tags", "Tags"),
+    ("properties.inbox", "Use as the quick capture inbox"),
+    ("tags.add", "Add tag"),
+    ("label.red", "Red"),
+    ("label.orange", "Orange"),
+    ("label.yellow", "Yellow"),
+    ("label.green", "Green"),
+    ("label.blue", "Blue"),
+    ("label.purple", "Purple"),
+    ("error.save_index", "Couldn't save document labels"),
+    ("viewer.current", "Current Document:"),
+    ("viewer.properties", "Properties"),
+    ("viewer.stats", "Statistics"),
+    ("viewer.outline", "Outline"),
+    ("viewer.redact", "Redact"),
+    ("viewer.show_redacted", "Redacted view"),
+    ("viewer.compare", "Compare"),
+    ("compare.title", "Compare with"),
+    ("compare.backup", "Backup from format version {}"),
+    ("compare.close", "Close"),
+    ("compare.loading", "Decrypting the backup…"),
+    ("compare.identical", "The backup is the same as the document."),
+    ("compare.restore", "Restore"),
+    ("compare.backup_side", "Backup"),
+    ("compare.current_side", "Current"),
+    ("merge.title", "Conflicts to resolve: {}"),
+    ("merge.description", "Someone, or a sync service, saved another version of this document while you were editing it. Pick which version to keep for each conflict."),
+    ("merge.save", "Save merged"),
+    ("merge.ours", "Mine"),
+    ("merge.base", "Original"),
+    ("merge.theirs", "Theirs"),
+    ("merge.toast", "Merged"),
+    ("checklist.remaining", "Tasks left: {}"),
+    ("pager.read_only", "Read-only: this document is too large to edit smoothly."),
+    ("pager.page", "Page {}"),
+    ("pager.edit", "Edit anyway"),
+    ("stats.reading_time", "Reading time: {} min"),
+    ("stats.words", "Words: {}"),
+    ("stats.sentences", "Sentences: {}"),
+    ("stats.paragraphs", "Paragraphs: {}"),
+    ("stats.average_word_length", "Average word length: {}"),
+    ("browser.title", "Documents in:"),
+    ("browser.filter", "Filter by name"),
+    ("browser.pin", "Pin"),
+    ("browser.unpin", "Unpin"),
+    ("browser.export_report", "Export report"),
+    ("browser.import_archive", "Import from..."),
+    ("browser.export_archive", "Export to..."),
+    ("report.name", "Name"),
+    ("dialog.save_report", "Save report"),
+    ("msg.report_saved", "The report has been saved."),
+    ("error.save_report", "Couldn't save the report"),
+    ("sort.name", "Name"),
+    ("sort.modified", "Last modified"),
+    ("sort.size", "Size"),
+    ("age.unknown", "unknown"),
+    ("age.just_now", "just now"),
+    ("age.minutes", "{} min ago"),
+    ("age.hours", "{} h ago"),
+    ("age.days", "{} days ago"),
+    ("search.title", "Search all documents in the save folder:"),
+    ("search.placeholder", "Search for..."),
+    ("search.searching", "Searching..."),
+    ("search.search", "Search"),
+    ("search.line", "(line {})"),
+    ("dialog.select_file", "Select file"),
+    ("dialog.select_folder", "Select folder"),
+    ("dialog.select_image", "Select image"),
+    ("dialog.images", "Images"),
+    ("dialog.choose_file", "Choose a file"),
+    ("tray.show", "Show CryptoDoc"),
+    ("tray.lock", "Lock"),
+    ("tray.open_recent", "Open recent..."),
+    ("tray.new_document", "New document"),
+    ("tray.encrypt_clipboard", "Encrypt clipboard..."),
+    ("tray.decrypt_clipboard", "Decrypt clipboard..."),
+    ("tray.quit", "Quit"),
+    ("start.pinned", "Pinned"),
+    ("start.recent", "Recent"),
+    (
+        "settings.minimize_to_tray",
+        "Minimize to tray instead of closing",
+    ),
+    ("settings.quick_capture", "Quick capture: {}"),
+    ("settings.no_inbox", "Quick capture needs an inbox. Choose one in a document's properties."),
+    (
+        "settings.on_screen_keypad",
+        "Show an on-screen keypad when unlocking documents",
+    ),
+    ("prompt.keypad", "Keypad"),
+    ("keypad.shift", "Shift"),
+    ("keypad.backspace", "Backspace"),
+    ("settings.security", "Security"),
+    (
+        "settings.require_reauth",
+        "Ask for the password before exporting or copying plaintext",
+    ),
+    ("viewer.export", "Export as…"),
+    ("viewer.copy_all", "Copy all"),
+    ("viewer.share", "Share..."),
+    ("viewer.armored", "Share as text"),
+    ("armor.copy", "Copy armored"),
+    ("armor.export", "Export .asc"),
+    ("armor.pasted_name", "Pasted document"),
+    ("reauth.title", "Enter the document password to continue"),
+    ("reauth.confirm", "Continue"),
+    ("external_edit.title", "Edit in another program"),
+    ("external_edit.body", "Another program wants to edit {}. Only allow this if you asked for it. Enter the document password to allow it."),
+    ("external_edit.allow", "Allow"),
+    ("external_edit.deny", "Deny"),
+    ("dialog.export_file", "Export document"),
+    ("dialog.export_armored", "Export armored document"),
+    ("dialog.armored_filter", "Armored document"),
+    ("dialog.import_file", "Import a file"),
+    ("dialog.import_archive", "Import documents"),
+    ("archive.title", "Import from {}"),
+    ("archive.body", "Every entry in {} becomes a new document in this folder. Existing documents aren't changed."),
+    ("archive.archive_password", "Password of the file being imported"),
+    ("archive.password", "Password for the new documents"),
+    ("archive.import", "Import"),
+    ("archive.importing", "Importing..."),
+    ("dialog.export_archive", "Export documents"),
+    ("archive_export.title", "Export to {}"),
+    ("archive_export.body", "Every document in this folder is decrypted and written unencrypted into one file. Documents that don't open with this password are left out."),
+    ("archive_export.password", "Password of the documents"),
+    ("archive_export.export", "Export"),
+    ("archive_export.decrypting", "Decrypting..."),
+    ("new.import", "Import from…"),
+    (
+        "msg.imported",
+        "The file has been imported. Set a password to save it.",
+    ),
+    ("error.import", "Couldn't import the file"),
+    ("error.export_archive", "Couldn't export the documents"),
+    ("error.export_profile", "Couldn't export the profile"),
+    ("error.import_profile", "Couldn't import the profile"),
+    ("dialog.export_profile", "Export profile"),
+    ("dialog.import_profile", "Import profile"),
+    ("dialog.profile_filter", "CryptoDoc profile"),
+    ("dialog.profile_folder", "Choose the folder for the documents"),
+    ("settings.profile", "Profile"),
+    ("settings.profile_documents", "Include the documents (they stay encrypted)"),
+    ("settings.export_profile", "Export profile"),
+    ("settings.import_profile", "Import profile"),
+    ("toast.profile", "Profile"),
+    ("msg.profile_exported", "Profile exported to {}"),
+    ("msg.profile_imported", "Profile imported with {} documents."),
+    ("msg.profile_skipped", "{} documents were already in the folder and were left as they are."),
+    ("msg.profile_hooks_removed", "The profile's post-save commands weren't imported. Set them again in Settings if you trust them."),
+    ("error.read_log", "Couldn't read the log"),
+    ("msg.exported", "The document has been exported."),
+    ("msg.image_not_embedded", "Only images up to {} in PNG, JPEG, GIF, WebP or BMP format can be embedded."),
+    ("msg.no_revisions", "There are no earlier versions of this document to compare with."),
+    ("msg.revision_password", "The backup was saved with a different password."),
+    ("msg.merged", "The document was changed on disk and the changes have been merged with yours."),
+    ("msg.merge_failed", "The document was changed on disk, but the other version couldn't be opened to merge: {}"),
+    ("msg.audit_enabled", "The audit log has been turned on."),
+    (
+        "msg.copied",
+        "The document has been copied to the clipboard.",
+    ),
+    ("msg.copied_cell", "The cell has been copied to the clipboard."),
+    ("msg.copied_code", "The code has been copied to the clipboard."),
+    ("msg.copied_share_key", "The key has been copied to the clipboard. Send it separately from the file."),
+    ("share.file_name", "{} (shared)"),
+    ("share.title", "Share bundle created"),
+    ("share.body", "A copy of the document was saved to {}, encrypted with this one-time key instead of your password:"),
+    ("share.warning", "Send the file and the key by different routes, such as email for the file and a call or this QR code for the key. The key is shown only once."),
+    ("share.copy_key", "Copy key"),
+    ("share.done", "Done"),
+    ("msg.copied_armored", "The encrypted document has been copied as text. It can be pasted into an email or chat."),
+    ("msg.no_inbox", "Choose an inbox in a document's properties to use quick capture."),
+    ("capture.title", "Add to {}"),
+    ("capture.placeholder", "Type a note..."),
+    ("capture.save", "Add"),
+    ("msg.clipboard_encrypted", "The clipboard now holds the encrypted text. Send it with the password kept separate."),
+    ("msg.clipboard_decrypted", "The clipboard now holds the decrypted text."),
+    ("clipboard.encrypt_title", "Encrypt the clipboard"),
+    ("clipboard.encrypt_body", "The text in the clipboard is replaced with an encrypted version that can be pasted into an email or chat."),
+    ("clipboard.decrypt_title", "Decrypt the clipboard"),
+    ("clipboard.decrypt_body", "The encrypted text in the clipboard is replaced with what it says."),
+    ("clipboard.encrypt", "Encrypt"),
+    ("clipboard.decrypt", "Decrypt"),
+    ("clipboard.empty", "The clipboard doesn't contain any text."),
+    ("clipboard.not_encrypted", "The clipboard doesn't contain encrypted text."),
+    ("msg.exported_armored", "The encrypted document has been saved to {}."),
+    ("msg.no_armored_document", "The clipboard doesn't contain an armored document."),
+    ("error.export", "Couldn't export the document"),
+    ("error.insert_image", "Couldn't insert the image"),
+    ("error.audit", "Couldn't turn on the audit log"),
+    ("error.armor", "Couldn't encrypt the document as text"),
+    ("error.export_armored", "Couldn't export the armored document"),
+    ("error.share", "Couldn't create the share bundle"),
+    (
+        "settings.lock_on_session",
+        "Lock documents when the screen locks or the computer sleeps",
+    ),
+    ("settings.plaintext_retention", "Keep a closed document in memory for quick re-opening"),
+    ("retention.immediate", "No, wipe it immediately"),
+    ("retention.one_minute", "For 1 minute"),
+    ("retention.five_minutes", "For 5 minutes"),
+    ("retention.fifteen_minutes", "For 15 minutes"),
+    ("settings.vaults", "Vault profiles"),
+    ("vaults.default_name", "Default"),
+    ("vaults.name_placeholder", "Name of a new profile, like Work"),
+    ("vaults.add", "Add profile"),
+    ("vaults.remove", "Remove this profile"),
+    ("vaults.remove_title", "Remove the profile \"{}\"?"),
+    ("vaults.remove_body", "Its settings, recent documents and password are forgotten. The documents in its folder stay where they are."),
+    ("vaults.active", "Active profile: {}"),
+    ("vaults.new_password", "Profile password (optional)"),
+    ("vaults.change_password", "New profile password, or empty to remove it"),
+    ("vaults.set_password", "Set password"),
+    ("vaults.password_hint", "Asked for when switching to this profile, and used for searching its documents. Documents keep their own passwords."),
+    ("vaults.prompt_title", "Password for \"{}\""),
+    ("vaults.switch", "Switch"),
+    ("msg.vault_added", "Added the profile \"{}\"."),
+    ("msg.vault_exists", "A profile needs a name no other profile has."),
+    ("msg.vault_removed", "Removed the profile \"{}\"."),
+    ("msg.vault_switched", "Switched to \"{}\"."),
+    ("msg.save_before_switching", "Save or close the document first."),
+    ("msg.vault_password_set", "The profile now asks for its password."),
+    ("msg.vault_password_removed", "The profile no longer has a password."),
+    ("error.vault_password", "Couldn't set the profile password"),
+    ("settings.editor", "Editor"),
+    ("settings.tab_width", "Tab width"),
+    ("settings.indent_with_spaces", "Indent with spaces"),
+    ("settings.auto_indent", "Keep indentation on new lines"),
+    ("settings.date_format", "Date format"),
+    ("settings.self_test", "Run self-test"),
+    ("settings.self_testing", "Testing..."),
+    ("settings.audit", "Audit log"),
+    ("audit.description", "Keeps an encrypted record of documents being opened or exported, passwords being changed and failed attempts to unlock. Anyone can add to it, but only its own password can read it."),
+    ("audit.new_password", "Password for the log"),
+    ("audit.enable", "Turn on"),
+    ("audit.show", "Show"),
+    ("audit.hide", "Hide"),
+    ("audit.empty", "Nothing has been recorded yet."),
+    ("audit.opened", "Opened {}"),
+    ("audit.failed_unlock", "Failed to unlock {}"),
+    ("audit.password_changed", "Changed the password of {}"),
+    ("audit.exported", "Exported {}"),
+    ("settings.check_for_updates", "Check for updates on startup"),
+    ("settings.release_channel", "Release channel"),
+    ("update.stable", "Stable"),
+    ("update.pre_release", "Pre-release"),
+    ("update.title", "Update available"),
+    ("update.available", "A newer version is available: {}"),
+    ("settings.debug", "Troubleshooting"),
+    ("settings.show_log", "Show log"),
+    ("settings.hide_log", "Hide log"),
+    ("settings.refresh_log", "Refresh"),
+    ("settings.log_empty", "Nothing has been logged yet."),
+    ("settings.bench", "Run benchmark"),
+    ("settings.benchmarking", "Measuring..."),
+    ("settings.bench_key_derivation", "Key derivation:"),
+    ("settings.bench_size", "Size"),
+    ("settings.bench_encrypt", "Encrypt"),
+    ("settings.bench_decrypt", "Decrypt"),
+    ("msg.self_test_passed", "All {} encryption checks passed."),
+    ("msg.self_test_failed", "Encryption is not working correctly on this machine. Don't trust it with documents. Failed: {}"),
+    ("msg.self_test_interrupted", "The self-test stopped unexpectedly."),
+    ("msg.bench_failed", "The benchmark got back different data than it encrypted. Don't trust this build with documents."),
+    (
+        "settings.post_save_hook",
+        "Run a command after saving a document",
+    ),
+    (
+        "settings.post_save_command",
+        "Command, e.g. rsync \"$1\" backup:docs/",
+    ),
+    ("msg.hook_failed", "The post-save command failed: {}"),
+    ("settings.invalid_date_format", "Invalid format"),
+    ("viewer.insert_date", "Insert date (Ctrl+Shift+D)"),
+    ("viewer.insert_image", "Insert image"),
+    ("images.unreadable", "This image can't be shown."),
+    ("links.title", "Links"),
+    ("links.backlinks", "Linked from"),
+    ("msg.link_missing", "There's no document called \"{}\" in the save folder."),
+    (
+        "msg.invalid_date_format",
+        "The date format is invalid. Check it in Settings.",
+    ),
+    ("msg.locked", "The document has been locked."),
+    ("msg.external_edit_saved", "Saved the changes another program made to {}."),
+    ("msg.archive_imported", "Imported {} documents."),
+    ("msg.archive_import_failed", "{} documents couldn't be imported. See the log for details."),
+    ("msg.archive_exported", "The documents have been exported to {}."),
+    ("msg.archive_export_incomplete", "{} documents couldn't be decrypted and were left out of the export. See the log for details."),
+    (
+        "overwrite.title",
+        "A document with this name already exists",
+    ),
+    ("overwrite.body", "Saving would replace {}."),
+    ("overwrite.rename", "Rename"),
+    ("overwrite.overwrite", "Overwrite"),
+];
+
+const GERMAN: &[(&str, &str)] = &[
+    ("toolbar.home", "Start (Alt+Pos1)"),
+    ("toolbar.new", "Neue Datei (Strg+N)"),
+    ("toolbar.open", "Datei öffnen (Strg+O)"),
+    ("toolbar.save", "Datei speichern (Strg+S)"),
+    ("toolbar.settings", "Einstellungen (Strg+,)"),
+    ("toast.failed", "Fehlgeschlagen"),
+    ("toast.success", "Erfolgreich"),
+    ("toast.search", "Suche"),
+    ("toast.properties", "Eigenschaften"),
+    ("toast.encoding", "Zeichenkodierung"),
+    (
+        "msg.converted_encoding",
+        "Dieses Dokument war als {} gespeichert und wird als UTF-8 gespeichert.",
+    ),
+    ("toast.old_format", "Älteres Format"),
+    ("msg.old_format", "Dieses Dokument verwendet ein älteres Dateiformat. Es wird beim Speichern aktualisiert, die alte Datei bleibt als Sicherung erhalten."),
+    ("msg.save_first", "Speichere das Dokument zuerst."),
+    ("msg.config_unreadable", "Deine Einstellungen konnten nicht gelesen werden, daher gelten die Standardwerte. Die alten Einstellungen liegen in {}."),
+    ("msg.config_unreadable_kept", "Deine Einstellungen konnten weder gelesen noch gesichert werden. Es gelten die Standardwerte, und Änderungen werden nicht gespeichert, bis du config.json reparierst oder entfernst."),
+    (
+        "msg.select_folder_first",
+        "Wähle zuerst in den Einstellungen einen Speicherordner.",
+    ),
+    ("msg.no_matches", "Keine Treffer gefunden."),
+    ("msg.open_first", "Öffne zuerst ein Dokument."),
+    ("msg.document_saved", "Das Dokument wurde gespeichert."),
+    ("msg.save_path_saved", "Der Speicherpfad wurde gespeichert."),
+    (
+        "error.read_folder",
+        "Der Speicherordner konnte nicht gelesen werden",
+    ),
+    (
+        "error.read_properties",
+        "Die Dokumenteigenschaften konnten nicht gelesen werden",
+    ),
+    (
+        "error.save_settings",
+        "Die Einstellungen konnten nicht gespeichert werden",
+    ),
+    (
+        "error.search_folder",
+        "Der Speicherordner konnte nicht durchsucht werden",
+    ),
+    (
+        "error.open_document",
+        "Das Dokument konnte nicht geöffnet werden",
+    ),
+    ("error.open_journal", "Das Tagebuch konnte nicht geöffnet werden"),
+    (
+        "error.select_folder",
+        "Der Ordner konnte nicht ausgewählt werden",
+    ),
+    (
+        "error.save_document",
+        "Das Dokument konnte nicht gespeichert werden",
+    ),
+    (
+        "error.save_path",
+        "Der Speicherpfad konnte nicht gespeichert werden",
+    ),
+    ("error.dismiss", "Schließen"),
+    ("error.dialog_closed", "Es wurde keine Datei ausgewählt."),
+    ("error.cancelled", "Der Vorgang wurde abgebrochen."),
+    (
+        "error.not_found",
+        "Die Datei oder der Ordner existiert nicht. Vielleicht wurde sie verschoben oder gelöscht.",
+    ),
+    (
+        "error.permission_denied",
+        "Zugriff verweigert. Prüfe, ob du Zugriff auf diesen Ort hast.",
+    ),
+    (
+        "error.invalid_data",
+        "Die Datei ist kein lesbarer Text. Sie ist beschädigt oder kein CryptoDoc-Dokument.",
+    ),
+    ("error.io", "Der Vorgang ist fehlgeschlagen: {}."),
+    ("prompt.title", "Gib das Passwort ein für:"),
+    ("prompt.wrong_password", "Das Passwort ist falsch."),
+    (
+        "prompt.invalid_document",
+        "Diese Datei ist kein CryptoDoc-Dokument.",
+    ),
+    ("prompt.corrupt_document", "Dieses Dokument ist beschädigt und kann nicht entschlüsselt werden."),
+    ("prompt.unsupported_version", "Dieses Dokument verwendet Formatversion {}, die eine neuere Version von CryptoDoc erfordert."),
+    ("prompt.attempts", "Fehlgeschlagene Versuche: {}"),
+    ("prompt.locked", "Zu viele fehlgeschlagene Versuche. Versuche es nach {} erneut."),
+    ("prompt.cancel", "Abbrechen"),
+    ("prompt.submit", "Bestätigen"),
+    ("prompt.decrypting", "Entschlüsseln…"),
+    ("recovery.title", "Ungespeicherte Änderungen wurden wiederhergestellt"),
+    ("recovery.body", "CryptoDoc wurde unerwartet beendet, während {} ungespeicherte Änderungen hatte. Wiederherstellen? Du benötigst das Passwort des Dokuments."),
+    ("recovery.restore", "Wiederherstellen"),
+    ("recovery.discard", "Verwerfen"),
+    ("progress.search", "Dokumente werden durchsucht…"),
+    ("progress.report", "Bericht wird erstellt…"),
+    ("progress.count", "{} Dokumente"),
+    ("progress.cancel", "Abbrechen"),
+    ("progress.cancelling", "Wird abgebrochen…"),
+    ("common.password", "Passwort"),
+    ("common.caps_lock", "Feststelltaste ist aktiv"),
+    (
+        "settings.save_dir",
+        "Ordner, in dem Dokumente gespeichert werden:",
+    ),
+    ("settings.select_path", "Pfad auswählen"),
+    ("settings.current_path", "Aktueller Pfad:"),
+    ("settings.storage_backend", "Gespeichert in:"),
+    ("settings.theme", "Design:"),
+    ("settings.language", "Sprache:"),
+    ("settings.ui_scale", "Skalierung der Oberfläche: {}"),
+    ("settings.large_text", "Große Schrift im Editor"),
+    ("start.placeholder", "Klicke, um loszulegen."),
+    ("start.search", "Dokumente durchsuchen (Strg+Umschalt+F)"),
+    ("start.journal", "Tagebuch"),
+    ("journal.title", "Tagebuch öffnen"),
+    ("journal.body", "Jeder Tag bekommt ein eigenes Dokument im Tagebuch-Ordner, alle mit diesem Passwort verschlüsselt."),
+    ("journal.open", "Öffnen"),
+    ("journal.today", "Heute"),
+    ("calendar.weekdays", "Mo Di Mi Do Fr Sa So"),
+    ("start.browse", "Dokumente anzeigen (Strg+B)"),
+    ("start.paste_armored", "Geschützten Text einfügen"),
+    ("new.name_title", "Gib den Namen des neuen Dokuments ein:"),
+    ("new.name_placeholder", "Dokumentname"),
+    (
+        "new.password_title",
+        "Gib ein Passwort für das Dokument ein:",
+    ),
+    ("new.create", "Erstellen"),
+    ("new.create_table", "Geheimnistabelle erstellen"),
+    ("table.label", "Bezeichnung"),
+    ("table.username", "Benutzername"),
+    ("table.secret", "Geheimnis"),
+    ("table.totp", "2FA-Schlüssel"),
+    ("table.notes", "Notizen"),
+    ("table.add_row", "Zeile hinzufügen"),
+    ("table.copy", "Kopieren"),
+    ("table.reveal", "Anzeigen"),
+    ("table.hide", "Verbergen"),
+    ("totp.title", "Zwei-Faktor-Codes"),
+    ("totp.remaining", "{} s"),
+    ("new.untitled", "Unbenannt"),
+    ("name.empty", "Der Name darf nicht leer sein."),
+    ("name.invalid_char", "Der Name darf kein \"{}\" enthalten."),
+    (
+        "name.reserved",
+        "Dieser Name ist vom Betriebssystem reserviert.",
+    ),
+    (
+        "name.trailing",
+        "Der Name darf nicht mit einem Punkt oder Leerzeichen enden.",
+    ),
+    ("properties.title", "Dokumenteigenschaften"),
+    ("properties.path", "Pfad"),
+    ("properties.size", "Größe auf dem Datenträger"),
+    ("properties.created", "Erstellt"),
+    ("properties.modified", "Geändert"),
+    ("properties.version", "Formatversion"),
+    ("properties.cipher", "Verschlüsselung"),
+    ("properties.kdf", "Schlüsselableitung"),
+    ("properties.iv_size", "IV-Größe"),
+    ("properties.mac_size", "MAC-Größe"),
+    ("properties.ciphertext_size", "Größe des Geheimtexts"),
+    ("properties.format", "Format"),
+    ("properties.unrecognized", "Unbekannt"),
+    ("properties.signature", "Signatur"),
+    ("properties.not_signed", "Nicht signiert"),
+    ("properties.bytes", "{} Bytes"),
+    ("properties.unknown", "Unbekannt"),
+    ("properties.back", "Zurück zum Dokument"),
+    ("properties.label", "Markierung"),
+    ("label.none", "Keine Farbe"),
+    ("label.emoji", "Emoji"),
+    ("label.clear", "Entfernen"),
+    ("properties.tags", "Schlagwörter"),
+    ("properties.inbox", "Als Eingang für Schnellnotizen verwenden"),
+    ("tags.add", "Schlagwort hinzufügen"),
+    ("label.red", "Rot"),
+    ("label.orange", "Orange"),
+    ("label.yellow", "Gelb"),
+    ("label.green", "Grün"),
+    ("label.blue", "Blau"),
+    ("label.purple", "Lila"),
+    (
+        "error.save_index",
+        "Markierungen konnten nicht gespeichert werden",
+    ),
+    ("viewer.current", "Aktuelles Dokument:"),
+    ("viewer.properties", "Eigenschaften"),
+    ("viewer.stats", "Statistik"),
+    ("viewer.outline", "Gliederung"),
+    ("viewer.redact", "Schwärzen"),
+    ("viewer.show_redacted", "Geschwärzte Ansicht"),
+    ("viewer.compare", "Vergleichen"),
+    ("compare.title", "Vergleichen mit"),
+    ("compare.backup", "Sicherung aus Formatversion {}"),
+    ("compare.close", "Schließen"),
+    ("compare.loading", "Sicherung wird entschlüsselt…"),
+    ("compare.identical", "Die Sicherung stimmt mit dem Dokument überein."),
+    ("compare.restore", "Wiederherstellen"),
+    ("compare.backup_side", "Sicherung"),
+    ("compare.current_side", "Aktuell"),
+    ("merge.title", "Zu lösende Konflikte: {}"),
+    ("merge.description", "Jemand, oder ein Synchronisierungsdienst, hat eine andere Version dieses Dokuments gespeichert, während du es bearbeitet hast. Wähle für jeden Konflikt aus, welche Version bleiben soll."),
+    ("merge.save", "Zusammengeführt speichern"),
+    ("merge.ours", "Meine"),
+    ("merge.base", "Original"),
+    ("merge.theirs", "Die andere"),
+    ("merge.toast", "Zusammengeführt"),
+    ("checklist.remaining", "Offene Aufgaben: {}"),
+    ("pager.read_only", "Schreibgeschützt: Dieses Dokument ist zu groß, um es flüssig zu bearbeiten."),
+    ("pager.page", "Seite {}"),
+    ("pager.edit", "Trotzdem bearbeiten"),
+    ("stats.reading_time", "Lesezeit: {} Min."),
+    ("stats.words", "Wörter: {}"),
+    ("stats.sentences", "Sätze: {}"),
+    ("stats.paragraphs", "Absätze: {}"),
+    (
+        "stats.average_word_length",
+        "Durchschnittliche Wortlänge: {}",
+    ),
+    ("browser.title", "Dokumente in:"),
+    ("browser.filter", "Nach Name filtern"),
+    ("browser.pin", "Anheften"),
+    ("browser.unpin", "Lösen"),
+    ("browser.export_report", "Bericht exportieren"),
+    ("browser.import_archive", "Importieren aus..."),
+    ("browser.export_archive", "Exportieren nach..."),
+    ("report.name", "Name"),
+    ("dialog.save_report", "Bericht speichern"),
+    ("msg.report_saved", "Der Bericht wurde gespeichert."),
+    (
+        "error.save_report",
+        "Der Bericht konnte nicht gespeichert werden",
+    ),
+    ("sort.name", "Name"),
+    ("sort.modified", "Zuletzt geändert"),
+    ("sort.size", "Größe"),
+    ("age.unknown", "unbekannt"),
+    ("age.just_now", "gerade eben"),
+    ("age.minutes", "vor {} Min."),
+    ("age.hours", "vor {} Std."),
+    ("age.days", "vor {} Tagen"),
+    (
+        "search.title",
+        "Alle Dokumente im Speicherordner durchsuchen:",
+    ),
+    ("search.placeholder", "Suchen nach..."),
+    ("search.searching", "Suche läuft..."),
+    ("search.search", "Suchen"),
+    ("search.line", "(Zeile {})"),
+    ("dialog.select_file", "Datei auswählen"),
+    ("dialog.select_folder", "Ordner auswählen"),
+    ("dialog.select_image", "Bild auswählen"),
+    ("dialog.images", "Bilder"),
+    ("dialog.choose_file", "Datei wählen"),
+    ("tray.show", "CryptoDoc anzeigen"),
+    ("tray.lock", "Sperren"),
+    ("tray.open_recent", "Zuletzt geöffnet..."),
+    ("tray.new_document", "Neues Dokument"),
+    ("tray.encrypt_clipboard", "Zwischenablage verschlüsseln..."),
+    ("tray.decrypt_clipboard", "Zwischenablage entschlüsseln..."),
+    ("tray.quit", "Beenden"),
+    ("start.pinned", "Angeheftet"),
+    ("start.recent", "Zuletzt geöffnet"),
+    (
+        "settings.minimize_to_tray",
+        "In den Infobereich minimieren statt zu schließen",
+    ),
+    ("settings.quick_capture", "Schnellnotiz: {}"),
+    ("settings.no_inbox", "Für Schnellnotizen wird ein Eingang benötigt. Er kann in den Eigenschaften eines Dokuments gewählt werden."),
+    (
+        "settings.on_screen_keypad",
+        "Beim Entsperren eine Bildschirmtastatur anzeigen",
+    ),
+    ("prompt.keypad", "Tastatur"),
+    ("keypad.shift", "Umschalt"),
+    ("keypad.backspace", "Rücktaste"),
+    ("settings.security", "Sicherheit"),
+    (
+        "settings.require_reauth",
+        "Vor dem Exportieren oder Kopieren von Klartext nach dem Passwort fragen",
+    ),
+    ("viewer.export", "Exportieren als…"),
+    ("viewer.copy_all", "Alles kopieren"),
+    ("viewer.share", "Teilen..."),
+    ("viewer.armored", "Als Text teilen"),
+    ("armor.copy", "Geschützt kopieren"),
+    ("armor.export", "Als .asc exportieren"),
+    ("armor.pasted_name", "Eingefügtes Dokument"),
+    ("reauth.title", "Dokumentpasswort eingeben, um fortzufahren"),
+    ("reauth.confirm", "Weiter"),
+    ("external_edit.title", "In einem anderen Programm bearbeiten"),
+    ("external_edit.body", "Ein anderes Programm möchte {} bearbeiten. Erlaube dies nur, wenn du es angefordert hast. Gib das Dokumentpasswort ein, um es zu erlauben."),
+    ("external_edit.allow", "Erlauben"),
+    ("external_edit.deny", "Ablehnen"),
+    ("dialog.export_file", "Dokument exportieren"),
+    ("dialog.export_armored", "Geschütztes Dokument exportieren"),
+    ("dialog.armored_filter", "Geschütztes Dokument"),
+    ("dialog.import_file", "Datei importieren"),
+    ("dialog.import_archive", "Dokumente importieren"),
+    ("archive.title", "Aus {} importieren"),
+    ("archive.body", "Jeder Eintrag in {} wird zu einem neuen Dokument in diesem Ordner. Vorhandene Dokumente bleiben unverändert."),
+    ("archive.archive_password", "Passwort der zu importierenden Datei"),
+    ("archive.password", "Passwort für die neuen Dokumente"),
+    ("archive.import", "Importieren"),
+    ("archive.importing", "Wird importiert..."),
+    ("dialog.export_archive", "Dokumente exportieren"),
+    ("archive_export.title", "Nach {} exportieren"),
+    ("archive_export.body", "Jedes Dokument in diesem Ordner wird entschlüsselt und unverschlüsselt in eine Datei geschrieben. Dokumente, die sich mit diesem Passwort nicht öffnen lassen, werden ausgelassen."),
+    ("archive_export.password", "Passwort der Dokumente"),
+    ("archive_export.export", "Exportieren"),
+    ("archive_export.decrypting", "Wird entschlüsselt..."),
+    ("new.import", "Importieren aus…"),
+    (
+        "msg.imported",
+        "Die Datei wurde importiert. Lege ein Passwort fest, um sie zu speichern.",
+    ),
+    ("error.import", "Die Datei konnte nicht importiert werden"),
+    ("error.export_archive", "Die Dokumente konnten nicht exportiert werden"),
+    ("error.export_profile", "Das Profil konnte nicht exportiert werden"),
+    ("error.import_profile", "Das Profil konnte nicht importiert werden"),
+    ("dialog.export_profile", "Profil exportieren"),
+    ("dialog.import_profile", "Profil importieren"),
+    ("dialog.profile_filter", "CryptoDoc-Profil"),
+    ("dialog.profile_folder", "Wähle den Ordner für die Dokumente"),
+    ("settings.profile", "Profil"),
+    ("settings.profile_documents", "Dokumente einschließen (sie bleiben verschlüsselt)"),
+    ("settings.export_profile", "Profil exportieren"),
+    ("settings.import_profile", "Profil importieren"),
+    ("toast.profile", "Profil"),
+    ("msg.profile_exported", "Profil exportiert nach {}"),
+    ("msg.profile_imported", "Profil mit {} Dokumenten importiert."),
+    ("msg.profile_skipped", "{} Dokumente waren schon im Ordner und wurden nicht verändert."),
+    ("msg.profile_hooks_removed", "Die Befehle nach dem Speichern aus dem Profil wurden nicht importiert. Lege sie in den Einstellungen neu fest, wenn du ihnen vertraust."),
+    ("error.read_log", "Das Protokoll konnte nicht gelesen werden"),
+    ("msg.exported", "Das Dokument wurde exportiert."),
+    ("msg.image_not_embedded", "Es können nur Bilder bis {} im Format PNG, JPEG, GIF, WebP oder BMP eingebettet werden."),
+    ("msg.no_revisions", "Es gibt keine früheren Versionen dieses Dokuments zum Vergleichen."),
+    ("msg.revision_password", "Die Sicherung wurde mit einem anderen Passwort gespeichert."),
+    ("msg.merged", "Das Dokument wurde auf der Festplatte geändert und die Änderungen wurden mit deinen zusammengeführt."),
+    ("msg.merge_failed", "Das Dokument wurde auf der Festplatte geändert, aber die andere Version konnte nicht zum Zusammenführen geöffnet werden: {}"),
+    ("msg.audit_enabled", "Das Prüfprotokoll wurde eingeschaltet."),
+    (
+        "msg.copied",
+        "Das Dokument wurde in die Zwischenablage kopiert.",
+    ),
+    ("msg.copied_cell", "Die Zelle wurde in die Zwischenablage kopiert."),
+    ("msg.copied_code", "Der Code wurde in die Zwischenablage kopiert."),
+    ("msg.copied_share_key", "Der Schlüssel wurde in die Zwischenablage kopiert. Er sollte getrennt von der Datei übermittelt werden."),
+    ("share.file_name", "{} (geteilt)"),
+    ("share.title", "Freigabepaket erstellt"),
+    ("share.body", "Eine Kopie des Dokuments wurde unter {} gespeichert, mit diesem Einmalschlüssel statt deines Passworts verschlüsselt:"),
+    ("share.warning", "Datei und Schlüssel sollten auf verschiedenen Wegen übermittelt werden, etwa die Datei per E-Mail und der Schlüssel per Anruf oder über diesen QR-Code. Der Schlüssel wird nur einmal angezeigt."),
+    ("share.copy_key", "Schlüssel kopieren"),
+    ("share.done", "Fertig"),
+    ("msg.copied_armored", "Das verschlüsselte Dokument wurde als Text kopiert. Es kann in eine E-Mail oder einen Chat eingefügt werden."),
+    ("msg.no_inbox", "Für Schnellnotizen muss in den Eigenschaften eines Dokuments ein Eingang gewählt werden."),
+    ("capture.title", "Zu {} hinzufügen"),
+    ("capture.placeholder", "Notiz eingeben..."),
+    ("capture.save", "Hinzufügen"),
+    ("msg.clipboard_encrypted", "Die Zwischenablage enthält jetzt den verschlüsselten Text. Das Passwort sollte getrennt davon übermittelt werden."),
+    ("msg.clipboard_decrypted", "Die Zwischenablage enthält jetzt den entschlüsselten Text."),
+    ("clipboard.encrypt_title", "Zwischenablage verschlüsseln"),
+    ("clipboard.encrypt_body", "Der Text in der Zwischenablage wird durch eine verschlüsselte Fassung ersetzt, die in eine E-Mail oder einen Chat eingefügt werden kann."),
+    ("clipboard.decrypt_title", "Zwischenablage entschlüsseln"),
+    ("clipboard.decrypt_body", "Der verschlüsselte Text in der Zwischenablage wird durch seinen Inhalt ersetzt."),
+    ("clipboard.encrypt", "Verschlüsseln"),
+    ("clipboard.decrypt", "Entschlüsseln"),
+    ("clipboard.empty", "Die Zwischenablage enthält keinen Text."),
+    ("clipboard.not_encrypted", "Die Zwischenablage enthält keinen verschlüsselten Text."),
+    ("msg.exported_armored", "Das verschlüsselte Dokument wurde unter {} gespeichert."),
+    ("msg.no_armored_document", "Die Zwischenablage enthält kein geschütztes Dokument."),
+    (
+        "error.export",
+        "Das Dokument konnte nicht exportiert werden",
+    ),
+    ("error.insert_image", "Das Bild konnte nicht eingefügt werden"),
+    ("error.audit", "Das Prüfprotokoll konnte nicht eingeschaltet werden"),
+    ("error.armor", "Das Dokument konnte nicht als Text verschlüsselt werden"),
+    ("error.export_armored", "Das geschützte Dokument konnte nicht exportiert werden"),
+    ("error.share", "Das Freigabepaket konnte nicht erstellt werden"),
+    (
+        "settings.lock_on_session",
+        "Dokumente sperren, wenn der Bildschirm gesperrt wird oder der Computer schläft",
+    ),
+    ("settings.plaintext_retention", "Geschlossenes Dokument für schnelles Öffnen im Speicher behalten"),
+    ("retention.immediate", "Nein, sofort löschen"),
+    ("retention.one_minute", "1 Minute lang"),
+    ("retention.five_minutes", "5 Minuten lang"),
+    ("retention.fifteen_minutes", "15 Minuten lang"),
+    ("settings.vaults", "Tresorprofile"),
+    ("vaults.default_name", "Standard"),
+    ("vaults.name_placeholder", "Name eines neuen Profils, z. B. Arbeit"),
+    ("vaults.add", "Profil hinzufügen"),
+    ("vaults.remove", "Dieses Profil entfernen"),
+    ("vaults.remove_title", "Profil „{}“ entfernen?"),
+    ("vaults.remove_body", "Seine Einstellungen, zuletzt geöffneten Dokumente und sein Passwort werden vergessen. Die Dokumente in seinem Ordner bleiben, wo sie sind."),
+    ("vaults.active", "Aktives Profil: {}"),
+    ("vaults.new_password", "Profilpasswort (optional)"),
+    ("vaults.change_password", "Neues Profilpasswort, leer zum Entfernen"),
+    ("vaults.set_password", "Passwort festlegen"),
+    ("vaults.password_hint", "Wird beim Wechsel zu diesem Profil abgefragt und zum Durchsuchen seiner Dokumente verwendet. Dokumente behalten ihre eigenen Passwörter."),
+    ("vaults.prompt_title", "Passwort für „{}“"),
+    ("vaults.switch", "Wechseln"),
+    ("msg.vault_added", "Profil „{}“ hinzugefügt."),
+    ("msg.vault_exists", "Ein Profil braucht einen Namen, den kein anderes Profil hat."),
+    ("msg.vault_removed", "Profil „{}“ entfernt."),
+    ("msg.vault_switched", "Zu „{}“ gewechselt."),
+    ("msg.save_before_switching", "Speichere oder schließe zuerst das Dokument."),
+    ("msg.vault_password_set", "Das Profil fragt jetzt nach seinem Passwort."),
+    ("msg.vault_password_removed", "Das Profil hat kein Passwort mehr."),
+    ("error.vault_password", "Profilpasswort konnte nicht festgelegt werden"),
+    ("settings.editor", "Editor"),
+    ("settings.tab_width", "Tabulatorbreite"),
+    ("settings.indent_with_spaces", "Mit Leerzeichen einrücken"),
+    (
+        "settings.auto_indent",
+        "Einrückung in neuen Zeilen beibehalten",
+    ),
+    ("settings.date_format", "Datumsformat"),
+    ("settings.self_test", "Selbsttest ausführen"),
+    ("settings.self_testing", "Wird getestet..."),
+    ("settings.audit", "Prüfprotokoll"),
+    ("audit.description", "Führt ein verschlüsseltes Protokoll darüber, wann Dokumente geöffnet oder exportiert, Passwörter geändert und Entsperrversuche fehlgeschlagen sind. Hinzugefügt werden kann ohne Passwort, lesen kannst du es nur mit seinem eigenen."),
+    ("audit.new_password", "Passwort für das Protokoll"),
+    ("audit.enable", "Einschalten"),
+    ("audit.show", "Anzeigen"),
+    ("audit.hide", "Ausblenden"),
+    ("audit.empty", "Es wurde noch nichts aufgezeichnet."),
+    ("audit.opened", "{} geöffnet"),
+    ("audit.failed_unlock", "Entsperren von {} fehlgeschlagen"),
+    ("audit.password_changed", "Passwort von {} geändert"),
+    ("audit.exported", "{} exportiert"),
+    ("settings.check_for_updates", "Beim Start nach Updates suchen"),
+    ("settings.release_channel", "Update-Kanal"),
+    ("update.stable", "Stabil"),
+    ("update.pre_release", "Vorabversion"),
+    ("update.title", "Update verfügbar"),
+    ("update.available", "Eine neuere Version ist verfügbar: {}"),
+    ("settings.debug", "Fehlerbehebung"),
+    ("settings.show_log", "Protokoll anzeigen"),
+    ("settings.hide_log", "Protokoll ausblenden"),
+    ("settings.refresh_log", "Aktualisieren"),
+    ("settings.log_empty", "Es wurde noch nichts protokolliert."),
+    ("settings.bench", "Leistungstest ausführen"),
+    ("settings.benchmarking", "Wird gemessen..."),
+    ("settings.bench_key_derivation", "Schlüsselableitung:"),
+    ("settings.bench_size", "Größe"),
+    ("settings.bench_encrypt", "Verschlüsseln"),
+    ("settings.bench_decrypt", "Entschlüsseln"),
+    ("msg.self_test_passed", "Alle {} Verschlüsselungsprüfungen waren erfolgreich."),
+    ("msg.self_test_failed", "Die Verschlüsselung funktioniert auf diesem Gerät nicht korrekt. Vertraue ihr keine Dokumente an. Fehlgeschlagen: {}"),
+    ("msg.self_test_interrupted", "Der Selbsttest wurde unerwartet abgebrochen."),
+    ("msg.bench_failed", "Der Leistungstest hat andere Daten zurückerhalten, als er verschlüsselt hat. Vertraue diesem Build keine Dokumente an."),
+    (
+        "settings.post_save_hook",
+        "Nach dem Speichern eines Dokuments einen Befehl ausführen",
+    ),
+    (
+        "settings.post_save_command",
+        "Befehl, z. B. rsync \"$1\" backup:docs/",
+    ),
+    (
+        "msg.hook_failed",
+        "Der Befehl nach dem Speichern ist fehlgeschlagen: {}",
+    ),
+    ("settings.invalid_date_format", "Ungültiges Format"),
+    ("viewer.insert_date", "Datum einfügen (Strg+Umschalt+D)"),
+    ("viewer.insert_image", "Bild einfügen"),
+    ("images.unreadable", "Dieses Bild kann nicht angezeigt werden."),
+    ("links.title", "Links"),
+    ("links.backlinks", "Verlinkt von"),
+    ("msg.link_missing", "Im Speicherordner gibt es kein Dokument namens „{}“."),
+    (
+        "msg.invalid_date_format",
+        "Das Datumsformat ist ungültig. Prüfe es in den Einstellungen.",
+    ),
+    ("msg.locked", "Das Dokument wurde gesperrt."),
+    ("msg.external_edit_saved", "Die Änderungen eines anderen Programms an {} wurden gespeichert."),
+    ("msg.archive_imported", "{} Dokumente wurden importiert."),
+    ("msg.archive_import_failed", "{} Dokumente konnten nicht importiert werden. Details stehen im Protokoll."),
+    ("msg.archive_exported", "Die Dokumente wurden nach {} exportiert."),
+    ("msg.archive_export_incomplete", "{} Dokumente konnten nicht entschlüsselt werden und fehlen im Export. Details stehen im Protokoll."),
+    (
+        "overwrite.title",
+        "Ein Dokument mit diesem Namen existiert bereits",
+    ),
+    ("overwrite.body", "Beim Speichern wird {} ersetzt."),
+    ("overwrite.rename", "Umbenennen"),
+    ("overwrite.overwrite", "Überschreiben"),
+];
@@ -3,7 +3,7 @@ use iced::{
     Element, Font, Length,
 };
 
-use crate::Message;
+use crate::gui::Message;
 
 fn icon<'a, Message>(codepoint: char) -> Element<'a, Message> {
     const ICON_FONT: Font = Font::with_name("editor-icons");
@@ -0,0 +1,117 @@
+//! Images embedded in a document as Markdown images with a data URI, like
+//! `![diagram](data:image/png;base64,...)`, so they're encrypted along with
+//! the text rather than left next to it as files. They're shown next to the
+//! editor.
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use iced::widget::image::Handle;
+use iced::widget::{column, container, image, scrollable, text};
+use iced::{Element, Length};
+
+use crate::gui::i18n::tr;
+use crate::gui::Message;
+
+/// Larger images would make the document, which is one line of Base64 per
+/// image, slow to edit.
+pub const MAX_IMAGE_SIZE: usize = 2 * 1024 * 1024;
+
+const DATA_PREFIX: &str = "data:image/";
+const BASE64_MARKER: &str = ";base64,";
+
+pub struct EmbeddedImage {
+    pub alt: String,
+    /// The Base64 data, kept to tell whether the image changed.
+    data: String,
+    /// `None` if the data isn't an image that can be decoded.
+    handle: Option<Handle>,
+}
+
+/// The images embedded in `text`. Images already decoded in `previous` are
+/// reused, as the document is searched again on every edit.
+pub fn find_images(text: &str, previous: &mut Vec<EmbeddedImage>) -> Vec<EmbeddedImage> {
+    let mut images = vec![];
+    let mut rest = text;
+
+    while let Some(start) = rest.find("![") {
+        rest = &rest[start + 2..];
+
+        let Some((alt, after)) = rest.split_once("](") else {
+            break;
+        };
+
+        let Some((url, after)) = after.split_once(')') else {
+            break;
+        };
+
+        let data = url
+            .strip_prefix(DATA_PREFIX)
+            .and_then(|url| url.split_once(BASE64_MARKER))
+            .map(|(_, data)| data);
+
+        if let Some(data) = data.filter(|_| !alt.contains('\n')) {
+            let handle = match previous.iter().position(|image| image.data == data) {
+                Some(index) => previous.swap_remove(index).handle,
+                None => decode(data),
+            };
+
+            images.push(EmbeddedImage {
+                alt: alt.to_string(),
+                data: data.to_string(),
+                handle,
+            });
+
+            rest = after;
+        }
+    }
+
+    images
+}
+
+/// The Markdown embedding `bytes`, or `None` if they aren't a known image
+/// format.
+pub fn markdown(name: &str, bytes: &[u8]) -> Option<String> {
+    let format = ::image::guess_format(bytes).ok()?;
+
+    Some(format!(
+        "![{}](data:{}{}{})",
+        name.replace(['[', ']'], ""),
+        format.to_mime_type(),
+        BASE64_MARKER,
+        STANDARD.encode(bytes)
+    ))
+}
+
+fn decode(data: &str) -> Option<Handle> {
+    let bytes = STANDARD.decode(data.trim()).ok()?;
+    let image = ::image::load_from_memory(&bytes).ok()?.to_rgba8();
+
+    Some(Handle::from_rgba(
+        image.width(),
+        image.height(),
+        image.into_raw(),
+    ))
+}
+
+pub fn view(images: &[EmbeddedImage]) -> Element<Message> {
+    let images = images
+        .iter()
+        .fold(column![].spacing(10), |images, embedded| {
+            let caption = (!embedded.alt.is_empty()).then(|| text(embedded.alt.as_str()).size(14));
+
+            match &embedded.handle {
+                Some(handle) => images.push(
+                    column![image(handle.clone()).width(Length::Fill)]
+                        .push_maybe(caption)
+                        .spacing(2),
+                ),
+                None => images.push(
+                    column![text(tr("images.unreadable")).size(14)]
+                        .push_maybe(caption)
+                        .spacing(2),
+                ),
+            }
+        });
+
+    container(scrollable(images)).width(250).padding(5).into()
+}
@@ -0,0 +1,53 @@
+use std::fmt;
+use std::path::PathBuf;
+
+use cryptodoc::file::FileError;
+pub use cryptodoc::index::{index_path, DocumentMeta, LabelColor, VaultIndex};
+use iced::Color;
+
+use crate::gui::dialogs::save_file;
+use crate::gui::i18n::tr;
+
+/// A [`LabelColor`] as it's shown, with its swatch and translated name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Label(pub LabelColor);
+
+impl Label {
+    pub const ALL: &'static [Self] = &[
+        Self(LabelColor::Red),
+        Self(LabelColor::Orange),
+        Self(LabelColor::Yellow),
+        Self(LabelColor::Green),
+        Self(LabelColor::Blue),
+        Self(LabelColor::Purple),
+    ];
+
+    pub fn color(self) -> Color {
+        match self.0 {
+            LabelColor::Red => Color::from_rgb8(0xE5, 0x48, 0x4D),
+            LabelColor::Orange => Color::from_rgb8(0xF7, 0x6B, 0x15),
+            LabelColor::Yellow => Color::from_rgb8(0xF5, 0xC5, 0x18),
+            LabelColor::Green => Color::from_rgb8(0x30, 0xA4, 0x6C),
+            LabelColor::Blue => Color::from_rgb8(0x00, 0x90, 0xFF),
+            LabelColor::Purple => Color::from_rgb8(0x8E, 0x4E, 0xC6),
+        }
+    }
+}
+
+impl fmt::Display for Label {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            LabelColor::Red => tr("label.red"),
+            LabelColor::Orange => tr("label.orange"),
+            LabelColor::Yellow => tr("label.yellow"),
+            LabelColor::Green => tr("label.green"),
+            LabelColor::Blue => tr("label.blue"),
+            LabelColor::Purple => tr("label.purple"),
+        }
+        .fmt(f)
+    }
+}
+
+pub async fn save_index(folder: PathBuf, index: VaultIndex) -> Result<PathBuf, FileError> {
+    save_file(Some(index_path(&folder)), index.to_json()).await
+}
@@ -0,0 +1,353 @@
+//! Handing requests to the instance already running. It listens on a
+//! local port, which any user of the machine can connect to, so both sides
+//! first prove they know a key only this user can read:
+//!
+//! ```text
+//! client: CRYPTODOC2 HELLO <client nonce>
+//! server: CRYPTODOC2 PROOF <server nonce> <HMAC of "server" and the client nonce>
+//! client: CRYPTODOC2 <HMAC of "client" and the server nonce> <request>
+//! ```
+//!
+//! The HMACs are HMAC-SHA256 under the key in [`KEY_PATH`], and nonces and
+//! HMACs are hex. Nothing but the nonces is sent before the other side has
+//! proven itself, so a program that took the port first learns nothing.
+
+use std::any::TypeId;
+use std::fs::{self, OpenOptions};
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+#[cfg(unix)]
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crypto::hmac::Hmac;
+use crypto::mac::{Mac, MacResult};
+use crypto::sha2::Sha256;
+use iced::futures::SinkExt;
+use iced::Subscription;
+
+const PORT: u16 = 47391;
+const GREETING: &str = "CRYPTODOC2";
+const TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Where the running instance keeps the key, next to the app's config and
+/// readable only by its user.
+const KEY_PATH: &str = "./instance.key";
+
+/// The longest line read from the other side, so a misbehaving one can't
+/// make us allocate without bound.
+const MAX_LINE: u64 = 16 << 10;
+
+/// How long the other editor may take before an edit is given up on.
+const EDIT_TIMEOUT: Duration = Duration::from_secs(60 * 60);
+
+/// The most edited text a client can hand back, so a misbehaving one can't
+/// make us allocate without bound.
+const MAX_EDIT_SIZE: usize = 64 << 20;
+
+#[derive(Debug, Clone)]
+pub enum Request {
+    Show,
+    Open(PathBuf),
+    /// Another program wants the plaintext of a document to edit. The
+    /// connection stays open until the edit is denied or finished.
+    Edit {
+        id: u64,
+        path: PathBuf,
+    },
+}
+
+static PRIMARY: Mutex<Option<(TcpListener, Vec<u8>, Option<PathBuf>)>> = Mutex::new(None);
+
+/// Connections of edits that are waiting for the user or for the client.
+static EDITS: Mutex<Vec<(u64, TcpStream)>> = Mutex::new(Vec::new());
+static NEXT_EDIT: AtomicU64 = AtomicU64::new(0);
+
+/// Claims the single-instance socket. If another instance already holds it,
+/// `path` is handed over to that instance and `false` is returned so this one
+/// can exit. If the other side doesn't answer like CryptoDoc, or can't prove
+/// it's this user's, we run anyway.
+pub fn acquire(path: Option<PathBuf>) -> bool {
+    let path = path.map(|path| std::fs::canonicalize(&path).unwrap_or(path));
+
+    match TcpListener::bind(address()) {
+        Ok(listener) => {
+            // Without a key no client could prove itself, so there's no
+            // point listening.
+            if let Ok(key) = new_key() {
+                *PRIMARY.lock().unwrap() = Some((listener, key, path));
+            }
+
+            true
+        }
+        Err(_) => forward(path).is_err(),
+    }
+}
+
+/// Writes a fresh key for this instance, replacing any earlier one.
+fn new_key() -> io::Result<Vec<u8>> {
+    let key: [u8; 32] = rand::random();
+
+    match fs::remove_file(KEY_PATH) {
+        Err(error) if error.kind() != io::ErrorKind::NotFound => return Err(error),
+        _ => {}
+    }
+
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    options.mode(0o600);
+
+    options
+        .open(KEY_PATH)?
+        .write_all(hex::encode(key).as_bytes())?;
+
+    Ok(key.to_vec())
+}
+
+fn read_key() -> io::Result<Vec<u8>> {
+    hex::decode(fs::read_to_string(KEY_PATH)?.trim()).map_err(|_| io::ErrorKind::InvalidData.into())
+}
+
+fn proof(key: &[u8], role: &str, nonce: &str) -> MacResult {
+    let mut hmac = Hmac::new(Sha256::new(), key);
+    hmac.input(role.as_bytes());
+    hmac.input(nonce.as_bytes());
+
+    hmac.result()
+}
+
+fn new_nonce() -> String {
+    hex::encode(rand::random::<[u8; 16]>())
+}
+
+/// Whether `proof` is a hex HMAC matching `expected`, compared in constant
+/// time.
+fn proves(proof: &str, expected: MacResult) -> bool {
+    hex::decode(proof).is_ok_and(|proof| MacResult::new(&proof) == expected)
+}
+
+/// Reads a line of at most [`MAX_LINE`] bytes, without the line ending and
+/// the greeting.
+fn read_line(reader: &mut impl BufRead) -> io::Result<String> {
+    let mut line = String::new();
+    reader.take(MAX_LINE).read_line(&mut line)?;
+
+    if !line.ends_with('\n') {
+        return Err(io::ErrorKind::InvalidData.into());
+    }
+
+    line.trim_end()
+        .strip_prefix(GREETING)
+        .and_then(|line| line.strip_prefix(' '))
+        .map(str::to_string)
+        .ok_or_else(|| io::ErrorKind::InvalidData.into())
+}
+
+fn address() -> SocketAddr {
+    SocketAddr::from((Ipv4Addr::LOCALHOST, PORT))
+}
+
+fn forward(path: Option<PathBuf>) -> io::Result<()> {
+    let key = read_key()?;
+
+    let mut stream = TcpStream::connect_timeout(&address(), TIMEOUT)?;
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    stream.set_write_timeout(Some(TIMEOUT))?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+
+    let nonce = new_nonce();
+    stream.write_all(format!("{} HELLO {}\n", GREETING, nonce).as_bytes())?;
+
+    let reply = read_line(&mut reader)?;
+    let (server_nonce, server_proof) = match reply.split(' ').collect::<Vec<_>>()[..] {
+        ["PROOF", server_nonce, server_proof] => (server_nonce, server_proof),
+        _ => return Err(io::ErrorKind::InvalidData.into()),
+    };
+
+    if !proves(server_proof, proof(&key, "server", &nonce)) {
+        return Err(io::ErrorKind::PermissionDenied.into());
+    }
+
+    let client_proof = hex::encode(proof(&key, "client", server_nonce).code());
+    let request = match path {
+        Some(path) => format!("{} {} OPEN {}\n", GREETING, client_proof, path.display()),
+        None => format!("{} {} SHOW\n", GREETING, client_proof),
+    };
+
+    stream.write_all(request.as_bytes())?;
+
+    if read_line(&mut reader)? == "OK" {
+        Ok(())
+    } else {
+        Err(io::Error::from(io::ErrorKind::InvalidData))
+    }
+}
+
+pub fn requests() -> Subscription<Request> {
+    struct Requests;
+
+    iced::subscription::channel(TypeId::of::<Requests>(), 16, |mut output| async move {
+        let primary = PRIMARY.lock().unwrap().take();
+
+        let Some((listener, key, startup)) = primary else {
+            return std::future::pending().await;
+        };
+
+        if let Some(path) = startup {
+            let _ = output.send(Request::Open(path)).await;
+        }
+
+        let listener = Arc::new(listener);
+        let key = Arc::new(key);
+
+        loop {
+            let listener = listener.clone();
+            let key = key.clone();
+
+            match tokio::task::spawn_blocking(move || accept(&listener, &key)).await {
+                Ok(Ok(Some(request))) => {
+                    let _ = output.send(request).await;
+                }
+                Ok(Ok(None)) => {}
+                // The socket is unusable, so stop listening instead of spinning.
+                _ => return std::future::pending().await,
+            }
+        }
+    })
+}
+
+fn accept(listener: &TcpListener, key: &[u8]) -> io::Result<Option<Request>> {
+    let (mut stream, _) = listener.accept()?;
+
+    Ok(read_request(&mut stream, key).ok().flatten())
+}
+
+fn read_request(stream: &mut TcpStream, key: &[u8]) -> io::Result<Option<Request>> {
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    stream.set_write_timeout(Some(TIMEOUT))?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+
+    let Some(client_nonce) = read_line(&mut reader)?
+        .strip_prefix("HELLO ")
+        .map(str::to_string)
+    else {
+        return Ok(None);
+    };
+
+    let nonce = new_nonce();
+    let server_proof = hex::encode(proof(key, "server", &client_nonce).code());
+    stream.write_all(format!("{} PROOF {} {}\n", GREETING, nonce, server_proof).as_bytes())?;
+
+    let line = read_line(&mut reader)?;
+    let Some((client_proof, request)) = line.split_once(' ') else {
+        return Ok(None);
+    };
+
+    if !proves(client_proof, proof(key, "client", &nonce)) {
+        return Ok(None);
+    }
+
+    let request = parse(request);
+
+    match &request {
+        // Answered once the user decides.
+        Some(Request::Edit { id, .. }) => EDITS.lock().unwrap().push((*id, stream.try_clone()?)),
+        Some(_) => stream.write_all(format!("{} OK\n", GREETING).as_bytes())?,
+        None => {}
+    }
+
+    Ok(request)
+}
+
+fn parse(request: &str) -> Option<Request> {
+    match request.split_once(' ') {
+        Some(("OPEN", path)) => Some(Request::Open(PathBuf::from(path))),
+        Some(("EDIT", path)) => Some(Request::Edit {
+            id: NEXT_EDIT.fetch_add(1, Ordering::Relaxed),
+            path: PathBuf::from(path),
+        }),
+        None if request == "SHOW" => Some(Request::Show),
+        _ => None,
+    }
+}
+
+/// Sends the plaintext of an allowed edit to the client and waits for the
+/// edited text to come back. `None` means the client gave up on the edit.
+///
+/// After `EDIT <path>`, the client gets `TEXT <length>` and that many bytes
+/// of UTF-8, or `DENIED`. It answers with `SAVE <length>` and the edited
+/// text, or `CANCEL`, within [`EDIT_TIMEOUT`], and after a save gets `OK` or
+/// `FAILED`. Every line starts with the greeting, like the other requests,
+/// and the request comes after the handshake described above. The text only
+/// ever passes through the socket, never a file.
+pub async fn exchange_text(id: u64, text: String) -> Result<Option<String>, io::ErrorKind> {
+    tokio::task::spawn_blocking(move || exchange(id, &text))
+        .await
+        .map_err(|_| io::ErrorKind::Other)?
+        .map_err(|error| error.kind())
+}
+
+fn exchange(id: u64, text: &str) -> io::Result<Option<String>> {
+    let mut stream = take_edit(id)?;
+    stream.set_write_timeout(Some(EDIT_TIMEOUT))?;
+
+    stream.write_all(format!("{} TEXT {}\n", GREETING, text.len()).as_bytes())?;
+    stream.write_all(text.as_bytes())?;
+
+    // The user can take a while in the other editor, but not forever.
+    stream.set_read_timeout(Some(EDIT_TIMEOUT))?;
+
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let reply = read_line(&mut reader)?;
+
+    match reply.split_once(' ') {
+        Some(("SAVE", length)) => {
+            let length = length
+                .parse()
+                .ok()
+                .filter(|&length| length <= MAX_EDIT_SIZE)
+                .ok_or(io::ErrorKind::InvalidData)?;
+
+            let mut edited = vec![0; length];
+            reader.read_exact(&mut edited)?;
+            let edited = String::from_utf8(edited).map_err(|_| io::ErrorKind::InvalidData)?;
+
+            // Kept until the save finishes, to report how it went.
+            EDITS.lock().unwrap().push((id, stream));
+
+            Ok(Some(edited))
+        }
+        None if reply == "CANCEL" => Ok(None),
+        _ => Err(io::ErrorKind::InvalidData.into()),
+    }
+}
+
+/// Tells the client whether its edited text was saved, and hangs up.
+pub fn finish_edit(id: u64, saved: bool) {
+    reply(id, if saved { "OK" } else { "FAILED" });
+}
+
+/// Tells the client the user refused the edit, and hangs up.
+pub fn deny_edit(id: u64) {
+    reply(id, "DENIED");
+}
+
+fn reply(id: u64, reply: &str) {
+    if let Ok(mut stream) = take_edit(id) {
+        let _ = stream.write_all(format!("{} {}\n", GREETING, reply).as_bytes());
+    }
+}
+
+fn take_edit(id: u64) -> io::Result<TcpStream> {
+    let mut edits = EDITS.lock().unwrap();
+    let index = edits
+        .iter()
+        .position(|(edit, _)| *edit == id)
+        .ok_or(io::ErrorKind::NotFound)?;
+
+    Ok(edits.remove(index).1)
+}
@@ -0,0 +1,225 @@
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+use chrono::{Datelike, Local, Months, NaiveDate};
+use cryptodoc::file::{FileError, DOCUMENT_EXTENSION};
+use cryptodoc::lockout;
+use cryptodoc::{Document, DocumentError};
+use iced::widget::{button, column, container, horizontal_space, row, text, Column};
+use iced::{Alignment, Element, Length};
+
+use crate::gui::i18n::tr;
+use crate::gui::Message;
+
+/// The subfolder of the save folder that holds the journal.
+const JOURNAL_FOLDER: &str = "journal";
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+pub fn today() -> NaiveDate {
+    Local::now().date_naive()
+}
+
+pub fn folder(save_path: &str) -> PathBuf {
+    Path::new(save_path).join(JOURNAL_FOLDER)
+}
+
+/// The document for `date`, like `journal/2025-06-01.cryptodoc`.
+pub fn entry_path(save_path: &str, date: NaiveDate) -> PathBuf {
+    folder(save_path).join(format!(
+        "{}.{}",
+        date.format(DATE_FORMAT),
+        DOCUMENT_EXTENSION
+    ))
+}
+
+/// The day `path` is the journal entry for, if it's one.
+pub fn entry_date(save_path: &str, path: &Path) -> Option<NaiveDate> {
+    if save_path.is_empty() || path.parent() != Some(folder(save_path).as_path()) {
+        return None;
+    }
+
+    let date = path.file_stem()?.to_str()?;
+
+    NaiveDate::parse_from_str(date, DATE_FORMAT)
+        .ok()
+        .filter(|&date| entry_path(save_path, date) == path)
+}
+
+/// Every day that has an entry.
+pub async fn entry_dates(folder: PathBuf) -> Result<BTreeSet<NaiveDate>, FileError> {
+    let mut entries = match tokio::fs::read_dir(&folder).await {
+        Ok(entries) => entries,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(BTreeSet::new()),
+        Err(error) => return Err(FileError::IOFailed(error.kind())),
+    };
+
+    let mut dates = BTreeSet::new();
+
+    while let Some(entry) = entries
+        .next_entry()
+        .await
+        .map_err(|error| FileError::IOFailed(error.kind()))?
+    {
+        let path = entry.path();
+
+        if path
+            .extension()
+            .is_some_and(|extension| extension == DOCUMENT_EXTENSION)
+        {
+            if let Some(date) = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .and_then(|stem| NaiveDate::parse_from_str(stem, DATE_FORMAT).ok())
+            {
+                dates.insert(date);
+            }
+        }
+    }
+
+    Ok(dates)
+}
+
+/// Checks `password` against the latest entry, so the whole journal keeps
+/// one password. Any password unlocks a journal without entries. Wrong
+/// passwords count against that entry, see [`lockout::open`].
+pub async fn unlock(save_path: String, password: String) -> Result<(), DocumentError> {
+    let latest = entry_dates(folder(&save_path)).await?.last().copied();
+
+    if let Some(date) = latest {
+        lockout::open(entry_path(&save_path, date), &password).await?;
+    }
+
+    Ok(())
+}
+
+/// Decrypts the entry for `date`, or returns `None` if there isn't one yet.
+pub async fn open_entry(
+    save_path: String,
+    date: NaiveDate,
+    password: String,
+) -> Result<Option<Document>, DocumentError> {
+    tokio::fs::create_dir_all(folder(&save_path))
+        .await
+        .map_err(|error| DocumentError::Io(error.kind()))?;
+
+    let path = entry_path(&save_path, date);
+
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    Document::open(path, &password).await.map(Some)
+}
+
+/// The month shown in the calendar picker, and which of its days have
+/// entries.
+pub struct Calendar {
+    /// The first day of the month.
+    pub month: NaiveDate,
+    pub entries: BTreeSet<NaiveDate>,
+    pub selected: NaiveDate,
+}
+
+impl Calendar {
+    pub fn new(selected: NaiveDate, entries: BTreeSet<NaiveDate>) -> Self {
+        Self {
+            month: selected.with_day(1).unwrap_or(selected),
+            entries,
+            selected,
+        }
+    }
+
+    pub fn change_month(&mut self, delta: i32) {
+        let months = Months::new(delta.unsigned_abs());
+
+        let month = if delta < 0 {
+            self.month.checked_sub_months(months)
+        } else {
+            self.month.checked_add_months(months)
+        };
+
+        if let Some(month) = month {
+            self.month = month;
+        }
+    }
+
+    pub fn view(&self) -> Element<Message> {
+        let header = row![
+            button(text("‹"))
+                .style(button::secondary)
+                .on_press(Message::CalendarMonthChanged(-1)),
+            horizontal_space(),
+            text(self.month.format("%Y-%m").to_string()),
+            horizontal_space(),
+            button(text("›"))
+                .style(button::secondary)
+                .on_press(Message::CalendarMonthChanged(1)),
+        ]
+        .align_items(Alignment::Center);
+
+        let weekdays = tr("calendar.weekdays")
+            .split_whitespace()
+            .fold(row![].spacing(5), |row, day| {
+                row.push(text(day).size(12).width(Length::Fixed(40.0)))
+            });
+
+        let offset = self.month.weekday().num_days_from_monday() as usize;
+        let days: Vec<NaiveDate> = self
+            .month
+            .iter_days()
+            .take_while(|day| day.month() == self.month.month())
+            .collect();
+
+        let mut cells: Vec<Option<NaiveDate>> = vec![None; offset];
+        cells.extend(days.into_iter().map(Some));
+
+        let weeks = cells
+            .chunks(7)
+            .fold(Column::new().spacing(5), |weeks, week| {
+                let week = week.iter().fold(row![].spacing(5), |row, day| {
+                    row.push(match day {
+                        Some(day) => self.day_button(*day),
+                        None => horizontal_space().width(Length::Fixed(40.0)).into(),
+                    })
+                });
+
+                weeks.push(week)
+            });
+
+        let buttons = row![
+            button(tr("journal.today"))
+                .style(button::secondary)
+                .on_press(Message::JournalDaySelected(today())),
+            horizontal_space(),
+            button(tr("prompt.cancel"))
+                .style(button::secondary)
+                .on_press(Message::CalendarClosed),
+        ];
+
+        container(
+            column![header, weekdays, weeks, buttons]
+                .spacing(10)
+                .width(Length::Shrink),
+        )
+        .padding(20)
+        .style(container::rounded_box)
+        .into()
+    }
+
+    fn day_button(&self, day: NaiveDate) -> Element<Message> {
+        let style = if day == self.selected {
+            button::success
+        } else if self.entries.contains(&day) {
+            button::primary
+        } else {
+            button::secondary
+        };
+
+        button(text(day.day().to_string()).size(14))
+            .width(Length::Fixed(40.0))
+            .style(style)
+            .on_press(Message::JournalDaySelected(day))
+            .into()
+    }
+}
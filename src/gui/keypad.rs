@@ -0,0 +1,93 @@
+use iced::widget::{button, column, row, text, Row};
+use iced::{Element, Length};
+use rand::seq::SliceRandom;
+
+use crate::gui::i18n::tr;
+
+const KEYS: &str = "abcdefghijklmnopqrstuvwxyz0123456789-_.!?@#$%&*+=";
+const KEYS_PER_ROW: usize = 10;
+
+#[derive(Debug, Clone)]
+pub struct Keypad {
+    keys: Vec<char>,
+    shift: bool,
+}
+
+impl Keypad {
+    pub fn new() -> Self {
+        let mut keypad = Self {
+            keys: KEYS.chars().collect(),
+            shift: false,
+        };
+        keypad.shuffle();
+        keypad
+    }
+
+    /// Moves every key to a new position so the click coordinates recorded
+    /// by a screen logger can't be mapped back to characters.
+    pub fn shuffle(&mut self) {
+        self.keys.shuffle(&mut rand::thread_rng());
+    }
+
+    pub fn toggle_shift(&mut self) {
+        self.shift = !self.shift;
+    }
+
+    pub fn key(&self, c: char) -> char {
+        if self.shift {
+            c.to_ascii_uppercase()
+        } else {
+            c
+        }
+    }
+}
+
+impl Default for Keypad {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub fn view<'a, Message>(
+    keypad: &'a Keypad,
+    on_key: impl Fn(char) -> Message,
+    on_backspace: Message,
+    on_shift: Message,
+) -> Element<'a, Message>
+where
+    Message: Clone + 'a,
+{
+    let keys = keypad
+        .keys
+        .chunks(KEYS_PER_ROW)
+        .fold(column![].spacing(4), |keys, chunk| {
+            keys.push(chunk.iter().fold(Row::new().spacing(4), |row, &c| {
+                let c = keypad.key(c);
+
+                row.push(
+                    button(text(c.to_string()).size(14))
+                        .width(Length::Fill)
+                        .style(button::secondary)
+                        .on_press(on_key(c)),
+                )
+            }))
+        });
+
+    let controls = row![
+        button(text(tr("keypad.shift")).size(14))
+            .width(Length::Fill)
+            .style(if keypad.shift {
+                button::primary
+            } else {
+                button::secondary
+            })
+            .on_press(on_shift),
+        button(text(tr("keypad.backspace")).size(14))
+            .width(Length::Fill)
+            .style(button::secondary)
+            .on_press(on_backspace),
+    ]
+    .spacing(4);
+
+    column![keys, controls].spacing(4).into()
+}
@@ -0,0 +1,125 @@
+//! Wiki-style `[[Document Name]]` links between the documents of the save
+//! folder, and the documents linking back to the open one.
+
+use std::path::{Path, PathBuf};
+
+use cryptodoc::file::list_documents;
+use cryptodoc::Document;
+use iced::widget::{button, column, container, scrollable, text};
+use iced::Element;
+
+use crate::gui::i18n::tr;
+use crate::gui::Message;
+
+/// The names linked to from `text`, in order and without repeats. Links in
+/// fenced code blocks are left out, like headings in the outline.
+pub fn links(text: &str) -> Vec<String> {
+    let mut names: Vec<String> = vec![];
+    let mut in_code = false;
+
+    for line in text.lines() {
+        if line.trim_start().starts_with("```") || line.trim_start().starts_with("~~~") {
+            in_code = !in_code;
+            continue;
+        }
+
+        if in_code {
+            continue;
+        }
+
+        let mut rest = line;
+
+        while let Some(start) = rest.find("[[") {
+            rest = &rest[start + 2..];
+
+            let Some((name, after)) = rest.split_once("]]") else {
+                break;
+            };
+
+            let name = name.trim();
+
+            if !name.is_empty()
+                && !name.contains('[')
+                && !names.iter().any(|known| known.eq_ignore_ascii_case(name))
+            {
+                names.push(name.to_string());
+            }
+
+            rest = after;
+        }
+    }
+
+    names
+}
+
+/// The name other documents link to the document at `path` by.
+pub fn link_name(path: &Path) -> Option<&str> {
+    path.file_stem().and_then(|name| name.to_str())
+}
+
+/// The documents in `folder` linking to the one at `path`. Like search, only
+/// the documents `password` unlocks are read, so in a folder with mixed
+/// passwords the others are left out.
+pub async fn find_backlinks(folder: PathBuf, password: String, path: PathBuf) -> Vec<PathBuf> {
+    let Some(name) = link_name(&path).map(str::to_string) else {
+        return vec![];
+    };
+
+    let Ok(documents) = list_documents(folder).await else {
+        return vec![];
+    };
+
+    let mut backlinks = vec![];
+
+    for document in documents {
+        if document.path == path {
+            continue;
+        }
+
+        let Ok(linking) = Document::open(document.path.clone(), &password).await else {
+            continue;
+        };
+
+        if links(&linking.text)
+            .iter()
+            .any(|link| link.eq_ignore_ascii_case(&name))
+        {
+            backlinks.push(document.path);
+        }
+    }
+
+    backlinks.sort();
+    backlinks
+}
+
+pub fn view<'a>(links: &'a [String], backlinks: &'a [PathBuf]) -> Element<'a, Message> {
+    let entry = |label: &'a str, message: Message| {
+        button(text(label).size(14))
+            .style(button::text)
+            .padding([2, 5])
+            .on_press(message)
+    };
+
+    let mut panel = column![].spacing(5);
+
+    if !links.is_empty() {
+        panel = panel.push(text(tr("links.title"))).push(
+            links.iter().fold(column![].spacing(2), |entries, name| {
+                entries.push(entry(name, Message::LinkSelected(name.clone())))
+            }),
+        );
+    }
+
+    if !backlinks.is_empty() {
+        panel = panel.push(text(tr("links.backlinks"))).push(
+            backlinks
+                .iter()
+                .filter_map(|path| Some((path, link_name(path)?)))
+                .fold(column![].spacing(2), |entries, (path, name)| {
+                    entries.push(entry(name, Message::BacklinkSelected(path.clone())))
+                }),
+        );
+    }
+
+    container(scrollable(panel)).width(220).padding(5).into()
+}
@@ -0,0 +1,72 @@
+use std::path::PathBuf;
+
+use cryptodoc::file::FileError;
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+
+const LOG_FOLDER: &str = "./logs";
+const LOG_PREFIX: &str = "cryptodoc";
+const LOG_SUFFIX: &str = "log";
+const MAX_LOG_FILES: usize = 7;
+const VIEWER_LINES: usize = 200;
+
+/// Starts writing log events to a daily file in `./logs`, keeping a week of
+/// history. Only file operations, formats and errors are logged, never
+/// document text or passwords.
+///
+/// Logging stops when the returned guard is dropped.
+pub fn init() -> Option<WorkerGuard> {
+    let appender = RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix(LOG_PREFIX)
+        .filename_suffix(LOG_SUFFIX)
+        .max_log_files(MAX_LOG_FILES)
+        .build(LOG_FOLDER)
+        .ok()?;
+
+    let (writer, guard) = tracing_appender::non_blocking(appender);
+
+    tracing_subscriber::fmt()
+        .with_writer(writer)
+        .with_ansi(false)
+        .with_max_level(tracing::Level::INFO)
+        .try_init()
+        .ok()?;
+
+    Some(guard)
+}
+
+/// The last lines of the newest log file, for the viewer in Settings.
+pub async fn read_recent() -> Result<String, FileError> {
+    let mut entries = tokio::fs::read_dir(LOG_FOLDER)
+        .await
+        .map_err(|error| FileError::IOFailed(error.kind()))?;
+
+    let mut newest: Option<PathBuf> = None;
+
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let path = entry.path();
+        let is_log = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.starts_with(LOG_PREFIX) && name.ends_with(LOG_SUFFIX));
+
+        // Rotated files are named by date, so the newest sorts last.
+        if is_log && newest.as_ref().map_or(true, |newest| &path > newest) {
+            newest = Some(path);
+        }
+    }
+
+    let Some(path) = newest else {
+        return Ok(String::new());
+    };
+
+    let contents = tokio::fs::read_to_string(path)
+        .await
+        .map_err(|error| FileError::IOFailed(error.kind()))?;
+
+    let lines: Vec<&str> = contents.lines().collect();
+    let start = lines.len().saturating_sub(VIEWER_LINES);
+
+    Ok(lines[start..].join("\n"))
+}
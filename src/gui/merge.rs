@@ -0,0 +1,124 @@
+//! Reconciles the open document with a version saved over it in the
+//! meantime, usually by a sync client bringing in edits from another
+//! machine. See [`cryptodoc::merge`].
+
+use std::time::SystemTime;
+
+use cryptodoc::merge::{conflicts, merge, merged_text, Chunk, Conflict, Side};
+use iced::widget::{button, column, container, horizontal_space, row, scrollable, text};
+use iced::{Alignment, Color, Element, Font, Length};
+
+use crate::gui::i18n::{tr, tr_with};
+use crate::gui::Message;
+
+pub struct Merge {
+    pub chunks: Vec<Chunk>,
+    /// When the other version was written, so saving the merge doesn't
+    /// count it as changed again.
+    pub modified: Option<SystemTime>,
+    /// The other version, which becomes the base once the merge is saved.
+    pub theirs: String,
+    trailing_newline: bool,
+}
+
+impl Merge {
+    pub fn new(base: &str, ours: &str, theirs: &str, modified: Option<SystemTime>) -> Self {
+        Self {
+            chunks: merge(base, ours, theirs),
+            modified,
+            theirs: theirs.to_string(),
+            trailing_newline: ours.ends_with('\n'),
+        }
+    }
+
+    pub fn resolve(&mut self, index: usize, side: Side) {
+        if let Some(Chunk::Conflict(conflict)) = self.chunks.get_mut(index) {
+            conflict.resolution = Some(side);
+        }
+    }
+
+    /// The merged text, once every conflict is resolved.
+    pub fn text(&self) -> Option<String> {
+        merged_text(&self.chunks, self.trailing_newline)
+    }
+
+    pub fn view(&self, text_size: f32) -> Element<Message> {
+        let done = self.text().is_some();
+
+        let controls = row![
+            text(tr_with("merge.title", conflicts(&self.chunks))).size(14),
+            horizontal_space(),
+            button(tr("prompt.cancel"))
+                .style(button::secondary)
+                .on_press(Message::MergeCancelled),
+            button(tr("merge.save")).on_press_maybe(done.then_some(Message::MergeCompleted)),
+        ]
+        .spacing(10)
+        .align_items(Alignment::Center);
+
+        let chunks =
+            self.chunks
+                .iter()
+                .enumerate()
+                .fold(
+                    column![].spacing(10),
+                    |chunks, (index, chunk)| match chunk {
+                        Chunk::Merged(lines) => chunks.push(
+                            text(lines.join("\n"))
+                                .font(Font::MONOSPACE)
+                                .size(text_size)
+                                .width(Length::Fill),
+                        ),
+                        Chunk::Conflict(conflict) => {
+                            chunks.push(conflict_view(index, conflict, text_size))
+                        }
+                    },
+                );
+
+        column![
+            text(tr("merge.description")).size(14),
+            controls,
+            scrollable(chunks).height(Length::Fill)
+        ]
+        .spacing(10)
+        .into()
+    }
+}
+
+fn conflict_view(index: usize, conflict: &Conflict, text_size: f32) -> Element<Message> {
+    let side = |side: Side, label: &'static str, lines: &[String]| {
+        let chosen = conflict.resolution == Some(side);
+
+        let lines = text(lines.join("\n")).font(Font::MONOSPACE).size(text_size);
+        // The sides that weren't picked are dimmed.
+        let lines = match conflict.resolution {
+            Some(resolution) if resolution != side => lines.color(Color::from_rgb(0.5, 0.5, 0.5)),
+            _ => lines,
+        };
+
+        column![
+            button(tr(label))
+                .style(if chosen {
+                    button::primary
+                } else {
+                    button::secondary
+                })
+                .on_press(Message::MergeSideChosen(index, side)),
+            lines,
+        ]
+        .spacing(5)
+        .width(Length::FillPortion(1))
+    };
+
+    container(
+        row![
+            side(Side::Ours, "merge.ours", &conflict.ours),
+            side(Side::Base, "merge.base", &conflict.base),
+            side(Side::Theirs, "merge.theirs", &conflict.theirs),
+        ]
+        .spacing(10),
+    )
+    .padding(10)
+    .style(container::rounded_box)
+    .into()
+}
@@ -0,0 +1,55 @@
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Heading {
+    pub line: usize,
+    /// 1 for `#`, up to 6 for `######`.
+    pub level: usize,
+    pub text: String,
+}
+
+/// Parses a Markdown heading line such as `## Notes` or `### Notes ###`.
+pub fn parse_heading(line: usize, text: &str) -> Option<Heading> {
+    let rest = text.trim_start_matches(' ');
+
+    // More than three spaces makes it an indented code block.
+    if text.len() - rest.len() > 3 {
+        return None;
+    }
+
+    let level = rest.chars().take_while(|c| *c == '#').count();
+
+    if !(1..=6).contains(&level) {
+        return None;
+    }
+
+    let rest = &rest[level..];
+
+    if !rest.is_empty() && !rest.starts_with([' ', '\t']) {
+        return None;
+    }
+
+    let title = rest.trim().trim_end_matches('#').trim_end();
+
+    Some(Heading {
+        line,
+        level,
+        text: title.to_string(),
+    })
+}
+
+/// The headings of `text`, leaving out any inside fenced code blocks.
+pub fn outline(text: &str) -> Vec<Heading> {
+    let mut in_code = false;
+
+    text.lines()
+        .enumerate()
+        .filter_map(|(line, text)| {
+            if text.trim_start().starts_with("```") || text.trim_start().starts_with("~~~") {
+                in_code = !in_code;
+                return None;
+            }
+
+            (!in_code).then(|| parse_heading(line, text)).flatten()
+        })
+        .filter(|heading| !heading.text.is_empty())
+        .collect()
+}
@@ -0,0 +1,106 @@
+//! A read-only view for documents too large for the editor, which lays out
+//! the whole text and freezes on something like a long log. The text is
+//! split into pages of lines and only the page on screen is shown.
+
+use cryptodoc::text::LineEnding;
+use iced::widget::{button, column, horizontal_space, row, scrollable, slider, text};
+use iced::{Alignment, Element, Font, Length};
+
+use crate::gui::i18n::{tr, tr_with};
+use crate::gui::Message;
+
+/// Documents at least this large open in the pager rather than the editor.
+pub const LARGE_DOCUMENT_SIZE: usize = 4 * 1024 * 1024;
+
+const PAGE_LINES: usize = 500;
+
+pub struct Pager {
+    /// The text with `\n` line breaks, like the editor's.
+    pub text: String,
+    /// Where each page starts in `text`.
+    page_starts: Vec<usize>,
+    page: usize,
+}
+
+impl Pager {
+    pub fn new(text: String) -> Self {
+        let text = if text.contains('\r') {
+            LineEnding::Lf.apply(&text)
+        } else {
+            text
+        };
+
+        let page_starts = std::iter::once(0)
+            .chain(
+                text.match_indices('\n')
+                    .map(|(index, _)| index + 1)
+                    .skip(PAGE_LINES - 1)
+                    .step_by(PAGE_LINES)
+                    .filter(|&start| start < text.len()),
+            )
+            .collect();
+
+        Self {
+            text,
+            page_starts,
+            page: 0,
+        }
+    }
+
+    pub fn pages(&self) -> usize {
+        self.page_starts.len()
+    }
+
+    pub fn set_page(&mut self, page: usize) {
+        self.page = page.min(self.pages() - 1);
+    }
+
+    fn page_text(&self) -> &str {
+        let start = self.page_starts[self.page];
+        let end = self
+            .page_starts
+            .get(self.page + 1)
+            .map_or(self.text.len(), |end| end - 1);
+
+        &self.text[start..end]
+    }
+
+    pub fn view(&self, text_size: f32) -> Element<Message> {
+        let last = self.pages() - 1;
+
+        let controls = row![
+            text(tr("pager.read_only")).size(14),
+            horizontal_space(),
+            button(text("‹"))
+                .style(button::secondary)
+                .on_press_maybe(self.page.checked_sub(1).map(Message::PagerPageSelected)),
+            text(tr_with(
+                "pager.page",
+                format!("{}/{}", self.page + 1, self.pages())
+            ))
+            .size(14),
+            button(text("›")).style(button::secondary).on_press_maybe(
+                (self.page < last).then_some(Message::PagerPageSelected(self.page + 1))
+            ),
+            slider(0..=last as u32, self.page as u32, |page| {
+                Message::PagerPageSelected(page as usize)
+            })
+            .width(200),
+            button(tr("pager.edit"))
+                .style(button::secondary)
+                .on_press(Message::PagerEditPressed),
+        ]
+        .spacing(10)
+        .align_items(Alignment::Center);
+
+        let page = scrollable(
+            text(self.page_text())
+                .font(Font::MONOSPACE)
+                .size(text_size)
+                .width(Length::Fill),
+        )
+        .height(Length::Fill);
+
+        column![controls, page].spacing(10).into()
+    }
+}
@@ -0,0 +1,210 @@
+//! The whole setup in one file, to move to another machine: the settings
+//! with the recent and pinned documents, the tags of the vault, and
+//! optionally the vault's documents, which stay encrypted.
+
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use cryptodoc::file::{list_documents, pathbuf_to_string, FileError};
+use serde::{Deserialize, Serialize};
+
+use crate::gui::config::Config;
+use crate::gui::index::{save_index, VaultIndex};
+
+pub const EXTENSION: &str = "cdprofile";
+
+/// Bumped when a profile changes in a way older versions can't import.
+const PROFILE_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize)]
+struct Profile {
+    version: u32,
+    config: Config,
+    index: VaultIndex,
+    /// The files of the vault by name, Base64 encoded. Empty if the profile
+    /// was exported without them.
+    documents: BTreeMap<String, String>,
+}
+
+/// What importing a profile restored.
+#[derive(Debug, Clone)]
+pub struct Imported {
+    pub config: Config,
+    pub index: VaultIndex,
+    pub documents: usize,
+    /// Documents left alone because the folder already had one by the same
+    /// name.
+    pub skipped: usize,
+    /// Whether the profile had post-save commands, which were left out.
+    pub hooks_removed: bool,
+}
+
+/// Writes the profile to `path`. With `with_documents`, the documents of the
+/// vault at `config.save_path` go into it as they are on disk.
+pub async fn export(
+    path: PathBuf,
+    config: Config,
+    index: VaultIndex,
+    with_documents: bool,
+) -> Result<PathBuf, FileError> {
+    let paths = if with_documents && !config.save_path.is_empty() {
+        list_documents(PathBuf::from(&config.save_path))
+            .await?
+            .into_iter()
+            .map(|document| document.path)
+            .collect()
+    } else {
+        vec![]
+    };
+
+    blocking(move || {
+        let mut documents = BTreeMap::new();
+
+        for document in paths {
+            let Some(name) = document.file_name() else {
+                continue;
+            };
+
+            documents.insert(
+                name.to_string_lossy().into_owned(),
+                STANDARD.encode(fs::read(&document)?),
+            );
+        }
+
+        let profile = Profile {
+            version: PROFILE_VERSION,
+            config,
+            index,
+            documents,
+        };
+
+        fs::write(&path, serde_json::to_vec(&profile)?)?;
+
+        Ok(path)
+    })
+    .await
+}
+
+/// Restores the profile at `path` with `folder` as the vault. Paths under the
+/// exported vault, like recent documents, are moved to `folder`, and tags
+/// are added to any the folder already has.
+pub async fn import(path: PathBuf, folder: PathBuf) -> Result<Imported, FileError> {
+    let imported = blocking({
+        let folder = folder.clone();
+        move || restore(&path, &folder)
+    })
+    .await?;
+
+    save_index(folder, imported.index.clone()).await?;
+
+    Ok(imported)
+}
+
+fn restore(path: &Path, folder: &Path) -> io::Result<Imported> {
+    let profile: Profile = serde_json::from_slice(&fs::read(path)?)?;
+
+    if profile.version > PROFILE_VERSION {
+        return Err(io::ErrorKind::InvalidData.into());
+    }
+
+    let mut documents = 0;
+    let mut skipped = 0;
+
+    for (name, contents) in &profile.documents {
+        // Only a plain file name, so a crafted profile can't write
+        // anywhere but the folder.
+        if Path::new(name).file_name() != Some(name.as_ref()) {
+            return Err(io::ErrorKind::InvalidData.into());
+        }
+
+        let contents = STANDARD
+            .decode(contents)
+            .map_err(|_| io::Error::from(io::ErrorKind::InvalidData))?;
+
+        match OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(folder.join(name))
+        {
+            Ok(mut file) => {
+                file.write_all(&contents)?;
+                documents += 1;
+            }
+            Err(error) if error.kind() == io::ErrorKind::AlreadyExists => skipped += 1,
+            Err(error) => return Err(error),
+        }
+    }
+
+    let mut index = VaultIndex::load(folder);
+
+    for (name, meta) in profile.index.documents {
+        index.documents.entry(name).or_insert(meta);
+    }
+
+    let (config, hooks_removed) = without_hooks(rebase(profile.config, folder).normalized());
+
+    Ok(Imported {
+        config,
+        index,
+        documents,
+        skipped,
+        hooks_removed,
+    })
+}
+
+/// `config` without any post-save commands, and whether it had some. They
+/// run on every save, so a profile from someone else could otherwise run
+/// whatever it likes.
+fn without_hooks(mut config: Config) -> (Config, bool) {
+    let mut removed = !config.post_save_hook.trim().is_empty();
+    config.post_save_hook_enabled = false;
+    config.post_save_hook.clear();
+
+    for vault in &mut config.vaults {
+        removed |= !vault.post_save_hook.trim().is_empty();
+        vault.post_save_hook_enabled = false;
+        vault.post_save_hook.clear();
+    }
+
+    (config, removed)
+}
+
+/// `config` with the paths under its vault moved to `folder`.
+fn rebase(mut config: Config, folder: &Path) -> Config {
+    let old = PathBuf::from(&config.save_path);
+    let new = pathbuf_to_string(&folder.to_path_buf());
+
+    if !config.save_path.is_empty() {
+        let paths = config
+            .recent
+            .iter_mut()
+            .chain(config.pinned.iter_mut())
+            .chain(config.inbox.iter_mut());
+
+        for path in paths {
+            if let Ok(relative) = path.strip_prefix(&old) {
+                *path = folder.join(relative);
+            }
+        }
+
+        if let Some(backend) = config.storage_backends.remove(&config.save_path) {
+            config.storage_backends.insert(new.clone(), backend);
+        }
+    }
+
+    config.save_path = new;
+    config
+}
+
+async fn blocking<T: Send + 'static>(
+    f: impl FnOnce() -> io::Result<T> + Send + 'static,
+) -> Result<T, FileError> {
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(|_| FileError::IOFailed(io::ErrorKind::Other))?
+        .map_err(|error| FileError::IOFailed(error.kind()))
+}
@@ -0,0 +1,96 @@
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use iced::futures::channel::mpsc;
+use iced::Task;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Progress {
+    pub done: usize,
+    pub total: usize,
+}
+
+impl Progress {
+    pub fn fraction(self) -> f32 {
+        if self.total == 0 {
+            0.0
+        } else {
+            self.done as f32 / self.total as f32
+        }
+    }
+}
+
+/// Handed to a running operation so it can report how far it got and notice
+/// when the user cancels it.
+#[derive(Debug, Clone)]
+pub struct Reporter {
+    sender: mpsc::UnboundedSender<Progress>,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl Reporter {
+    pub fn report(&self, done: usize, total: usize) {
+        // The UI may already have dropped the receiver; that's fine.
+        let _ = self.sender.unbounded_send(Progress { done, total });
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+/// A long-running operation shown in the progress overlay.
+#[derive(Debug)]
+pub struct Operation {
+    pub title: &'static str,
+    pub progress: Option<Progress>,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl Operation {
+    /// Asks the operation to stop. It finishes on its own time, at the next
+    /// point where it checks.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+/// Starts `job`, returning the operation to show and a task that produces
+/// `on_progress` for every report and `on_finish` with the result.
+pub fn start<T, Message, Fut>(
+    title: &'static str,
+    job: impl FnOnce(Reporter) -> Fut,
+    on_progress: impl Fn(Progress) -> Message + Send + 'static,
+    on_finish: impl FnOnce(T) -> Message + Send + 'static,
+) -> (Operation, Task<Message>)
+where
+    T: Send + 'static,
+    Message: Send + 'static,
+    Fut: Future<Output = T> + Send + 'static,
+{
+    let (sender, receiver) = mpsc::unbounded();
+    let cancelled = Arc::new(AtomicBool::new(false));
+
+    let reporter = Reporter {
+        sender,
+        cancelled: cancelled.clone(),
+    };
+
+    let task = Task::batch([
+        Task::run(receiver, on_progress),
+        Task::perform(job(reporter), on_finish),
+    ]);
+
+    let operation = Operation {
+        title,
+        progress: None,
+        cancelled,
+    };
+
+    (operation, task)
+}
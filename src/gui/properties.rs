@@ -0,0 +1,60 @@
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+use chrono::format::{Item, StrftimeItems};
+use chrono::{DateTime, Local};
+use cryptodoc::crypto::{inspect, FormatInfo};
+use cryptodoc::file::{load_file, FileError};
+
+use crate::gui::i18n::tr;
+
+#[derive(Debug, Clone)]
+pub struct DocumentProperties {
+    pub path: PathBuf,
+    pub size: u64,
+    pub created: Option<SystemTime>,
+    pub modified: Option<SystemTime>,
+    pub format: Option<FormatInfo>,
+}
+
+pub async fn load_properties(path: PathBuf) -> Result<DocumentProperties, FileError> {
+    let metadata = tokio::fs::metadata(&path)
+        .await
+        .map_err(|error| FileError::IOFailed(error.kind()))?;
+
+    let (path, contents) = load_file(path).await?;
+
+    let format = inspect(contents.as_bytes()).ok();
+
+    Ok(DocumentProperties {
+        path,
+        size: metadata.len(),
+        created: metadata.created().ok(),
+        modified: metadata.modified().ok(),
+        format,
+    })
+}
+
+pub fn format_timestamp(time: Option<SystemTime>) -> String {
+    time.map_or(tr("properties.unknown").to_string(), |time| {
+        DateTime::<Local>::from(time)
+            .format("%Y-%m-%d %H:%M:%S")
+            .to_string()
+    })
+}
+
+/// Formats the current time with a user-supplied strftime `format`, or returns
+/// `None` if the format contains an unknown specifier.
+pub fn format_now(format: &str) -> Option<String> {
+    let items: Vec<Item> = StrftimeItems::new(format).collect();
+
+    if items.iter().any(|item| matches!(item, Item::Error)) {
+        return None;
+    }
+
+    Some(
+        Local::now()
+            .format_with_items(items.into_iter())
+            .to_string(),
+    )
+}
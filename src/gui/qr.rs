@@ -0,0 +1,36 @@
+use iced::widget::image::Handle;
+use qrcode::{Color, QrCode};
+
+/// Pixels per module, so a code stays easy to scan off a screen.
+const SCALE: usize = 6;
+/// The blank margin scanners need around a code, in modules.
+const QUIET_ZONE: usize = 4;
+
+/// Draws `data` as a black-on-white QR code, or returns `None` if it's too
+/// long to fit in one.
+pub fn render(data: &str) -> Option<Handle> {
+    let code = QrCode::new(data.as_bytes()).ok()?;
+    let width = code.width();
+    let size = (width + 2 * QUIET_ZONE) * SCALE;
+
+    let mut pixels = vec![255; size * size * 4];
+
+    for (index, color) in code.to_colors().into_iter().enumerate() {
+        if color != Color::Dark {
+            continue;
+        }
+
+        let x = (index % width + QUIET_ZONE) * SCALE;
+        let y = (index / width + QUIET_ZONE) * SCALE;
+
+        for row in y..y + SCALE {
+            let start = (row * size + x) * 4;
+
+            for pixel in pixels[start..start + SCALE * 4].chunks_mut(4) {
+                pixel[..3].fill(0);
+            }
+        }
+    }
+
+    Some(Handle::from_rgba(size as u32, size as u32, pixels))
+}
@@ -0,0 +1,47 @@
+use std::io;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use cryptodoc::file::FileError;
+use serde::{Deserialize, Serialize};
+
+use crate::gui::dialogs::save_file;
+
+/// How often unsaved edits are written to the journal.
+pub const INTERVAL: Duration = Duration::from_secs(30);
+
+/// Unsaved edits written out periodically so they survive a crash. The text
+/// is encrypted with the document's own password, so the journal is no
+/// easier to read than the document itself.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Recovery {
+    /// Where the document will be saved.
+    pub path: PathBuf,
+    pub encrypted: String,
+}
+
+fn get_recovery_file_path() -> PathBuf {
+    PathBuf::from("./recovery.json")
+}
+
+pub fn load() -> Option<Recovery> {
+    std::fs::read_to_string(get_recovery_file_path())
+        .ok()
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+}
+
+pub async fn save(recovery: Recovery) -> Result<PathBuf, FileError> {
+    let contents = serde_json::to_string(&recovery).expect("Failed to serialize recovery");
+
+    save_file(Some(get_recovery_file_path()), contents).await
+}
+
+/// Removes the journal once the edits in it are safely saved or unwanted.
+pub async fn discard() -> Result<(), FileError> {
+    match tokio::fs::remove_file(get_recovery_file_path()).await {
+        Err(error) if error.kind() != io::ErrorKind::NotFound => {
+            Err(FileError::IOFailed(error.kind()))
+        }
+        _ => Ok(()),
+    }
+}
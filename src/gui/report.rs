@@ -0,0 +1,168 @@
+use std::fmt;
+use std::path::PathBuf;
+
+use cryptodoc::file::{document_name, list_documents, FileError};
+
+use crate::gui::i18n::tr;
+use crate::gui::index::{Label, VaultIndex};
+use crate::gui::progress::Reporter;
+use crate::gui::properties::{format_timestamp, load_properties};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    Csv,
+    Markdown,
+}
+
+impl ReportFormat {
+    pub const ALL: &'static [Self] = &[Self::Csv, Self::Markdown];
+
+    fn extension(self) -> &'static str {
+        match self {
+            ReportFormat::Csv => "csv",
+            ReportFormat::Markdown => "md",
+        }
+    }
+}
+
+impl fmt::Display for ReportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReportFormat::Csv => "CSV",
+            ReportFormat::Markdown => "Markdown",
+        }
+        .fmt(f)
+    }
+}
+
+/// Builds an inventory of every document in `folder` from file metadata and
+/// the unencrypted format header. No document is ever decrypted.
+pub async fn build_report(
+    folder: PathBuf,
+    index: VaultIndex,
+    format: ReportFormat,
+    reporter: Reporter,
+) -> Result<String, FileError> {
+    let headers = [
+        tr("report.name"),
+        tr("properties.size"),
+        tr("properties.created"),
+        tr("properties.modified"),
+        tr("properties.label"),
+        tr("properties.version"),
+    ];
+
+    let mut rows = vec![];
+
+    let documents = list_documents(folder).await?;
+    let total = documents.len();
+
+    for (done, document) in documents.into_iter().enumerate() {
+        if reporter.is_cancelled() {
+            return Err(FileError::Cancelled);
+        }
+
+        reporter.report(done, total);
+
+        let properties = load_properties(document.path.clone()).await?;
+
+        let label = index
+            .meta(&document.path)
+            .map(|meta| {
+                [
+                    meta.color.map(|color| Label(color).to_string()),
+                    meta.emoji.clone(),
+                ]
+                .into_iter()
+                .flatten()
+                .collect::<Vec<_>>()
+                .join(" ")
+            })
+            .unwrap_or_default();
+
+        rows.push([
+            document_name(&document.path),
+            properties.size.to_string(),
+            format_timestamp(properties.created),
+            format_timestamp(properties.modified),
+            label,
+            properties
+                .format
+                .map_or(tr("properties.unrecognized").to_string(), |format| {
+                    format.version.to_string()
+                }),
+        ]);
+    }
+
+    Ok(match format {
+        ReportFormat::Csv => to_csv(&headers, &rows),
+        ReportFormat::Markdown => to_markdown(&headers, &rows),
+    })
+}
+
+pub async fn save_report(format: ReportFormat, report: String) -> Result<PathBuf, FileError> {
+    let path = rfd::AsyncFileDialog::new()
+        .set_title(tr("dialog.save_report"))
+        .set_file_name(format!("cryptodoc-report.{}", format.extension()))
+        .add_filter(format.to_string(), &[format.extension()])
+        .save_file()
+        .await
+        .ok_or(FileError::DialogClosed)
+        .map(|handle| handle.path().to_owned())?;
+
+    tokio::fs::write(&path, report)
+        .await
+        .map_err(|error| FileError::IOFailed(error.kind()))?;
+
+    Ok(path)
+}
+
+fn to_csv<const N: usize>(headers: &[&str; N], rows: &[[String; N]]) -> String {
+    let line = |fields: Vec<&str>| {
+        fields
+            .into_iter()
+            .map(csv_field)
+            .collect::<Vec<_>>()
+            .join(",")
+    };
+
+    let mut csv = line(headers.to_vec());
+
+    for row in rows {
+        csv.push_str("\r\n");
+        csv.push_str(&line(row.iter().map(String::as_str).collect()));
+    }
+
+    csv
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn to_markdown<const N: usize>(headers: &[&str; N], rows: &[[String; N]]) -> String {
+    let line = |fields: Vec<&str>| {
+        let cells: Vec<String> = fields
+            .into_iter()
+            .map(|field| field.replace('|', "\\|").replace('\n', " "))
+            .collect();
+
+        format!("| {} |", cells.join(" | "))
+    };
+
+    let mut markdown = line(headers.to_vec());
+    markdown.push('\n');
+    markdown.push_str(&line(vec!["---"; N]));
+
+    for row in rows {
+        markdown.push('\n');
+        markdown.push_str(&line(row.iter().map(String::as_str).collect()));
+    }
+
+    markdown.push('\n');
+    markdown
+}
@@ -0,0 +1,111 @@
+//! How long a document's plaintext may stay in memory once it's closed or
+//! the app is locked, so opening it again right away skips decrypting it.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use crypto::util::fixed_time_eq;
+use cryptodoc::Document;
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroize;
+
+use crate::gui::i18n::tr;
+use crate::gui::modified_time;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Retention {
+    /// Wiped as soon as the document is closed.
+    #[default]
+    Immediate,
+    OneMinute,
+    FiveMinutes,
+    FifteenMinutes,
+}
+
+impl Retention {
+    pub const ALL: &'static [Self] = &[
+        Self::Immediate,
+        Self::OneMinute,
+        Self::FiveMinutes,
+        Self::FifteenMinutes,
+    ];
+
+    /// How long to keep a closed document, or `None` not to.
+    pub fn duration(self) -> Option<Duration> {
+        match self {
+            Retention::Immediate => None,
+            Retention::OneMinute => Some(Duration::from_secs(60)),
+            Retention::FiveMinutes => Some(Duration::from_secs(5 * 60)),
+            Retention::FifteenMinutes => Some(Duration::from_secs(15 * 60)),
+        }
+    }
+}
+
+impl fmt::Display for Retention {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Retention::Immediate => tr("retention.immediate"),
+            Retention::OneMinute => tr("retention.one_minute"),
+            Retention::FiveMinutes => tr("retention.five_minutes"),
+            Retention::FifteenMinutes => tr("retention.fifteen_minutes"),
+        }
+        .fmt(f)
+    }
+}
+
+/// A closed document, kept until its [`Retention`] runs out. Its text and
+/// password are overwritten when it's dropped.
+pub struct Retained {
+    path: PathBuf,
+    /// When the file was last written, so a document changed on disk since
+    /// is decrypted again instead.
+    modified: SystemTime,
+    password: String,
+    document: Document,
+    /// Tells the timer wiping this document apart from one for a document
+    /// kept after it.
+    pub generation: u64,
+}
+
+impl Retained {
+    pub fn new(
+        path: PathBuf,
+        modified: SystemTime,
+        password: String,
+        document: Document,
+        generation: u64,
+    ) -> Self {
+        Self {
+            path,
+            modified,
+            password,
+            document,
+            generation,
+        }
+    }
+
+    /// Whether this is the document at `path`, unchanged, and `password` is
+    /// its password.
+    pub fn matches(&self, path: &Path, password: &str) -> bool {
+        self.path == path
+            && fixed_time_eq(self.password.as_bytes(), password.as_bytes())
+            && modified_time(path) == Some(self.modified)
+    }
+
+    pub fn into_document(mut self) -> Document {
+        let text = std::mem::take(&mut self.document.text);
+
+        Document {
+            text,
+            ..self.document.clone()
+        }
+    }
+}
+
+impl Drop for Retained {
+    fn drop(&mut self) {
+        self.password.zeroize();
+        self.document.text.zeroize();
+    }
+}
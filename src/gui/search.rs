@@ -0,0 +1,84 @@
+use std::path::PathBuf;
+
+use cryptodoc::file::{list_documents, FileError};
+use cryptodoc::Document;
+
+use crate::gui::progress::Reporter;
+
+const SNIPPET_RADIUS: usize = 40;
+
+#[derive(Debug, Clone)]
+pub struct SearchHit {
+    pub path: PathBuf,
+    pub line: usize,
+    pub snippet: String,
+}
+
+/// Decrypts every document in `folder` with `password` and collects the lines
+/// matching `query`. Documents that fail to decrypt are skipped, so a vault with
+/// mixed passwords only reports hits for the ones `password` unlocks.
+pub async fn search_documents(
+    folder: PathBuf,
+    password: String,
+    query: String,
+    reporter: Reporter,
+) -> Result<Vec<SearchHit>, FileError> {
+    let needle = query.to_lowercase();
+    let mut hits = vec![];
+
+    if needle.is_empty() {
+        return Ok(hits);
+    }
+
+    let documents = list_documents(folder).await?;
+    let total = documents.len();
+
+    for (done, document) in documents.into_iter().enumerate() {
+        if reporter.is_cancelled() {
+            return Err(FileError::Cancelled);
+        }
+
+        reporter.report(done, total);
+
+        let path = document.path;
+
+        let Ok(document) = Document::open(path.clone(), &password).await else {
+            continue;
+        };
+
+        for (index, line) in document.text.lines().enumerate() {
+            if let Some(snippet) = snippet(line, &needle) {
+                hits.push(SearchHit {
+                    path: path.clone(),
+                    line: index + 1,
+                    snippet,
+                });
+            }
+        }
+    }
+
+    Ok(hits)
+}
+
+fn snippet(line: &str, needle: &str) -> Option<String> {
+    let lower = line.to_lowercase();
+    let start = lower.find(needle)?;
+
+    // Lowercasing can change byte lengths, so work in chars from here on.
+    let chars: Vec<char> = line.chars().collect();
+    let match_start = lower[..start].chars().count().min(chars.len());
+    let from = match_start.saturating_sub(SNIPPET_RADIUS);
+    let to = (match_start + needle.chars().count() + SNIPPET_RADIUS).min(chars.len());
+
+    let mut snippet: String = chars[from..to].iter().collect();
+
+    if from > 0 {
+        snippet.insert_str(0, "...");
+    }
+
+    if to < chars.len() {
+        snippet.push_str("...");
+    }
+
+    Some(snippet.trim().to_string())
+}
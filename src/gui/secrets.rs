@@ -0,0 +1,161 @@
+use std::collections::HashSet;
+
+use cryptodoc::table::{SecretRow, SecretTable};
+use cryptodoc::totp::Totp;
+use iced::widget::{button, column, container, row, scrollable, text, text_input};
+use iced::{Alignment, Element, Font, Length};
+
+use crate::gui::i18n::{tr, tr_with};
+use crate::gui::Message;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    Label,
+    Username,
+    Secret,
+    Totp,
+    Notes,
+}
+
+impl Field {
+    pub fn get(self, row: &SecretRow) -> &str {
+        match self {
+            Field::Label => &row.label,
+            Field::Username => &row.username,
+            Field::Secret => &row.secret,
+            Field::Totp => &row.totp,
+            Field::Notes => &row.notes,
+        }
+    }
+
+    pub fn get_mut(self, row: &mut SecretRow) -> &mut String {
+        match self {
+            Field::Label => &mut row.label,
+            Field::Username => &mut row.username,
+            Field::Secret => &mut row.secret,
+            Field::Totp => &mut row.totp,
+            Field::Notes => &mut row.notes,
+        }
+    }
+
+    fn placeholder(self) -> &'static str {
+        match self {
+            Field::Label => tr("table.label"),
+            Field::Username => tr("table.username"),
+            Field::Secret => tr("table.secret"),
+            Field::Totp => tr("table.totp"),
+            Field::Notes => tr("table.notes"),
+        }
+    }
+}
+
+/// The table editor. Secrets stay hidden unless their row is in `revealed`.
+pub fn view<'a>(table: &'a SecretTable, revealed: &HashSet<usize>) -> Element<'a, Message> {
+    let header = [
+        Field::Label,
+        Field::Username,
+        Field::Secret,
+        Field::Totp,
+        Field::Notes,
+    ]
+    .into_iter()
+    .fold(row![].spacing(10), |header, field| {
+        header.push(text(field.placeholder()).size(14).width(width(field)))
+    });
+
+    let rows =
+        table
+            .rows
+            .iter()
+            .enumerate()
+            .fold(column![].spacing(5), |rows, (index, secret_row)| {
+                rows.push(table_row(index, secret_row, revealed.contains(&index)))
+            });
+
+    let add_btn = button(tr("table.add_row")).on_press(Message::TableRowAdded);
+
+    column![header, scrollable(rows).height(Length::Fill), add_btn]
+        .spacing(10)
+        .into()
+}
+
+fn table_row(index: usize, secret_row: &SecretRow, revealed: bool) -> Element<Message> {
+    let cell = |field: Field| {
+        text_input(field.placeholder(), field.get(secret_row))
+            .on_input(move |value| Message::TableCellEdited(index, field, value))
+            .padding([5, 10])
+            .width(width(field))
+    };
+
+    let copy = |field: Field| {
+        button(text(tr("table.copy")).size(12))
+            .style(button::secondary)
+            .padding([2, 6])
+            .on_press_maybe(
+                (!field.get(secret_row).is_empty())
+                    .then_some(Message::TableCellCopied(index, field)),
+            )
+    };
+
+    let reveal_btn = button(
+        text(if revealed {
+            tr("table.hide")
+        } else {
+            tr("table.reveal")
+        })
+        .size(12),
+    )
+    .style(button::secondary)
+    .padding([2, 6])
+    .on_press(Message::SecretRevealToggled(index));
+
+    let remove_btn = button(text("×").size(14))
+        .style(button::secondary)
+        .padding([2, 8])
+        .on_press(Message::TableRowRemoved(index));
+
+    container(
+        row![
+            cell(Field::Label),
+            column![cell(Field::Username), copy(Field::Username)].spacing(2),
+            column![
+                cell(Field::Secret).secure(!revealed),
+                row![reveal_btn, copy(Field::Secret)].spacing(5)
+            ]
+            .spacing(2),
+            column![cell(Field::Totp).secure(!revealed)]
+                .push_maybe(Totp::parse(&secret_row.totp).map(|totp| code(&totp)))
+                .spacing(2),
+            cell(Field::Notes),
+            remove_btn,
+        ]
+        .spacing(10)
+        .align_items(Alignment::Start),
+    )
+    .into()
+}
+
+/// The current code of a two-factor seed, with the seconds it has left and
+/// a button to copy it.
+pub fn code<'a>(totp: &Totp) -> Element<'a, Message> {
+    let (code, remaining) = totp.now();
+
+    row![
+        text(code.clone()).font(Font::MONOSPACE),
+        text(tr_with("totp.remaining", remaining)).size(12),
+        button(text(tr("table.copy")).size(12))
+            .style(button::secondary)
+            .padding([2, 6])
+            .on_press(Message::TotpCopied(code)),
+    ]
+    .spacing(5)
+    .align_items(Alignment::Center)
+    .into()
+}
+
+fn width(field: Field) -> Length {
+    match field {
+        Field::Notes => Length::Fill,
+        _ => Length::Fixed(180.0),
+    }
+}
@@ -0,0 +1,84 @@
+use std::time::{Duration, SystemTime};
+
+use iced::futures::SinkExt;
+use iced::Subscription;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+// A tick that arrives this much later than scheduled means the process was
+// frozen, which in practice only happens while the machine is asleep.
+const SUSPEND_THRESHOLD: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionEvent {
+    ScreenLocked,
+    Resumed,
+}
+
+/// Watches for the screen being locked and for the system waking up from
+/// suspend. Neither is reported by the windowing layer, so both are polled.
+pub fn events() -> Subscription<SessionEvent> {
+    struct Events;
+
+    iced::subscription::channel(
+        std::any::TypeId::of::<Events>(),
+        16,
+        |mut output| async move {
+            let mut last_tick = SystemTime::now();
+            let mut was_locked = false;
+
+            loop {
+                tokio::time::sleep(POLL_INTERVAL).await;
+
+                let now = SystemTime::now();
+                let elapsed = now.duration_since(last_tick).unwrap_or_default();
+                last_tick = now;
+
+                if elapsed > POLL_INTERVAL + SUSPEND_THRESHOLD {
+                    let _ = output.send(SessionEvent::Resumed).await;
+                }
+
+                let is_locked = tokio::task::spawn_blocking(screen_locked)
+                    .await
+                    .unwrap_or(false);
+
+                if is_locked && !was_locked {
+                    let _ = output.send(SessionEvent::ScreenLocked).await;
+                }
+
+                was_locked = is_locked;
+            }
+        },
+    )
+}
+
+#[cfg(target_os = "linux")]
+fn screen_locked() -> bool {
+    // logind tracks the lock state of every session; desktop environments set
+    // it when their lock screen comes up.
+    std::process::Command::new("loginctl")
+        .args(["show-session", "auto", "--property=LockedHint", "--value"])
+        .output()
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim() == "yes")
+        .unwrap_or(false)
+}
+
+#[cfg(target_os = "windows")]
+fn screen_locked() -> bool {
+    use std::os::windows::process::CommandExt;
+
+    const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+    // LogonUI only runs while the lock screen (or login screen) is shown.
+    std::process::Command::new("tasklist")
+        .args(["/FI", "IMAGENAME eq LogonUI.exe", "/NH"])
+        .creation_flags(CREATE_NO_WINDOW)
+        .output()
+        .map(|output| String::from_utf8_lossy(&output.stdout).contains("LogonUI.exe"))
+        .unwrap_or(false)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "windows")))]
+fn screen_locked() -> bool {
+    false
+}
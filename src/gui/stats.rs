@@ -0,0 +1,56 @@
+// A typical silent reading speed for prose.
+const WORDS_PER_MINUTE: usize = 200;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TextStats {
+    pub words: usize,
+    pub sentences: usize,
+    pub paragraphs: usize,
+    pub average_word_length: f32,
+    pub reading_minutes: usize,
+}
+
+impl TextStats {
+    pub fn new(text: &str) -> Self {
+        let words: Vec<&str> = text
+            .split_whitespace()
+            .filter(|word| word.chars().any(char::is_alphanumeric))
+            .collect();
+
+        let letters: usize = words
+            .iter()
+            .map(|word| word.chars().filter(|c| c.is_alphanumeric()).count())
+            .sum();
+
+        // Runs of terminators ("?!", "...") end a single sentence.
+        let sentences = text
+            .split(['.', '!', '?'])
+            .filter(|sentence| sentence.chars().any(char::is_alphanumeric))
+            .count();
+
+        let mut paragraphs = 0;
+        let mut in_paragraph = false;
+
+        for line in text.lines() {
+            let is_blank = line.trim().is_empty();
+
+            if !is_blank && !in_paragraph {
+                paragraphs += 1;
+            }
+
+            in_paragraph = !is_blank;
+        }
+
+        Self {
+            words: words.len(),
+            sentences,
+            paragraphs,
+            average_word_length: if words.is_empty() {
+                0.0
+            } else {
+                letters as f32 / words.len() as f32
+            },
+            reading_minutes: words.len().div_ceil(WORDS_PER_MINUTE),
+        }
+    }
+}
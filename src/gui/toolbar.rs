@@ -0,0 +1,90 @@
+//! Keyboard focus for the toolbar. iced's buttons can't be focused, so which
+//! action has focus is kept by the app: F6 moves focus to the toolbar, Tab
+//! and Shift+Tab walk its actions and Enter or Space presses the focused one.
+
+use iced::widget::container;
+use iced::{Border, Element, Theme};
+
+use crate::gui::Message;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToolbarAction {
+    Home,
+    New,
+    Open,
+    Save,
+    Settings,
+}
+
+impl ToolbarAction {
+    /// The actions in the order they're shown, and tabbed through.
+    pub const ALL: &'static [Self] = &[
+        Self::Home,
+        Self::New,
+        Self::Open,
+        Self::Save,
+        Self::Settings,
+    ];
+
+    pub fn message(self) -> Message {
+        match self {
+            ToolbarAction::Home => Message::HomePressed,
+            ToolbarAction::New => Message::NewDocumentPressed,
+            ToolbarAction::Open => Message::OpenDocumentPressed,
+            ToolbarAction::Save => Message::SaveDocumentPressed,
+            ToolbarAction::Settings => Message::SettingsPressed,
+        }
+    }
+
+    /// The action after this one, or `None` past the last, when focus moves
+    /// on to the page. Actions `enabled` rejects are skipped.
+    pub fn next(current: Option<Self>, enabled: impl Fn(Self) -> bool) -> Option<Self> {
+        let start = current.map_or(0, |current| position(current) + 1);
+
+        Self::ALL[start..]
+            .iter()
+            .copied()
+            .find(|&action| enabled(action))
+    }
+
+    /// The action before this one, or `None` before the first.
+    pub fn previous(current: Option<Self>, enabled: impl Fn(Self) -> bool) -> Option<Self> {
+        let end = current.map_or(Self::ALL.len(), position);
+
+        Self::ALL[..end]
+            .iter()
+            .rev()
+            .copied()
+            .find(|&action| enabled(action))
+    }
+}
+
+fn position(action: ToolbarAction) -> usize {
+    ToolbarAction::ALL
+        .iter()
+        .position(|&other| other == action)
+        .expect("Every action is in ALL")
+}
+
+/// `content` with an outline drawn around it while it has keyboard focus.
+pub fn outline<'a>(content: Element<'a, Message>, focused: bool) -> Element<'a, Message> {
+    container(content)
+        .padding(2)
+        .style(move |theme: &Theme| {
+            let color = if focused {
+                theme.extended_palette().primary.strong.color
+            } else {
+                iced::Color::TRANSPARENT
+            };
+
+            container::Style {
+                border: Border {
+                    color,
+                    width: 2.0,
+                    radius: 4.0.into(),
+                },
+                ..container::Style::default()
+            }
+        })
+        .into()
+}
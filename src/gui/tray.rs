@@ -0,0 +1,125 @@
+use iced::futures::SinkExt;
+use iced::Subscription;
+use tray_icon::menu::{Menu, MenuEvent, MenuItem, PredefinedMenuItem};
+use tray_icon::{Icon, TrayIcon, TrayIconBuilder};
+
+use crate::gui::i18n::tr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrayAction {
+    Show,
+    Lock,
+    OpenRecent,
+    NewDocument,
+    EncryptClipboard,
+    DecryptClipboard,
+    Quit,
+}
+
+impl TrayAction {
+    const ALL: [Self; 7] = [
+        Self::Show,
+        Self::Lock,
+        Self::OpenRecent,
+        Self::NewDocument,
+        Self::EncryptClipboard,
+        Self::DecryptClipboard,
+        Self::Quit,
+    ];
+
+    fn id(self) -> &'static str {
+        match self {
+            TrayAction::Show => "show",
+            TrayAction::Lock => "lock",
+            TrayAction::OpenRecent => "open-recent",
+            TrayAction::NewDocument => "new-document",
+            TrayAction::EncryptClipboard => "encrypt-clipboard",
+            TrayAction::DecryptClipboard => "decrypt-clipboard",
+            TrayAction::Quit => "quit",
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            TrayAction::Show => tr("tray.show"),
+            TrayAction::Lock => tr("tray.lock"),
+            TrayAction::OpenRecent => tr("tray.open_recent"),
+            TrayAction::NewDocument => tr("tray.new_document"),
+            TrayAction::EncryptClipboard => tr("tray.encrypt_clipboard"),
+            TrayAction::DecryptClipboard => tr("tray.decrypt_clipboard"),
+            TrayAction::Quit => tr("tray.quit"),
+        }
+    }
+
+    fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|action| action.id() == id)
+    }
+}
+
+/// Creates the tray icon. On Linux it lives on its own GTK thread and `None` is
+/// returned; elsewhere the returned handle must be kept alive while the app runs.
+#[cfg(target_os = "linux")]
+pub fn spawn(rgba: Vec<u8>, width: u32, height: u32) -> Option<TrayIcon> {
+    std::thread::spawn(move || {
+        if gtk::init().is_err() {
+            return;
+        }
+
+        if let Some(_tray) = build(rgba, width, height) {
+            gtk::main();
+        }
+    });
+
+    None
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn spawn(rgba: Vec<u8>, width: u32, height: u32) -> Option<TrayIcon> {
+    build(rgba, width, height)
+}
+
+fn build(rgba: Vec<u8>, width: u32, height: u32) -> Option<TrayIcon> {
+    let menu = Menu::new();
+
+    for action in TrayAction::ALL {
+        if matches!(action, TrayAction::EncryptClipboard | TrayAction::Quit) {
+            menu.append(&PredefinedMenuItem::separator()).ok()?;
+        }
+
+        menu.append(&MenuItem::with_id(action.id(), action.label(), true, None))
+            .ok()?;
+    }
+
+    let icon = Icon::from_rgba(rgba, width, height).ok()?;
+
+    TrayIconBuilder::new()
+        .with_menu(Box::new(menu))
+        .with_tooltip("CryptoDoc")
+        .with_icon(icon)
+        .build()
+        .ok()
+}
+
+pub fn events() -> Subscription<TrayAction> {
+    struct Events;
+
+    iced::subscription::channel(
+        std::any::TypeId::of::<Events>(),
+        16,
+        |mut output| async move {
+            loop {
+                let event = tokio::task::spawn_blocking(|| MenuEvent::receiver().recv()).await;
+
+                match event {
+                    Ok(Ok(event)) => {
+                        if let Some(action) = TrayAction::from_id(event.id.as_ref()) {
+                            let _ = output.send(action).await;
+                        }
+                    }
+                    // The menu is gone, so there is nothing left to listen for.
+                    _ => std::future::pending::<()>().await,
+                }
+            }
+        },
+    )
+}
@@ -0,0 +1,88 @@
+use std::fmt;
+
+use iced::futures::SinkExt;
+use iced::Subscription;
+use semver::Version;
+use serde::{Deserialize, Serialize};
+
+use crate::gui::i18n::tr;
+
+const RELEASES_URL: &str = "https://api.github.com/repos/acatiadroid/cryptodoc/releases";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ReleaseChannel {
+    #[default]
+    Stable,
+    PreRelease,
+}
+
+impl ReleaseChannel {
+    pub const ALL: &'static [Self] = &[Self::Stable, Self::PreRelease];
+}
+
+impl fmt::Display for ReleaseChannel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReleaseChannel::Stable => tr("update.stable"),
+            ReleaseChannel::PreRelease => tr("update.pre_release"),
+        }
+        .fmt(f)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Release {
+    pub tag_name: String,
+    pub html_url: String,
+    #[serde(default)]
+    pub prerelease: bool,
+    #[serde(default)]
+    pub draft: bool,
+}
+
+impl Release {
+    fn version(&self) -> Option<Version> {
+        Version::parse(self.tag_name.trim_start_matches('v')).ok()
+    }
+}
+
+/// Checks once for a release newer than this build on `channel`, then stays
+/// idle. The request only reveals that CryptoDoc is in use, nothing about any
+/// document.
+pub fn check(channel: ReleaseChannel) -> Subscription<Result<Option<Release>, String>> {
+    struct Check;
+
+    iced::subscription::channel(
+        (std::any::TypeId::of::<Check>(), channel),
+        1,
+        move |mut output| async move {
+            let _ = output.send(newer_release(channel).await).await;
+
+            std::future::pending().await
+        },
+    )
+}
+
+async fn newer_release(channel: ReleaseChannel) -> Result<Option<Release>, String> {
+    let current = Version::parse(env!("CARGO_PKG_VERSION")).map_err(|error| error.to_string())?;
+
+    let releases: Vec<Release> = reqwest::Client::new()
+        .get(RELEASES_URL)
+        .header(reqwest::header::USER_AGENT, "cryptodoc")
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|error| error.to_string())?
+        .json()
+        .await
+        .map_err(|error| error.to_string())?;
+
+    Ok(releases
+        .into_iter()
+        .filter(|release| !release.draft)
+        .filter(|release| channel == ReleaseChannel::PreRelease || !release.prerelease)
+        .filter_map(|release| Some((release.version()?, release)))
+        .filter(|(version, _)| version > &current)
+        .max_by(|(a, _), (b, _)| a.cmp(b))
+        .map(|(_, release)| release))
+}
@@ -0,0 +1,128 @@
+//! Switching between vault profiles, see
+//! [`VaultProfile`](crate::gui::config::VaultProfile).
+
+use std::io;
+
+use crypto::pbkdf2::{pbkdf2_check, pbkdf2_simple};
+use cryptodoc::crypto::KDF_ITERATIONS;
+use cryptodoc::file::FileError;
+use iced::widget::{button, column, container, horizontal_space, pick_list, row, text, text_input};
+use iced::{Color, Element};
+
+use crate::gui::config::Config;
+use crate::gui::i18n::{tr, tr_with};
+use crate::gui::Message;
+
+/// Asks for the password of a vault profile before switching to it.
+pub struct VaultPrompt {
+    pub name: String,
+    pub hash: String,
+    pub password: String,
+    pub error: Option<String>,
+    pub checking: bool,
+    /// Whether the active profile is being removed, rather than only left.
+    pub remove_current: bool,
+}
+
+/// A hash of `password` to keep with a vault profile, or `None` to remove
+/// the password if it's empty.
+pub async fn hash_password(password: String) -> Result<Option<String>, FileError> {
+    if password.is_empty() {
+        return Ok(None);
+    }
+
+    tokio::task::spawn_blocking(move || pbkdf2_simple(&password, KDF_ITERATIONS))
+        .await
+        .map_err(|_| FileError::IOFailed(io::ErrorKind::Other))?
+        .map(Some)
+        .map_err(|error| FileError::IOFailed(error.kind()))
+}
+
+pub async fn check_password(password: String, hash: String) -> bool {
+    tokio::task::spawn_blocking(move || pbkdf2_check(&password, &hash).unwrap_or(false))
+        .await
+        .unwrap_or(false)
+}
+
+/// The vault profile list for the toolbar, once there's more than one.
+pub fn switcher(config: &Config) -> Option<Element<Message>> {
+    if config.vaults.is_empty() {
+        return None;
+    }
+
+    Some(
+        pick_list(
+            config.vault_names(),
+            Some(config.vault_name.clone()),
+            Message::VaultSelected,
+        )
+        .text_size(14)
+        .padding([5, 10])
+        .into(),
+    )
+}
+
+pub fn prompt_view(prompt: &VaultPrompt) -> Element<Message> {
+    let title = text(tr_with("vaults.prompt_title", &prompt.name));
+
+    let pass_input = text_input(tr("common.password"), &prompt.password)
+        .id(vault_prompt_id())
+        .padding(10)
+        .on_input(Message::VaultPasswordInput)
+        .on_submit(Message::VaultPasswordSubmitted)
+        .secure(true);
+
+    let error = prompt
+        .error
+        .as_ref()
+        .map(|error| text(error.as_str()).color(Color::from_rgb(0.9, 0.3, 0.3)));
+
+    let buttons = row![
+        horizontal_space(),
+        button(tr("prompt.cancel"))
+            .style(button::secondary)
+            .on_press(Message::VaultPromptCancelled),
+        button(tr("vaults.switch"))
+            .on_press_maybe((!prompt.checking).then_some(Message::VaultPasswordSubmitted)),
+    ]
+    .spacing(10);
+
+    container(
+        column![title, pass_input]
+            .push_maybe(error)
+            .push(buttons)
+            .spacing(10),
+    )
+    .width(400)
+    .padding(20)
+    .style(container::rounded_box)
+    .into()
+}
+
+/// Asks before removing the active profile `name`.
+pub fn remove_view(name: &str) -> Element<Message> {
+    let title = text(tr_with("vaults.remove_title", name));
+
+    let body = text(tr("vaults.remove_body")).size(14);
+
+    let buttons = row![
+        horizontal_space(),
+        button(tr("prompt.cancel"))
+            .style(button::secondary)
+            .on_press(Message::RemoveVaultCancelled),
+        button(tr("vaults.remove"))
+            .style(button::danger)
+            .on_press(Message::RemoveVaultConfirmed),
+    ]
+    .spacing(10);
+
+    container(column![title, body, buttons].spacing(10))
+        .width(400)
+        .padding(20)
+        .style(container::rounded_box)
+        .into()
+}
+
+pub fn vault_prompt_id() -> text_input::Id {
+    text_input::Id::new("vault-prompt")
+}
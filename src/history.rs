@@ -0,0 +1,140 @@
+//! Salted hashes of the passwords a document had before, so changing back
+//! to one of them can be warned about.
+//!
+//! They're a line after the encrypted document, before any
+//! [failure record](crate::lockout):
+//!
+//! ```text
+//! previous:<iterations>/<salt>/<hash>/<salt>/<hash>...
+//! ```
+//!
+//! Each hash is PBKDF2 with a salt of its own and as many iterations as the
+//! document key, so guessing an old password from the line is no easier
+//! than guessing it from the document back when it had that password. The
+//! line isn't authenticated, as it's only there for a warning.
+
+use std::io::{self, Write};
+
+use crypto::util::fixed_time_eq;
+
+use crate::crypto::{derive_key, KDF_ITERATIONS};
+use crate::lockout;
+
+pub(crate) const LINE_PREFIX: &str = "previous:";
+
+/// Older passwords than this many are forgotten.
+pub const MAX_PASSWORDS: usize = 5;
+
+const SALT_SIZE: usize = 16;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PasswordHistory {
+    iterations: u32,
+    /// Salt and hash of each password, oldest first.
+    hashes: Vec<(Vec<u8>, Vec<u8>)>,
+}
+
+impl PasswordHistory {
+    pub fn is_empty(&self) -> bool {
+        self.hashes.is_empty()
+    }
+
+    /// Whether `password` is one the document had before. Each remembered
+    /// password takes a key derivation to check.
+    pub fn contains(&self, password: &str) -> bool {
+        self.hashes
+            .iter()
+            .any(|(salt, hash)| fixed_time_eq(&derive_key(password, salt, self.iterations), hash))
+    }
+
+    /// Adds `password`, the one the document is being changed from.
+    pub fn remember(&mut self, password: &str) {
+        if self.contains(password) {
+            return;
+        }
+
+        if self.hashes.is_empty() {
+            self.iterations = KDF_ITERATIONS;
+        }
+
+        let salt: [u8; SALT_SIZE] = rand::random();
+        let hash = derive_key(password, &salt, self.iterations);
+
+        self.hashes.push((salt.to_vec(), hash));
+
+        if self.hashes.len() > MAX_PASSWORDS {
+            self.hashes.remove(0);
+        }
+    }
+
+    /// Writes the line after a document, unless there's nothing to
+    /// remember.
+    pub fn write_to(&self, writer: &mut impl Write) -> io::Result<()> {
+        if self.is_empty() {
+            return Ok(());
+        }
+
+        write!(writer, "\n{}{}", LINE_PREFIX, self.iterations)?;
+
+        for (salt, hash) in &self.hashes {
+            write!(writer, "/{}/{}", hex::encode(salt), hex::encode(hash))?;
+        }
+
+        Ok(())
+    }
+
+    /// `encrypted` followed by the line.
+    pub fn append_to(&self, mut encrypted: String) -> String {
+        let mut line = vec![];
+        self.write_to(&mut line)
+            .expect("Writing to a Vec can't fail");
+
+        encrypted.push_str(std::str::from_utf8(&line).expect("The line is ASCII"));
+        encrypted
+    }
+}
+
+/// `contents` without the password history, for parsing the document
+/// itself. Any failure record has to be stripped first.
+pub(crate) fn strip(contents: &str) -> &str {
+    match contents.trim_end().rsplit_once('\n') {
+        Some((document, line)) if line.starts_with(LINE_PREFIX) => document,
+        _ => contents,
+    }
+}
+
+/// The password history in the contents of an encrypted document, or an
+/// empty one if it has none or it's damaged.
+pub fn read(contents: &str) -> PasswordHistory {
+    let contents = lockout::strip(contents);
+
+    contents
+        .trim_end()
+        .rsplit_once('\n')
+        .and_then(|(_, line)| parse(line.strip_prefix(LINE_PREFIX)?))
+        .unwrap_or_default()
+}
+
+fn parse(line: &str) -> Option<PasswordHistory> {
+    let mut parts = line.split('/');
+
+    let iterations = parts
+        .next()?
+        .parse()
+        .ok()
+        .filter(|&iterations| iterations > 0)?;
+    let parts: Vec<Vec<u8>> = parts
+        .map(|part| hex::decode(part).ok())
+        .collect::<Option<_>>()?;
+
+    if parts.is_empty() || parts.len() % 2 != 0 {
+        return None;
+    }
+
+    let hashes = parts
+        .chunks(2)
+        .map(|pair| (pair[0].clone(), pair[1].clone()))
+        .collect();
+
+    Some(PasswordHistory { iterations, hashes })
+}
@@ -0,0 +1,117 @@
+//! Per-document metadata kept next to the documents in a vault folder, like
+//! labels and tags. It never contains anything from inside a document.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::file::replace_with;
+
+const INDEX_FILE_NAME: &str = "index.json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LabelColor {
+    Red,
+    Orange,
+    Yellow,
+    Green,
+    Blue,
+    Purple,
+}
+
+impl LabelColor {
+    pub const ALL: &'static [Self] = &[
+        Self::Red,
+        Self::Orange,
+        Self::Yellow,
+        Self::Green,
+        Self::Blue,
+        Self::Purple,
+    ];
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DocumentMeta {
+    pub color: Option<LabelColor>,
+    pub emoji: Option<String>,
+    pub tags: Vec<String>,
+}
+
+impl DocumentMeta {
+    pub fn is_empty(&self) -> bool {
+        self == &DocumentMeta::default()
+    }
+
+    /// Adds `tag` unless the document already has it, ignoring case.
+    pub fn add_tag(&mut self, tag: &str) {
+        let tag = tag.trim();
+
+        if !tag.is_empty()
+            && !self
+                .tags
+                .iter()
+                .any(|existing| existing.to_lowercase() == tag.to_lowercase())
+        {
+            self.tags.push(tag.to_string());
+        }
+    }
+}
+
+/// The metadata of every document in a folder, kept in it so it travels
+/// with them.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VaultIndex {
+    pub documents: BTreeMap<String, DocumentMeta>,
+}
+
+impl VaultIndex {
+    pub fn load(folder: &Path) -> Self {
+        fs::read_to_string(index_path(folder))
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, folder: &Path) -> io::Result<()> {
+        let contents = self.to_json();
+
+        replace_with(&index_path(folder), |writer| {
+            writer.write_all(contents.as_bytes())
+        })
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("Failed to serialize index")
+    }
+
+    pub fn meta(&self, path: &Path) -> Option<&DocumentMeta> {
+        self.documents.get(&key(path))
+    }
+
+    pub fn update(&mut self, path: &Path, f: impl FnOnce(&mut DocumentMeta)) {
+        let key = key(path);
+        let meta = self.documents.entry(key.clone()).or_default();
+
+        f(meta);
+
+        if meta.is_empty() {
+            self.documents.remove(&key);
+        }
+    }
+}
+
+// Documents are keyed by file name so the index survives the folder moving.
+fn key(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+pub fn index_path(folder: &Path) -> PathBuf {
+    folder.join(INDEX_FILE_NAME)
+}
@@ -0,0 +1,48 @@
+//! The CryptoDoc document format and storage, without the GUI.
+//!
+//! A [`Vault`] is a folder of encrypted `.cryptodoc` files, and a [`Document`]
+//! is the decrypted text of one of them:
+//!
+//! ```no_run
+//! use cryptodoc::{Document, Vault};
+//!
+//! # async fn run() -> Result<(), cryptodoc::DocumentError> {
+//! let vault = Vault::new("/home/me/Documents/vault");
+//!
+//! let mut document = vault.open("notes", "hunter2").await?;
+//! document.text.push_str("\nAnother line.");
+//! vault.save("notes", &document, "hunter2").await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! The lower-level [`crypto`] module works on the encrypted file contents
+//! directly.
+
+pub mod armor;
+pub mod audit;
+pub mod bench;
+pub mod crypto;
+pub mod diff;
+pub mod document;
+pub mod file;
+pub mod formats;
+pub mod history;
+pub mod index;
+pub mod lockout;
+pub mod merge;
+pub mod migrate;
+pub mod mirror;
+#[cfg(feature = "openpgp")]
+pub mod openpgp;
+pub mod redact;
+pub mod selftest;
+pub mod share;
+pub mod storage;
+pub mod table;
+pub mod text;
+pub mod totp;
+pub mod vault;
+
+pub use document::{Container, Document, DocumentError};
+pub use vault::Vault;
@@ -0,0 +1,244 @@
+//! Failed unlock attempts recorded in the document itself, so the cool-down
+//! after too many of them survives restarting the app, unlike the count in
+//! the password prompt.
+//!
+//! The record is a last line after the encrypted document:
+//!
+//! ```text
+//! failed:<count>/<unix time of the last one>/<tag>
+//! ```
+//!
+//! It can't be encrypted, since a failed attempt is exactly when there's no
+//! key. Instead the tag is an HMAC with a key kept on this device, over the
+//! record and the document's own MAC. A record that doesn't check out is
+//! ignored, so someone who can only write the file can't lock its owner
+//! out, or copy a record over from another document. Deleting the line
+//! resets the count, which is no worse than the key derivation alone: this
+//! slows down guessing in the app, not against a copy of the file.
+//!
+//! Only documents with a version header are recorded, as version 1 and
+//! OpenPGP files are read by other programs that wouldn't expect the line.
+
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+#[cfg(unix)]
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use chrono::{DateTime, TimeZone, Utc};
+use crypto::hmac::Hmac;
+use crypto::mac::{Mac, MacResult};
+use crypto::sha2::Sha256;
+
+use crate::crypto::format_version;
+use crate::file::{load_file, replace_with, FileError};
+use crate::history;
+use crate::{Document, DocumentError};
+
+pub(crate) const RECORD_PREFIX: &str = "failed:";
+
+/// Where the key for the tags is kept, next to the app's config.
+pub const KEY_PATH: &str = "./lockout.key";
+
+/// Failed attempts allowed before the document is locked.
+pub const MAX_FAILURES: u32 = 5;
+
+/// The cool-down after [`MAX_FAILURES`], doubling with each failure after
+/// it up to [`MAX_COOL_DOWN`].
+const FIRST_COOL_DOWN: Duration = Duration::from_secs(30);
+const MAX_COOL_DOWN: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Failures {
+    pub count: u32,
+    pub last: DateTime<Utc>,
+}
+
+impl Failures {
+    /// When the document can be tried again, if it's locked.
+    pub fn locked_until(&self) -> Option<DateTime<Utc>> {
+        let extra = self.count.checked_sub(MAX_FAILURES)?;
+        let cool_down = FIRST_COOL_DOWN
+            .checked_mul(1 << extra.min(16))
+            .map_or(MAX_COOL_DOWN, |cool_down| cool_down.min(MAX_COOL_DOWN));
+
+        // A record from the future would otherwise lock for longer.
+        let until = self.last.min(Utc::now()) + cool_down;
+
+        (until > Utc::now()).then_some(until)
+    }
+}
+
+/// `contents` without the failure record, for parsing the document itself.
+pub(crate) fn strip(contents: &str) -> &str {
+    match contents.trim_end().rsplit_once('\n') {
+        Some((document, record)) if record.starts_with(RECORD_PREFIX) => document,
+        _ => contents,
+    }
+}
+
+/// The failed attempts recorded in `contents`, if there are any that were
+/// recorded on this device.
+pub fn read(contents: &str) -> Option<Failures> {
+    let (document, record) = contents.trim_end().rsplit_once('\n')?;
+    let record = record.strip_prefix(RECORD_PREFIX)?;
+
+    let mut parts = record.split('/');
+    let (Some(count), Some(last), Some(tag), None) =
+        (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        return None;
+    };
+
+    let key = fs::read_to_string(KEY_PATH).ok()?;
+    let key = hex::decode(key.trim()).ok()?;
+    let tag = hex::decode(tag).ok()?;
+
+    if tag_for(&key, count, last, document) != MacResult::new(&tag) {
+        return None;
+    }
+
+    Some(Failures {
+        count: count.parse().ok()?,
+        last: Utc.timestamp_opt(last.parse().ok()?, 0).single()?,
+    })
+}
+
+/// Counts another failed attempt in the document at `path`.
+pub fn record_failure(path: &Path) -> io::Result<Failures> {
+    let contents = fs::read_to_string(path)?;
+    let document = strip(&contents).trim_end();
+
+    if format_version(document).map_err(|_| io::ErrorKind::InvalidData)? < 2 {
+        return Err(io::ErrorKind::Unsupported.into());
+    }
+
+    let failures = Failures {
+        count: read(&contents).map_or(0, |failures| failures.count) + 1,
+        last: Utc::now(),
+    };
+
+    let key = device_key()?;
+    let (count, last) = (
+        failures.count.to_string(),
+        failures.last.timestamp().to_string(),
+    );
+    let tag = tag_for(&key, &count, &last, document);
+
+    replace(
+        path,
+        format!(
+            "{}\n{}{}/{}/{}\n",
+            document,
+            RECORD_PREFIX,
+            count,
+            last,
+            hex::encode(tag.code())
+        ),
+    )?;
+
+    Ok(failures)
+}
+
+/// Removes the failure record from the document at `path`, once it's been
+/// unlocked.
+pub fn clear(path: &Path) -> io::Result<()> {
+    let contents = fs::read_to_string(path)?;
+    let document = strip(&contents);
+
+    if document.len() == contents.len() {
+        return Ok(());
+    }
+
+    replace(path, document.to_string())
+}
+
+/// Opens the document at `path` like [`Document::open`], unless failed
+/// attempts have locked it. A wrong password is recorded as another failed
+/// attempt, and the record is removed once the document opens, the same as
+/// the main password prompt does. Like there, not being able to write the
+/// record doesn't change the outcome; documents in older formats have
+/// nowhere to keep it anyway.
+pub async fn open(path: PathBuf, password: &str) -> Result<Document, DocumentError> {
+    let (_, encrypted) = load_file(path.clone()).await?;
+    let failures = read(&encrypted.tail());
+
+    if let Some(until) = failures.and_then(|failures| failures.locked_until()) {
+        return Err(DocumentError::Locked(until));
+    }
+
+    match Document::decrypt_in_background(encrypted, password.to_string()).await {
+        Ok(document) => {
+            if failures.is_some() {
+                let _ = clear_in_background(path).await;
+            }
+
+            Ok(document)
+        }
+        Err(DocumentError::WrongPassword) => {
+            let _ = record_failure_in_background(path).await;
+
+            Err(DocumentError::WrongPassword)
+        }
+        Err(error) => Err(error),
+    }
+}
+
+pub async fn record_failure_in_background(path: PathBuf) -> Result<Failures, FileError> {
+    tokio::task::spawn_blocking(move || record_failure(&path))
+        .await
+        .map_err(|_| FileError::IOFailed(io::ErrorKind::Other))?
+        .map_err(|error| FileError::IOFailed(error.kind()))
+}
+
+pub async fn clear_in_background(path: PathBuf) -> Result<(), FileError> {
+    tokio::task::spawn_blocking(move || clear(&path))
+        .await
+        .map_err(|_| FileError::IOFailed(io::ErrorKind::Other))?
+        .map_err(|error| FileError::IOFailed(error.kind()))
+}
+
+/// Ties the record to the document through its MAC, the last field of
+/// every format version.
+fn tag_for(key: &[u8], count: &str, last: &str, document: &str) -> MacResult {
+    let mac = history::strip(document)
+        .trim_end()
+        .rsplit('/')
+        .next()
+        .unwrap_or_default();
+
+    let mut hmac = Hmac::new(Sha256::new(), key);
+    hmac.input(format!("{}{}/{}/{}", RECORD_PREFIX, count, last, mac).as_bytes());
+
+    hmac.result()
+}
+
+fn device_key() -> io::Result<Vec<u8>> {
+    match fs::read_to_string(KEY_PATH) {
+        Ok(key) => hex::decode(key.trim()).map_err(|_| io::ErrorKind::InvalidData.into()),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => {
+            let key: [u8; 32] = rand::random();
+
+            // Only readable by this user, as anyone with the key could forge
+            // records.
+            let mut options = OpenOptions::new();
+            options.write(true).create_new(true);
+            #[cfg(unix)]
+            options.mode(0o600);
+
+            options
+                .open(KEY_PATH)?
+                .write_all(hex::encode(key).as_bytes())?;
+
+            Ok(key.to_vec())
+        }
+        Err(error) => Err(error),
+    }
+}
+
+/// Writes the document with the record changed, flushed to disk before it
+/// replaces the original, as it's usually the only copy.
+fn replace(path: &Path, contents: String) -> io::Result<()> {
+    replace_with(path, |writer| writer.write_all(contents.as_bytes()))
+}
@@ -0,0 +1,341 @@
+//! Three-way merging of two versions of a document edited apart, like on
+//! two machines between syncs, against the version they both started from.
+//!
+//! Changes on only one side, or the same change on both, are taken as they
+//! are. Where both sides changed the same lines differently there's a
+//! [`Conflict`] to resolve by picking one of the three versions.
+
+use std::ops::Range;
+
+use crate::diff::{diff, Hunk};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Chunk {
+    Merged(Vec<String>),
+    Conflict(Conflict),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Conflict {
+    pub base: Vec<String>,
+    pub ours: Vec<String>,
+    pub theirs: Vec<String>,
+    pub resolution: Option<Side>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Base,
+    Ours,
+    Theirs,
+}
+
+impl Conflict {
+    fn lines(&self, side: Side) -> &[String] {
+        match side {
+            Side::Base => &self.base,
+            Side::Ours => &self.ours,
+            Side::Theirs => &self.theirs,
+        }
+    }
+}
+
+/// Merges the changes from `base` to `ours` and from `base` to `theirs`.
+pub fn merge(base: &str, ours: &str, theirs: &str) -> Vec<Chunk> {
+    let ours_hunks = diff(base, ours);
+    let theirs_hunks = diff(base, theirs);
+
+    let base: Vec<&str> = base.lines().collect();
+    let ours: Vec<&str> = ours.lines().collect();
+    let theirs: Vec<&str> = theirs.lines().collect();
+
+    let mut hunks: Vec<(Side, &Hunk)> = ours_hunks
+        .iter()
+        .map(|hunk| (Side::Ours, hunk))
+        .chain(theirs_hunks.iter().map(|hunk| (Side::Theirs, hunk)))
+        .collect();
+    hunks.sort_by_key(|(_, hunk)| (hunk.old.start, hunk.old.end));
+
+    let mut chunks = vec![];
+    let mut offset = 0;
+    let mut hunks = hunks.into_iter().peekable();
+
+    while let Some((side, hunk)) = hunks.next() {
+        // Changes touching the same lines of the base, or right next to
+        // each other, are merged as one.
+        let mut group = vec![(side, hunk)];
+        let mut end = hunk.old.end;
+
+        while let Some((side, hunk)) = hunks.next_if(|(_, hunk)| hunk.old.start <= end) {
+            end = end.max(hunk.old.end);
+            group.push((side, hunk));
+        }
+
+        let start = group[0].1.old.start;
+        push_merged(&mut chunks, &base[offset..start]);
+        offset = end;
+
+        let our_lines = side_lines(&group, Side::Ours, &ours, start..end);
+        let their_lines = side_lines(&group, Side::Theirs, &theirs, start..end);
+
+        match (our_lines, their_lines) {
+            (Some(lines), None) | (None, Some(lines)) => push_merged(&mut chunks, lines),
+            (Some(a), Some(b)) if a == b => push_merged(&mut chunks, a),
+            (a, b) => chunks.push(Chunk::Conflict(Conflict {
+                base: to_owned(&base[start..end]),
+                ours: to_owned(a.unwrap_or_default()),
+                theirs: to_owned(b.unwrap_or_default()),
+                resolution: None,
+            })),
+        }
+    }
+
+    push_merged(&mut chunks, &base[offset..]);
+
+    chunks
+}
+
+/// The merged text, or `None` while a conflict is unresolved.
+pub fn merged_text(chunks: &[Chunk], trailing_newline: bool) -> Option<String> {
+    let mut lines: Vec<&str> = vec![];
+
+    for chunk in chunks {
+        match chunk {
+            Chunk::Merged(merged) => lines.extend(merged.iter().map(String::as_str)),
+            Chunk::Conflict(conflict) => lines.extend(
+                conflict
+                    .lines(conflict.resolution?)
+                    .iter()
+                    .map(String::as_str),
+            ),
+        }
+    }
+
+    let mut text = lines.join("\n");
+
+    if trailing_newline {
+        text.push('\n');
+    }
+
+    Some(text)
+}
+
+pub fn conflicts(chunks: &[Chunk]) -> usize {
+    chunks
+        .iter()
+        .filter(|chunk| matches!(chunk, Chunk::Conflict(_)))
+        .count()
+}
+
+/// What one side has in place of `range` of the base, or `None` if it
+/// didn't change it.
+fn side_lines<'a>(
+    group: &[(Side, &Hunk)],
+    side: Side,
+    lines: &'a [&'a str],
+    range: Range<usize>,
+) -> Option<&'a [&'a str]> {
+    let mut hunks = group
+        .iter()
+        .filter(|(s, _)| *s == side)
+        .map(|(_, hunk)| hunk);
+
+    let first = hunks.next()?;
+    let last = hunks.last().unwrap_or(first);
+
+    // Lines of the base around the side's own changes are unchanged, so
+    // they're at the same distance from them.
+    let start = first.new.start - (first.old.start - range.start);
+    let end = last.new.end + (range.end - last.old.end);
+
+    Some(&lines[start..end])
+}
+
+fn push_merged(chunks: &mut Vec<Chunk>, lines: &[&str]) {
+    if lines.is_empty() {
+        return;
+    }
+
+    match chunks.last_mut() {
+        Some(Chunk::Merged(merged)) => merged.extend(to_owned(lines)),
+        _ => chunks.push(Chunk::Merged(to_owned(lines))),
+    }
+}
+
+fn to_owned(lines: &[&str]) -> Vec<String> {
+    lines.iter().map(|line| line.to_string()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn merged(base: &str, ours: &str, theirs: &str) -> Option<String> {
+        merged_text(&merge(base, ours, theirs), true)
+    }
+
+    fn lines(lines: &[&str]) -> Vec<String> {
+        to_owned(lines)
+    }
+
+    #[test]
+    fn takes_changes_from_one_side() {
+        let base = "a\nb\nc\n";
+
+        assert_eq!(
+            merged(base, "a\nB\nc\n", base).as_deref(),
+            Some("a\nB\nc\n")
+        );
+        assert_eq!(
+            merged(base, base, "a\nB\nc\n").as_deref(),
+            Some("a\nB\nc\n")
+        );
+        assert_eq!(merged(base, base, base).as_deref(), Some(base));
+    }
+
+    #[test]
+    fn takes_changes_from_both_sides() {
+        let merged = merged("a\nb\nc\nd\ne\n", "a\nB\nc\nd\ne\n", "a\nb\nc\nD\ne\n");
+
+        assert_eq!(merged.as_deref(), Some("a\nB\nc\nD\ne\n"));
+    }
+
+    #[test]
+    fn takes_the_same_change_once() {
+        let merged = merged("a\nb\nc\n", "a\nB\nc\n", "a\nB\nc\n");
+
+        assert_eq!(merged.as_deref(), Some("a\nB\nc\n"));
+    }
+
+    #[test]
+    fn overlapping_changes_conflict() {
+        let chunks = merge("a\nb\nc\n", "a\nX\nc\n", "a\nY\nc\n");
+
+        assert_eq!(
+            chunks,
+            vec![
+                Chunk::Merged(lines(&["a"])),
+                Chunk::Conflict(Conflict {
+                    base: lines(&["b"]),
+                    ours: lines(&["X"]),
+                    theirs: lines(&["Y"]),
+                    resolution: None,
+                }),
+                Chunk::Merged(lines(&["c"])),
+            ]
+        );
+        assert_eq!(conflicts(&chunks), 1);
+        assert_eq!(merged_text(&chunks, true), None);
+    }
+
+    #[test]
+    fn resolved_conflicts_take_the_chosen_side() {
+        let mut chunks = merge("a\nb\nc\n", "a\nX\nc\n", "a\nY\nc\n");
+
+        for (side, expected) in [
+            (Side::Ours, "a\nX\nc\n"),
+            (Side::Base, "a\nb\nc\n"),
+            (Side::Theirs, "a\nY\nc\n"),
+        ] {
+            let Chunk::Conflict(conflict) = &mut chunks[1] else {
+                panic!("Expected a conflict");
+            };
+            conflict.resolution = Some(side);
+
+            assert_eq!(merged_text(&chunks, true).as_deref(), Some(expected));
+        }
+    }
+
+    #[test]
+    fn adjacent_changes_conflict() {
+        let chunks = merge("a\nb\nc\nd\n", "a\nB\nc\nd\n", "a\nb\nC\nd\n");
+
+        assert_eq!(
+            chunks[1],
+            Chunk::Conflict(Conflict {
+                base: lines(&["b", "c"]),
+                ours: lines(&["B", "c"]),
+                theirs: lines(&["b", "C"]),
+                resolution: None,
+            })
+        );
+    }
+
+    #[test]
+    fn keeps_unchanged_lines_between_one_sides_changes() {
+        // Theirs replaces the lines around both of ours, so the conflict
+        // takes in the unchanged line between them.
+        let chunks = merge("a\nb\nc\nd\ne\n", "a\nB\nc\nD\ne\n", "a\nX\ne\n");
+
+        assert_eq!(
+            chunks,
+            vec![
+                Chunk::Merged(lines(&["a"])),
+                Chunk::Conflict(Conflict {
+                    base: lines(&["b", "c", "d"]),
+                    ours: lines(&["B", "c", "D"]),
+                    theirs: lines(&["X"]),
+                    resolution: None,
+                }),
+                Chunk::Merged(lines(&["e"])),
+            ]
+        );
+    }
+
+    #[test]
+    fn inserts_at_the_end() {
+        let base = "a\nb\n";
+
+        assert_eq!(
+            merged(base, "a\nb\nc\n", base).as_deref(),
+            Some("a\nb\nc\n")
+        );
+        assert_eq!(
+            merged(base, "a\nb\nc\n", "A\nb\n").as_deref(),
+            Some("A\nb\nc\n")
+        );
+        assert_eq!(
+            merged(base, "a\nb\nc\n", "a\nb\nc\n").as_deref(),
+            Some("a\nb\nc\n")
+        );
+
+        assert_eq!(
+            merge(base, "a\nb\nc\n", "a\nb\nd\n")[1],
+            Chunk::Conflict(Conflict {
+                base: vec![],
+                ours: lines(&["c"]),
+                theirs: lines(&["d"]),
+                resolution: None,
+            })
+        );
+    }
+
+    #[test]
+    fn side_lines_widen_to_the_group() {
+        let ours = ["a", "B", "c", "D", "e"];
+        let first = Hunk {
+            old: 1..2,
+            new: 1..2,
+        };
+        let last = Hunk {
+            old: 3..4,
+            new: 3..4,
+        };
+        let theirs = Hunk {
+            old: 0..5,
+            new: 0..1,
+        };
+        let group = [
+            (Side::Theirs, &theirs),
+            (Side::Ours, &first),
+            (Side::Ours, &last),
+        ];
+
+        assert_eq!(side_lines(&group, Side::Ours, &ours, 0..5), Some(&ours[..]));
+        assert_eq!(
+            side_lines(&group, Side::Ours, &ours, 1..4),
+            Some(&ours[1..4])
+        );
+        assert_eq!(side_lines(&group[1..], Side::Theirs, &ours, 1..4), None);
+    }
+}
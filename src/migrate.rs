@@ -0,0 +1,92 @@
+//! Upgrades documents written in older formats to [`FORMAT_VERSION`].
+//!
+//! Every document still decrypts in the version it was written in, so
+//! upgrading is never required. It happens when a document is saved, or in
+//! bulk with `cryptodoc migrate`. Either way the old file is copied aside
+//! first, so a bug in the new format can't take the only copy with it.
+
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+
+use crate::crypto::{
+    decrypt, encrypt, format_version, header_version, CryptoError, FORMAT_VERSION, HEADER_SIZE,
+};
+use crate::file::{replace_with, FileError};
+
+/// Where the copy of `path` from before migrating away from `version` goes,
+/// e.g. `notes.cryptodoc.v1.bak`.
+pub fn backup_path(path: &Path, version: u32) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".v{}.bak", version));
+
+    path.with_file_name(name)
+}
+
+/// The backups of `path` that exist, by the format version they're in,
+/// oldest first.
+pub fn backups(path: &Path) -> Vec<(u32, PathBuf)> {
+    (1..FORMAT_VERSION)
+        .map(|version| (version, backup_path(path, version)))
+        .filter(|(_, backup)| backup.exists())
+        .collect()
+}
+
+/// Copies the document at `path` aside if it's in an older format, before
+/// it's overwritten. An existing backup is kept rather than replaced, since
+/// it's the older of the two. Only the header is read, as this runs on
+/// every save.
+pub fn backup_if_outdated(path: &Path) -> io::Result<Option<PathBuf>> {
+    // Nothing to lose if there's no document there, or it isn't one.
+    let Ok(header) = read_header(path) else {
+        return Ok(None);
+    };
+
+    let version = match header_version(&header) {
+        Some(version) if version < FORMAT_VERSION => version,
+        _ => return Ok(None),
+    };
+
+    let backup = backup_path(path, version);
+
+    if !backup.exists() {
+        fs::copy(path, &backup)?;
+    }
+
+    Ok(Some(backup))
+}
+
+/// Like [`backup_if_outdated`], but on a blocking thread.
+pub async fn backup_in_background(path: PathBuf) -> Result<Option<PathBuf>, FileError> {
+    tokio::task::spawn_blocking(move || backup_if_outdated(&path))
+        .await
+        .map_err(|_| FileError::IOFailed(io::ErrorKind::Other))?
+        .map_err(|error| FileError::IOFailed(error.kind()))
+}
+
+/// Rewrites the document at `path` in the current format. Returns the
+/// backup of the old file, or `None` if it was already current.
+pub fn migrate_document(path: &Path, password: &str) -> Result<Option<PathBuf>, CryptoError> {
+    let encrypted = fs::read_to_string(path)?;
+
+    if format_version(encrypted.trim_end())? >= FORMAT_VERSION {
+        return Ok(None);
+    }
+
+    let plaintext = decrypt(encrypted.trim_end(), password)?;
+    let backup = backup_if_outdated(path)?;
+
+    let encrypted = encrypt(&plaintext, password);
+    replace_with(path, |writer| writer.write_all(encrypted.as_bytes()))?;
+
+    Ok(backup)
+}
+
+fn read_header(path: &Path) -> io::Result<String> {
+    let mut header = vec![];
+    File::open(path)?
+        .take(HEADER_SIZE as u64)
+        .read_to_end(&mut header)?;
+
+    Ok(String::from_utf8_lossy(&header).into_owned())
+}
@@ -0,0 +1,236 @@
+//! An encrypted copy of a folder of notes, such as an Obsidian vault, that's
+//! safe to put in any sync service.
+//!
+//! Every file in the notes folder, subfolders included, is encrypted into
+//! the same place in the mirror with `.cryptodoc` added to its name, so
+//! `daily/2025-06-01.md` becomes `daily/2025-06-01.md.cryptodoc`. The mirror
+//! can be decrypted back out the same way on another machine.
+//!
+//! A mirrored file gets the modification time of the file it came from, and
+//! only files whose times differ are written again. That keeps a sync client
+//! from uploading the whole mirror after every run, since encrypting the same
+//! note twice never gives the same bytes.
+//!
+//! The time each file was mirrored with is kept in [`State`], so a file
+//! changed on the receiving side since then isn't overwritten, even if the
+//! other side changed later too. Without a record, only a source newer than
+//! its target is written.
+
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use serde::{Deserialize, Serialize};
+
+use crate::crypto::{decrypt, encrypt, CryptoError};
+use crate::file::{replace_with, DOCUMENT_EXTENSION};
+
+/// Where [`State`] is kept, next to the app's config rather than in either
+/// folder, so it isn't synced or mirrored itself.
+pub const STATE_PATH: &str = "./mirror.json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// From the notes into the mirror.
+    Encrypt,
+    /// From the mirror back into the notes.
+    Decrypt,
+}
+
+/// A file to write, and the one it's written from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Change {
+    pub source: PathBuf,
+    pub target: PathBuf,
+    /// The target was changed since it was last mirrored, so writing it
+    /// would lose that change.
+    pub conflict: bool,
+}
+
+/// The modification time every target was given when it was last written,
+/// by its full path.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct State {
+    mirrored: BTreeMap<String, SystemTime>,
+}
+
+impl State {
+    /// The state from the last run, or none if it can't be read.
+    pub fn load() -> Self {
+        fs::read_to_string(STATE_PATH)
+            .ok()
+            .and_then(|state| serde_json::from_str(&state).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        replace_with(Path::new(STATE_PATH), |writer| {
+            serde_json::to_writer(&mut *writer, self).map_err(io::Error::from)
+        })
+    }
+
+    /// Notes that `target` was just written.
+    pub fn record(&mut self, target: &Path) {
+        if let (Some(key), Ok(modified)) = (key(target), modified(target)) {
+            self.mirrored.insert(key, modified);
+        }
+    }
+
+    fn mirrored(&self, target: &Path) -> Option<SystemTime> {
+        self.mirrored.get(&key(target)?).copied()
+    }
+}
+
+/// The files under `from` that are missing under `to` or differ from it,
+/// sorted by path. Symbolic links aren't followed, and `to` is skipped if
+/// it's inside `from`.
+pub fn changes(
+    from: &Path,
+    to: &Path,
+    direction: Direction,
+    state: &State,
+) -> io::Result<Vec<Change>> {
+    let mut changes = vec![];
+
+    for source in files_in(from, to)? {
+        let relative = source.strip_prefix(from).unwrap_or(&source);
+
+        let Some(target) = counterpart(relative, direction) else {
+            continue;
+        };
+        let target = to.join(target);
+
+        if let Some(conflict) = compare(&source, &target, state) {
+            changes.push(Change {
+                source,
+                target,
+                conflict,
+            });
+        }
+    }
+
+    Ok(changes)
+}
+
+/// Checks `password` against any file already in the mirror, so a typo
+/// doesn't leave the mirror encrypted under two passwords.
+pub fn check_password(mirror: &Path, password: &str) -> Result<(), CryptoError> {
+    let Some(existing) = files_in(mirror, mirror)?
+        .into_iter()
+        .find(|path| counterpart(path, Direction::Decrypt).is_some())
+    else {
+        return Ok(());
+    };
+
+    let encrypted = fs::read_to_string(existing)?;
+    decrypt(encrypted.trim_end(), password)?;
+
+    Ok(())
+}
+
+/// Encrypts or decrypts one file, replacing the target in one step so an
+/// interrupted run never leaves half a file behind.
+pub fn apply(change: &Change, direction: Direction, password: &str) -> Result<(), CryptoError> {
+    let contents = match direction {
+        Direction::Encrypt => encrypt(&fs::read(&change.source)?, password).into_bytes(),
+        Direction::Decrypt => {
+            let encrypted = fs::read_to_string(&change.source)?;
+            decrypt(encrypted.trim_end(), password)?
+        }
+    };
+
+    if let Some(parent) = change.target.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    replace_with(&change.target, |writer| {
+        writer.write_all(&contents)?;
+        // Flushed first, as writing after setting the time would change it.
+        writer.flush()?;
+
+        if let Ok(modified) = modified(&change.source) {
+            writer.get_ref().set_modified(modified)?;
+        }
+
+        Ok(())
+    })?;
+
+    Ok(())
+}
+
+/// Where the file at `relative` goes, or `None` if it isn't mirrored in that
+/// direction.
+fn counterpart(relative: &Path, direction: Direction) -> Option<PathBuf> {
+    match direction {
+        Direction::Encrypt => {
+            let mut name = OsString::from(relative);
+            name.push(".");
+            name.push(DOCUMENT_EXTENSION);
+
+            Some(PathBuf::from(name))
+        }
+        Direction::Decrypt => {
+            let is_document = relative
+                .extension()
+                .is_some_and(|extension| extension == DOCUMENT_EXTENSION);
+
+            is_document.then(|| relative.with_extension(""))
+        }
+    }
+}
+
+/// `None` if `target` is up to date with `source`, otherwise whether
+/// writing it would be a conflict.
+fn compare(source: &Path, target: &Path, state: &State) -> Option<bool> {
+    let (Ok(source), Ok(target_modified)) = (modified(source), modified(target)) else {
+        return Some(false);
+    };
+
+    if source == target_modified {
+        return None;
+    }
+
+    let untouched = match state.mirrored(target) {
+        Some(mirrored) => mirrored == target_modified,
+        None => source > target_modified,
+    };
+
+    Some(!untouched)
+}
+
+fn modified(path: &Path) -> io::Result<SystemTime> {
+    fs::metadata(path).and_then(|metadata| metadata.modified())
+}
+
+fn key(path: &Path) -> Option<String> {
+    fs::canonicalize(path)
+        .ok()
+        .map(|path| path.to_string_lossy().into_owned())
+}
+
+/// Every file under `folder`, leaving out `skip`.
+fn files_in(folder: &Path, skip: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut files = vec![];
+    let mut folders = vec![folder.to_path_buf()];
+
+    while let Some(folder) = folders.pop() {
+        for entry in fs::read_dir(&folder)? {
+            let entry = entry?;
+            let path = entry.path();
+            let file_type = entry.file_type()?;
+
+            if file_type.is_dir() && path != skip {
+                folders.push(path);
+            } else if file_type.is_file() {
+                files.push(path);
+            }
+        }
+    }
+
+    files.sort();
+
+    Ok(files)
+}
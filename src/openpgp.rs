@@ -0,0 +1,76 @@
+//! Symmetric (passphrase-only) OpenPGP messages, as written by `gpg -c`.
+//!
+//! These let documents be exchanged with people who only have GnuPG. Only
+//! passphrase encryption is supported; messages encrypted to a public key
+//! fail like a wrong password would.
+
+use std::io::Cursor;
+
+use pgp::crypto::sym::SymmetricKeyAlgorithm;
+use pgp::types::StringToKey;
+use pgp::{Deserializable, Message};
+
+use crate::crypto::CryptoError;
+
+/// The extension GnuPG gives the files it encrypts.
+pub const EXTENSION: &str = "gpg";
+
+/// Every extension an OpenPGP file commonly has.
+pub const EXTENSIONS: &[&str] = &["gpg", "pgp", "asc"];
+
+const ARMOR_HEADER: &str = "-----BEGIN PGP MESSAGE-----";
+
+/// Whether `contents` is an ASCII-armored OpenPGP message.
+pub fn is_armored(contents: &str) -> bool {
+    contents.trim_start().starts_with(ARMOR_HEADER)
+}
+
+/// Whether `bytes` looks like a binary OpenPGP message. Every packet header
+/// has the top bit set, which no text file starts with.
+pub fn is_binary(bytes: &[u8]) -> bool {
+    bytes.first().is_some_and(|byte| byte & 0x80 != 0)
+}
+
+/// Converts a binary OpenPGP message to ASCII armor, so it can be handled
+/// as text like any other document.
+pub fn armor(bytes: &[u8]) -> Result<String, CryptoError> {
+    let message = Message::from_bytes(Cursor::new(bytes)).map_err(|_| CryptoError::BadFormat)?;
+
+    message
+        .to_armored_string(Default::default())
+        .map_err(|_| CryptoError::CorruptData)
+}
+
+/// Decrypts an armored OpenPGP message with a passphrase.
+pub fn decrypt(armored: &str, password: &str) -> Result<Vec<u8>, CryptoError> {
+    let (message, _) =
+        Message::from_armor_single(Cursor::new(armored)).map_err(|_| CryptoError::BadFormat)?;
+
+    // The session key is checked as part of decrypting, so a wrong password
+    // fails here rather than producing garbage.
+    let message = message
+        .decrypt_with_password(|| password.to_string())
+        .map_err(|_| CryptoError::WrongPassword)?
+        .decompress()
+        .map_err(|_| CryptoError::CorruptData)?;
+
+    message
+        .get_content()
+        .map_err(|_| CryptoError::CorruptData)?
+        .ok_or(CryptoError::CorruptData)
+}
+
+/// Encrypts `data` into an armored message that `gpg --decrypt` can read.
+pub fn encrypt(data: &[u8], password: &str) -> String {
+    let mut rng = rand::thread_rng();
+
+    Message::new_literal_bytes("", data)
+        .encrypt_with_password(
+            &mut rng,
+            StringToKey::new_default(&mut rng),
+            SymmetricKeyAlgorithm::AES256,
+            || password.to_string(),
+        )
+        .and_then(|message| message.to_armored_string(Default::default()))
+        .expect("Failed to encrypt OpenPGP message")
+}
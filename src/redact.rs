@@ -0,0 +1,86 @@
+//! Text marked as redacted, which stays in the encrypted document but is
+//! blacked out in every export.
+//!
+//! The marks are part of the text, like a checklist's boxes, so they're
+//! encrypted, synced and backed up with it and still make sense in an app
+//! that doesn't know about them:
+//!
+//! ```text
+//! The code is [redact]1234[/redact].
+//! ```
+
+use std::borrow::Cow;
+use std::ops::Range;
+
+pub const START: &str = "[redact]";
+pub const END: &str = "[/redact]";
+
+/// What an export shows instead of redacted text. It's the same whatever
+/// the length, so the export doesn't give that away either.
+pub const REPLACEMENT: &str = "██";
+
+/// The redacted parts of `text`, each including its marks. A mark without
+/// an end redacts the rest of the text, so a forgotten end mark errs on the
+/// side of hiding too much.
+pub fn ranges(text: &str) -> Vec<Range<usize>> {
+    let mut ranges = vec![];
+    let mut offset = 0;
+
+    while let Some(start) = text[offset..].find(START) {
+        let start = offset + start;
+        let end = text[start + START.len()..]
+            .find(END)
+            .map_or(text.len(), |end| start + START.len() + end + END.len());
+
+        ranges.push(start..end);
+        offset = end;
+    }
+
+    ranges
+}
+
+/// `text` with each redacted part replaced by [`REPLACEMENT`].
+pub fn redact(text: &str) -> Cow<'_, str> {
+    replace(text, |_| REPLACEMENT.into())
+}
+
+/// `text` with each redacted part blacked out character by character, so
+/// it lines up with the original. For showing the document, not exporting.
+pub fn black_out(text: &str) -> Cow<'_, str> {
+    replace(text, |redacted| {
+        let inner = redacted.strip_prefix(START).unwrap_or(redacted);
+        let inner = inner.strip_suffix(END).unwrap_or(inner);
+
+        inner
+            .chars()
+            .map(|c| if c == '\n' { '\n' } else { '█' })
+            .collect::<String>()
+            .into()
+    })
+}
+
+/// Marks `text` as redacted.
+pub fn mark(text: &str) -> String {
+    format!("{}{}{}", START, text, END)
+}
+
+fn replace<'a>(text: &'a str, with: impl Fn(&str) -> Cow<'_, str>) -> Cow<'a, str> {
+    let ranges = ranges(text);
+
+    if ranges.is_empty() {
+        return Cow::Borrowed(text);
+    }
+
+    let mut replaced = String::with_capacity(text.len());
+    let mut offset = 0;
+
+    for range in ranges {
+        replaced.push_str(&text[offset..range.start]);
+        replaced.push_str(&with(&text[range.clone()]));
+        offset = range.end;
+    }
+
+    replaced.push_str(&text[offset..]);
+
+    Cow::Owned(replaced)
+}
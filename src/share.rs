@@ -0,0 +1,46 @@
+//! One-time share bundles: a document encrypted with a fresh random key
+//! instead of the owner's password.
+//!
+//! The bundle can go over email or chat like any armored document, and the
+//! key is handed over some other way, read out or shown as a QR code. It
+//! opens like any other document, with the key as its password, and gives
+//! away nothing about the owner's own password.
+
+use rand::seq::SliceRandom;
+
+use crate::Document;
+
+/// Lowercase letters and digits, leaving out the ones that are easily
+/// mistaken for each other when read out or typed: `i`, `l`, `o`, `0`, `1`.
+const KEY_ALPHABET: &[u8] = b"abcdefghjkmnpqrstuvwxyz23456789";
+
+/// 20 characters from [`KEY_ALPHABET`], about 99 bits.
+const KEY_GROUPS: usize = 4;
+const GROUP_LENGTH: usize = 5;
+
+/// A new random key, in dash-separated groups like `k7qfm-2mxp9-zrtwb-4nh6j`.
+pub fn generate_key() -> String {
+    let mut rng = rand::thread_rng();
+
+    (0..KEY_GROUPS)
+        .map(|_| {
+            (0..GROUP_LENGTH)
+                .map(|_| {
+                    *KEY_ALPHABET
+                        .choose(&mut rng)
+                        .expect("the alphabet isn't empty") as char
+                })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Encrypts `document` with a new key. Returns the key and the armored
+/// bundle.
+pub fn seal(document: &Document) -> (String, String) {
+    let key = generate_key();
+    let bundle = document.encrypt_armored(&key);
+
+    (key, bundle)
+}
@@ -0,0 +1,159 @@
+//! Where a vault's encrypted documents are kept.
+//!
+//! A [`StorageBackend`] only ever sees encrypted blobs, addressed by file
+//! name, so a remote backend learns nothing about the documents beyond
+//! their names and sizes. [`LocalFolder`] is the default; others, like
+//! WebDAV or S3, are added as a new [`Backend`] and chosen per vault.
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::file::DOCUMENT_EXTENSION;
+
+#[derive(Debug, Error)]
+pub enum StorageError {
+    #[error("{0} doesn't exist")]
+    NotFound(String),
+    /// The name would reach outside the vault, or isn't a document.
+    #[error("{0} isn't a valid document name")]
+    InvalidName(String),
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
+
+/// One encrypted document in a backend.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlobInfo {
+    /// The file name, extension included.
+    pub name: String,
+    pub size: u64,
+    pub modified: Option<SystemTime>,
+}
+
+/// Stores encrypted documents. Every call may block on the network, so call
+/// these from a blocking thread in async code.
+pub trait StorageBackend: Send + Sync {
+    /// Every document, sorted by name.
+    fn list(&self) -> Result<Vec<BlobInfo>, StorageError>;
+
+    fn get(&self, name: &str) -> Result<Vec<u8>, StorageError>;
+
+    /// Writes `contents` to `name`, replacing whatever was there. A reader
+    /// never sees a half-written document.
+    fn put(&self, name: &str, contents: &[u8]) -> Result<(), StorageError>;
+
+    fn delete(&self, name: &str) -> Result<(), StorageError>;
+}
+
+/// The kinds of backend a vault can use.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Backend {
+    #[default]
+    LocalFolder,
+}
+
+impl Backend {
+    pub const ALL: &'static [Self] = &[Self::LocalFolder];
+
+    /// Opens the backend for the vault at `location`.
+    pub fn open(self, location: &Path) -> Box<dyn StorageBackend> {
+        match self {
+            Backend::LocalFolder => Box::new(LocalFolder::new(location)),
+        }
+    }
+}
+
+impl fmt::Display for Backend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Backend::LocalFolder => "Local folder",
+        }
+        .fmt(f)
+    }
+}
+
+/// Documents as `.cryptodoc` files in a folder on this machine.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LocalFolder {
+    folder: PathBuf,
+}
+
+impl LocalFolder {
+    pub fn new(folder: impl Into<PathBuf>) -> Self {
+        Self {
+            folder: folder.into(),
+        }
+    }
+
+    fn path_of(&self, name: &str) -> Result<PathBuf, StorageError> {
+        let path = Path::new(name);
+
+        let is_plain_name = path.file_name().is_some_and(|file_name| file_name == name);
+        let is_document = path
+            .extension()
+            .is_some_and(|extension| extension == DOCUMENT_EXTENSION);
+
+        if !is_plain_name || !is_document {
+            return Err(StorageError::InvalidName(name.to_string()));
+        }
+
+        Ok(self.folder.join(name))
+    }
+}
+
+impl StorageBackend for LocalFolder {
+    fn list(&self) -> Result<Vec<BlobInfo>, StorageError> {
+        let mut blobs = vec![];
+
+        for entry in fs::read_dir(&self.folder)? {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let metadata = entry.metadata()?;
+
+            if metadata.is_file() && self.path_of(&name).is_ok() {
+                blobs.push(BlobInfo {
+                    name,
+                    size: metadata.len(),
+                    modified: metadata.modified().ok(),
+                });
+            }
+        }
+
+        blobs.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(blobs)
+    }
+
+    fn get(&self, name: &str) -> Result<Vec<u8>, StorageError> {
+        fs::read(self.path_of(name)?).map_err(|error| not_found(error, name))
+    }
+
+    fn put(&self, name: &str, contents: &[u8]) -> Result<(), StorageError> {
+        let path = self.path_of(name)?;
+
+        // Write next to the document and rename over it, like a migration.
+        let temporary = path.with_extension("put");
+        fs::write(&temporary, contents)?;
+        fs::rename(&temporary, &path)?;
+
+        Ok(())
+    }
+
+    fn delete(&self, name: &str) -> Result<(), StorageError> {
+        fs::remove_file(self.path_of(name)?).map_err(|error| not_found(error, name))
+    }
+}
+
+fn not_found(error: io::Error, name: &str) -> StorageError {
+    match error.kind() {
+        io::ErrorKind::NotFound => StorageError::NotFound(name.to_string()),
+        _ => StorageError::Io(error),
+    }
+}
@@ -0,0 +1,53 @@
+//! Documents that are a table of secrets rather than free text.
+//!
+//! A table is stored as an ordinary document whose text is a [`HEADER`] line
+//! followed by the rows as JSON, so it's encrypted, armored, synced and
+//! backed up exactly like any other document, and still readable as text in
+//! an app that doesn't know about tables.
+
+use serde::{Deserialize, Serialize};
+
+/// The first line of every table document.
+pub const HEADER: &str = "#!cryptodoc-table v1";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SecretRow {
+    pub label: String,
+    pub username: String,
+    pub secret: String,
+    pub notes: String,
+    /// A two-factor seed, as a Base32 secret or `otpauth://` link.
+    pub totp: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SecretTable {
+    pub rows: Vec<SecretRow>,
+}
+
+impl SecretTable {
+    /// Reads a table from the text of a document, or returns `None` if the
+    /// document is free text.
+    pub fn parse(text: &str) -> Option<Self> {
+        let (header, rows) = text.split_once('\n').unwrap_or((text, ""));
+
+        if header.trim_end() != HEADER {
+            return None;
+        }
+
+        let rows = match rows.trim() {
+            "" => vec![],
+            rows => serde_json::from_str(rows).ok()?,
+        };
+
+        Some(Self { rows })
+    }
+
+    /// The text of the document holding the table.
+    pub fn to_text(&self) -> String {
+        let rows = serde_json::to_string_pretty(&self.rows).expect("Failed to serialize table");
+
+        format!("{}\n{}\n", HEADER, rows)
+    }
+}
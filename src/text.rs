@@ -0,0 +1,114 @@
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineEnding {
+    Lf,
+    CrLf,
+}
+
+impl LineEnding {
+    pub const ALL: &'static [Self] = &[Self::Lf, Self::CrLf];
+
+    pub fn native() -> Self {
+        if cfg!(windows) {
+            LineEnding::CrLf
+        } else {
+            LineEnding::Lf
+        }
+    }
+
+    /// Picks whichever ending the majority of lines use, so a file with a
+    /// single stray line break keeps the style of the rest.
+    pub fn detect(text: &str) -> Self {
+        let crlf = text.matches("\r\n").count();
+        let lf = text.matches('\n').count() - crlf;
+
+        match (crlf, lf) {
+            (0, 0) => LineEnding::native(),
+            (crlf, lf) if crlf > lf => LineEnding::CrLf,
+            _ => LineEnding::Lf,
+        }
+    }
+
+    /// Rewrites every line break in `text` to this ending.
+    pub fn apply(self, text: &str) -> String {
+        let text = text.replace("\r\n", "\n");
+
+        match self {
+            LineEnding::Lf => text,
+            LineEnding::CrLf => text.replace('\n', "\r\n"),
+        }
+    }
+}
+
+impl fmt::Display for LineEnding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LineEnding::Lf => "LF",
+            LineEnding::CrLf => "CRLF",
+        }
+        .fmt(f)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    Utf8,
+    Utf16Le,
+    Utf16Be,
+    Latin1,
+}
+
+impl fmt::Display for Encoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Encoding::Utf8 => "UTF-8",
+            Encoding::Utf16Le => "UTF-16 LE",
+            Encoding::Utf16Be => "UTF-16 BE",
+            Encoding::Latin1 => "ISO-8859-1",
+        }
+        .fmt(f)
+    }
+}
+
+/// Decodes plaintext that may not be UTF-8. Anything that isn't valid UTF-8
+/// and has no UTF-16 byte order mark is read as Latin-1, which maps every
+/// byte to a character and so can never fail.
+pub fn decode(bytes: Vec<u8>) -> (String, Encoding) {
+    match bytes.as_slice() {
+        [0xEF, 0xBB, 0xBF, rest @ ..] => {
+            if std::str::from_utf8(rest).is_ok() {
+                let mut bytes = bytes;
+                bytes.drain(..3);
+
+                let text = String::from_utf8(bytes).expect("Checked above");
+                return (text, Encoding::Utf8);
+            }
+        }
+        [0xFF, 0xFE, rest @ ..] => {
+            return (decode_utf16(rest, u16::from_le_bytes), Encoding::Utf16Le)
+        }
+        [0xFE, 0xFF, rest @ ..] => {
+            return (decode_utf16(rest, u16::from_be_bytes), Encoding::Utf16Be)
+        }
+        _ => {}
+    }
+
+    match String::from_utf8(bytes) {
+        Ok(text) => (text, Encoding::Utf8),
+        Err(error) => (
+            error.into_bytes().into_iter().map(char::from).collect(),
+            Encoding::Latin1,
+        ),
+    }
+}
+
+fn decode_utf16(bytes: &[u8], from_bytes: fn([u8; 2]) -> u16) -> String {
+    let units = bytes
+        .chunks_exact(2)
+        .map(|pair| from_bytes([pair[0], pair[1]]));
+
+    char::decode_utf16(units)
+        .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+        .collect()
+}
@@ -0,0 +1,173 @@
+//! Time-based one-time passwords (RFC 6238), the codes shown by
+//! authenticator apps.
+//!
+//! A seed is either the Base32 secret a site shows when setting up two-factor
+//! authentication, or the `otpauth://totp/...` link in its QR code, which
+//! can also change the number of digits, the period and the hash.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crypto::digest::Digest;
+use crypto::hmac::Hmac;
+use crypto::mac::Mac;
+use crypto::sha1::Sha1;
+use crypto::sha2::{Sha256, Sha512};
+
+const URI_PREFIX: &str = "otpauth://totp/";
+
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Algorithm {
+    Sha1,
+    Sha256,
+    Sha512,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Totp {
+    secret: Vec<u8>,
+    pub digits: u32,
+    /// How long each code lasts, in seconds.
+    pub period: u64,
+    pub algorithm: Algorithm,
+}
+
+impl Totp {
+    /// Reads a Base32 secret or an `otpauth://totp/` link. Returns `None` if
+    /// `seed` is neither.
+    pub fn parse(seed: &str) -> Option<Self> {
+        let seed = seed.trim();
+
+        let Some(uri) = seed.strip_prefix(URI_PREFIX) else {
+            return Self::from_secret(seed);
+        };
+
+        let (_, query) = uri.split_once('?')?;
+        let mut totp = None;
+        let (mut digits, mut period, mut algorithm) = (6, 30, Algorithm::Sha1);
+
+        for (key, value) in query.split('&').filter_map(|pair| pair.split_once('=')) {
+            match key {
+                "secret" => totp = Self::from_secret(value),
+                "digits" => {
+                    digits = value
+                        .parse()
+                        .ok()
+                        .filter(|digits| (6..=8).contains(digits))?
+                }
+                "period" => period = value.parse().ok().filter(|period| *period > 0)?,
+                "algorithm" => {
+                    algorithm = match value.to_ascii_uppercase().as_str() {
+                        "SHA1" => Algorithm::Sha1,
+                        "SHA256" => Algorithm::Sha256,
+                        "SHA512" => Algorithm::Sha512,
+                        _ => return None,
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        totp.map(|totp| Self {
+            digits,
+            period,
+            algorithm,
+            ..totp
+        })
+    }
+
+    fn from_secret(secret: &str) -> Option<Self> {
+        let secret = decode_base32(secret)?;
+
+        if secret.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            secret,
+            digits: 6,
+            period: 30,
+            algorithm: Algorithm::Sha1,
+        })
+    }
+
+    /// The code for the given Unix time.
+    pub fn code_at(&self, seconds: u64) -> String {
+        let counter = (seconds / self.period).to_be_bytes();
+
+        let hash = match self.algorithm {
+            Algorithm::Sha1 => hmac(Sha1::new(), &self.secret, &counter),
+            Algorithm::Sha256 => hmac(Sha256::new(), &self.secret, &counter),
+            Algorithm::Sha512 => hmac(Sha512::new(), &self.secret, &counter),
+        };
+
+        // Dynamic truncation, from RFC 4226.
+        let offset = (hash[hash.len() - 1] & 0x0f) as usize;
+        let value = u32::from_be_bytes([
+            hash[offset] & 0x7f,
+            hash[offset + 1],
+            hash[offset + 2],
+            hash[offset + 3],
+        ]);
+
+        format!(
+            "{:0width$}",
+            value % 10u32.pow(self.digits),
+            width = self.digits as usize
+        )
+    }
+
+    /// The current code, and the seconds until it changes.
+    pub fn now(&self) -> (String, u64) {
+        let seconds = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs());
+
+        (self.code_at(seconds), self.period - seconds % self.period)
+    }
+}
+
+/// Every `otpauth://totp/` link in `text`, with the text before it on its
+/// line as a label.
+pub fn find_seeds(text: &str) -> Vec<(String, Totp)> {
+    text.lines()
+        .filter_map(|line| {
+            let start = line.find(URI_PREFIX)?;
+            let uri = line[start..].split_whitespace().next()?;
+            let label = line[..start].trim().trim_end_matches(':').trim();
+
+            Some((label.to_string(), Totp::parse(uri)?))
+        })
+        .collect()
+}
+
+fn hmac<D: Digest>(digest: D, key: &[u8], message: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::new(digest, key);
+    mac.input(message);
+
+    mac.result().code().to_vec()
+}
+
+/// Decodes RFC 4648 Base32, ignoring case, spaces and padding, as sites
+/// often show secrets in lowercase groups of four.
+fn decode_base32(encoded: &str) -> Option<Vec<u8>> {
+    let mut bytes = vec![];
+    let (mut buffer, mut bits) = (0u32, 0);
+
+    for c in encoded.chars().filter(|c| !c.is_whitespace() && *c != '=') {
+        let letter = u8::try_from(c).ok()?.to_ascii_uppercase();
+        let value = BASE32_ALPHABET.iter().position(|&known| known == letter)?;
+
+        buffer = (buffer << 5) | value as u32;
+        bits += 5;
+
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+
+    Some(bytes)
+}
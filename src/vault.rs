@@ -0,0 +1,75 @@
+use std::path::{Path, PathBuf};
+
+use crate::document::{Document, DocumentError};
+use crate::file::{
+    list_documents, sanitize_document_name, DocumentInfo, FileError, DOCUMENT_EXTENSION,
+};
+use crate::storage::{Backend, StorageBackend};
+
+/// A folder of `.cryptodoc` files, addressed by document name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Vault {
+    folder: PathBuf,
+}
+
+impl Vault {
+    pub fn new(folder: impl Into<PathBuf>) -> Self {
+        Self {
+            folder: folder.into(),
+        }
+    }
+
+    pub fn folder(&self) -> &Path {
+        &self.folder
+    }
+
+    /// The file a document called `name` is stored in.
+    pub fn path_for(&self, name: &str) -> PathBuf {
+        // Appended rather than set, so a dot in the name, as in
+        // `example.com`, isn't taken for the start of an extension.
+        self.folder.join(format!("{}.{}", name, DOCUMENT_EXTENSION))
+    }
+
+    /// A path for a new document called `name` that won't replace an
+    /// existing one. The name is made safe for a file name first, and gets a
+    /// number, like `notes (2)`, if it's taken.
+    pub fn unused_path_for(&self, name: &str) -> PathBuf {
+        let name = match sanitize_document_name(name) {
+            name if name.is_empty() => "Untitled".to_string(),
+            name => name,
+        };
+
+        let mut path = self.path_for(&name);
+        let mut number = 2;
+
+        while path.exists() {
+            path = self.path_for(&format!("{} ({})", name, number));
+            number += 1;
+        }
+
+        path
+    }
+
+    /// The vault's documents as stored by `backend`.
+    pub fn storage(&self, backend: Backend) -> Box<dyn StorageBackend> {
+        backend.open(&self.folder)
+    }
+
+    /// Every document in the vault, sorted by path.
+    pub async fn documents(&self) -> Result<Vec<DocumentInfo>, FileError> {
+        list_documents(self.folder.clone()).await
+    }
+
+    pub async fn open(&self, name: &str, password: &str) -> Result<Document, DocumentError> {
+        Document::open(self.path_for(name), password).await
+    }
+
+    pub async fn save(
+        &self,
+        name: &str,
+        document: &Document,
+        password: &str,
+    ) -> Result<PathBuf, DocumentError> {
+        document.save(self.path_for(name), password).await
+    }
+}